@@ -0,0 +1,191 @@
+//! [`Archive`](crate::Archive) implementation for binary heaps.
+
+use crate::vec::{ArchivedVec, VecResolver};
+use core::{fmt, slice};
+
+/// An archived `BinaryHeap`. This is a wrapper around an archived vec of the heap's backing
+/// storage.
+///
+/// The elements are archived in the same order as the backing vector of the heap. As long as the
+/// archived elements are ordered the same way as the unarchived elements, the archived elements
+/// still satisfy the heap invariant and the greatest element can be read with
+/// [`peek`](ArchivedBinaryHeap::peek). Validation checks the heap invariant with the ordering of
+/// the archived elements.
+#[repr(transparent)]
+pub struct ArchivedBinaryHeap<T>(pub(crate) ArchivedVec<T>);
+
+impl<T> ArchivedBinaryHeap<T> {
+    /// Returns the greatest item in the binary heap, or `None` if it is empty.
+    ///
+    /// This returns the first element of the heap without comparing it to the others. It is only
+    /// the greatest item if the elements satisfy the heap invariant, which is checked during
+    /// validation but not by [`archived_root`](crate::archived_root).
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.0.as_slice().first()
+    }
+
+    /// Returns the number of items in the binary heap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the binary heap contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets the underlying elements of the binary heap as a slice, in heap order.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.0.as_slice()
+    }
+
+    /// Gets an iterator over the elements of the binary heap, in heap order.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.0.as_slice().iter()
+    }
+
+    /// Resolves a binary heap from its length.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a binary heap
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: BinaryHeapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.0);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.0, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        Serialize,
+    };
+
+    impl<T> ArchivedBinaryHeap<T> {
+        /// Serializes the backing storage of a binary heap from an iterator.
+        ///
+        /// The iterator should return the elements in the order of the heap's backing vector so
+        /// that the archived elements satisfy the heap invariant.
+        #[inline]
+        pub fn serialize_from_iter<'a, U, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<BinaryHeapResolver, S::Error>
+        where
+            U: 'a + Serialize<S, Archived = T>,
+            S: ScratchSpace + Serializer + ?Sized,
+            I: ExactSizeIterator<Item = &'a U>,
+        {
+            Ok(BinaryHeapResolver(ArchivedVec::serialize_from_iter::<
+                U,
+                _,
+                _,
+                _,
+            >(iter, serializer)?))
+        }
+    }
+};
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedBinaryHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedBinaryHeap<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The resolver for archived binary heaps.
+pub struct BinaryHeapResolver(VecResolver);
+
+/// Errors that can occur while checking an archived binary heap.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum BinaryHeapError<E> {
+    /// An error occurred while checking the elements of the binary heap
+    CheckBytesError(E),
+    /// An element was greater than its parent in the heap
+    HeapOrder {
+        /// The index of the out-of-order element
+        index: usize,
+    },
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for BinaryHeapError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryHeapError::CheckBytesError(e) => write!(f, "elements check error: {}", e),
+            BinaryHeapError::HeapOrder { index } => write!(
+                f,
+                "heap order error: element at index {} is greater than its parent",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for BinaryHeapError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                BinaryHeapError::CheckBytesError(e) => Some(e as &dyn Error),
+                BinaryHeapError::HeapOrder { .. } => None,
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::CheckBytes;
+    use core::ptr;
+
+    impl<T: Ord, C: ?Sized> CheckBytes<C> for ArchivedBinaryHeap<T>
+    where
+        ArchivedVec<T>: CheckBytes<C>,
+    {
+        type Error = BinaryHeapError<<ArchivedVec<T> as CheckBytes<C>>::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let elements = ArchivedVec::<T>::check_bytes(ptr::addr_of!((*value).0), context)
+                .map_err(BinaryHeapError::CheckBytesError)?;
+
+            // `peek` returns the first element, so every element must be at most its parent
+            for index in 1..elements.len() {
+                if elements[index] > elements[(index - 1) / 2] {
+                    return Err(BinaryHeapError::HeapOrder { index });
+                }
+            }
+
+            Ok(&*value)
+        }
+    }
+};
@@ -0,0 +1,168 @@
+//! An archived fixed-size bit set.
+//!
+//! Use the [`AsBitSet`](crate::with::AsBitSet) wrapper to archive a `[u64; N]` or `u128` as an
+//! [`ArchivedBitSet`].
+
+use crate::Archived;
+use core::{fmt, iter::FusedIterator, pin::Pin};
+
+/// An archived fixed-size bit set backed by `N` 64-bit words.
+///
+/// Bit `i` is stored in word `i / 64` at position `i % 64`, so a bit set archived from a `u128`
+/// has its low 64 bits in the first word.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct ArchivedBitSet<const N: usize> {
+    words: [Archived<u64>; N],
+}
+
+impl<const N: usize> ArchivedBitSet<N> {
+    /// The number of bits in the bit set.
+    pub const CAPACITY: usize = N * 64;
+
+    /// Returns the number of bits in the bit set.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// Returns whether bit `index` is set.
+    ///
+    /// Bits past the capacity of the bit set are never set.
+    #[inline]
+    pub fn get(&self, index: usize) -> bool {
+        if index < Self::CAPACITY {
+            from_archived!(self.words[index / 64]) & (1 << (index % 64)) != 0
+        } else {
+            false
+        }
+    }
+
+    /// Sets bit `index` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than or equal to the capacity of the bit set.
+    #[inline]
+    pub fn set(self: Pin<&mut Self>, index: usize, value: bool) {
+        assert!(
+            index < Self::CAPACITY,
+            "bit index {} out of range for bit set of capacity {}",
+            index,
+            Self::CAPACITY,
+        );
+
+        let word = &mut self.get_mut().words[index / 64];
+        let mask = 1 << (index % 64);
+        let bits = from_archived!(*word);
+        *word = to_archived!(if value { bits | mask } else { bits & !mask });
+    }
+
+    /// Returns the number of set bits.
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| from_archived!(*word).count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if no bits are set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| from_archived!(*word) == 0)
+    }
+
+    /// Gets an iterator over the indices of the set bits, in increasing order.
+    #[inline]
+    pub fn iter_ones(&self) -> IterOnes<'_> {
+        IterOnes {
+            words: &self.words,
+            base: 0,
+            current: self.words.first().map_or(0, |word| from_archived!(*word)),
+        }
+    }
+
+    /// Returns the words backing the bit set.
+    #[inline]
+    pub fn as_words(&self) -> &[Archived<u64>; N] {
+        &self.words
+    }
+
+    /// Returns the words backing the bit set as native integers.
+    #[inline]
+    pub fn to_words(&self) -> [u64; N] {
+        let mut result = [0; N];
+        for (out, word) in result.iter_mut().zip(self.words.iter()) {
+            *out = from_archived!(*word);
+        }
+        result
+    }
+
+    /// Emplaces an archived bit set from its words.
+    ///
+    /// # Safety
+    ///
+    /// `out` must point to memory suitable for holding an `ArchivedBitSet<N>`.
+    #[inline]
+    pub unsafe fn emplace(words: &[u64; N], out: *mut Self) {
+        let out = out.cast::<Archived<u64>>();
+        for (i, word) in words.iter().enumerate() {
+            out.add(i).write(to_archived!(*word));
+        }
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArchivedBitSet<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter_ones()).finish()
+    }
+}
+
+/// An iterator over the indices of the set bits of an [`ArchivedBitSet`].
+///
+/// This `struct` is created by the [`ArchivedBitSet::iter_ones`] function.
+pub struct IterOnes<'a> {
+    words: &'a [Archived<u64>],
+    base: usize,
+    current: u64,
+}
+
+impl Iterator for IterOnes<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            if self.words.len() <= 1 {
+                self.words = &[];
+                return None;
+            }
+            self.words = &self.words[1..];
+            self.base += 64;
+            self.current = from_archived!(self.words[0]);
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.base + bit)
+    }
+}
+
+impl FusedIterator for IterOnes<'_> {}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::CheckBytes;
+    use core::convert::Infallible;
+
+    impl<C: ?Sized, const N: usize> CheckBytes<C> for ArchivedBitSet<N> {
+        type Error = Infallible;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+            Ok(&*value)
+        }
+    }
+};
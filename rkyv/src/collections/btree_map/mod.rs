@@ -236,6 +236,43 @@ impl<K, V> ArchivedBTreeMap<K, V> {
         }
     }
 
+    /// Descends to the leaf node that may contain the supplied key.
+    ///
+    /// Also returns whether there are any leaf nodes after the returned one.
+    fn find_leaf<Q: Ord + ?Sized>(&self, k: &Q) -> Option<(&LeafNode<K, V>, bool)>
+    where
+        K: Borrow<Q> + Ord,
+    {
+        let mut current = self.root()?;
+        let mut has_next = false;
+        loop {
+            match current {
+                ClassifiedNode::Inner(node) => {
+                    // Binary search for the next node layer
+                    let next = match node
+                        .tail
+                        .binary_search_by(|probe| probe.key.borrow().cmp(k))
+                    {
+                        Ok(i) => {
+                            has_next |= i + 1 < node.tail.len();
+                            unsafe { &*node.tail[i].ptr.as_ptr() }
+                        }
+                        Err(0) => {
+                            has_next |= !node.tail.is_empty();
+                            unsafe { &*node.header.ptr.as_ptr() }
+                        }
+                        Err(i) => {
+                            has_next |= i < node.tail.len();
+                            unsafe { &*node.tail[i - 1].ptr.as_ptr() }
+                        }
+                    };
+                    current = next.classify();
+                }
+                ClassifiedNode::Leaf(node) => break Some((node, has_next)),
+            }
+        }
+    }
+
     /// Returns `true` if the map contains a value for the specified key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering on the borrowed
@@ -268,43 +305,65 @@ impl<K, V> ArchivedBTreeMap<K, V> {
     where
         K: Borrow<Q> + Ord,
     {
-        if let Some(mut current) = self.root() {
-            loop {
-                match current {
-                    ClassifiedNode::Inner(node) => {
-                        // Binary search for the next node layer
-                        let next = match node
-                            .tail
-                            .binary_search_by(|probe| probe.key.borrow().cmp(k))
-                        {
-                            Ok(i) => unsafe { &*node.tail[i].ptr.as_ptr() },
-                            Err(i) => {
-                                if i == 0 {
-                                    unsafe { &*node.header.ptr.as_ptr() }
-                                } else {
-                                    unsafe { &*node.tail[i - 1].ptr.as_ptr() }
-                                }
-                            }
-                        };
-                        current = next.classify();
-                    }
-                    ClassifiedNode::Leaf(node) => {
-                        // Binary search for the value
-                        if let Ok(i) = node
-                            .tail
-                            .binary_search_by(|probe| probe.key.borrow().cmp(k))
-                        {
-                            let entry = &node.tail[i];
-                            break Some((&entry.key, &entry.value));
-                        } else {
-                            break None;
-                        }
-                    }
-                }
+        let (node, _) = self.find_leaf(k)?;
+        // Binary search for the value
+        let i = node
+            .tail
+            .binary_search_by(|probe| probe.key.borrow().cmp(k))
+            .ok()?;
+        let entry = &node.tail[i];
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns the key-value pair with the smallest key that is greater than or equal to the
+    /// supplied key, or `None` if there is no such key.
+    ///
+    /// The supplied key may be any borrowed form of the map’s key type, but the ordering on the
+    /// borrowed form must match the ordering on the key type.
+    pub fn get_next<Q: Ord + ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q> + Ord,
+    {
+        let (node, has_next) = self.find_leaf(k)?;
+        let entry = match node
+            .tail
+            .binary_search_by(|probe| probe.key.borrow().cmp(k))
+        {
+            Ok(i) => &node.tail[i],
+            Err(i) if i < node.tail.len() => &node.tail[i],
+            // Every key in this leaf is less than the supplied key, so the next key is the first
+            // one in the following leaf
+            Err(_) if has_next => {
+                let next = unsafe { &*node.header.ptr.as_ptr() };
+                &next.classify_leaf::<K, V>().tail[0]
             }
-        } else {
-            None
-        }
+            Err(_) => return None,
+        };
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns the key-value pair with the largest key that is less than or equal to the supplied
+    /// key, or `None` if there is no such key.
+    ///
+    /// The supplied key may be any borrowed form of the map’s key type, but the ordering on the
+    /// borrowed form must match the ordering on the key type.
+    pub fn get_prev<Q: Ord + ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q> + Ord,
+    {
+        let (node, _) = self.find_leaf(k)?;
+        // The keys in inner nodes are the first keys of their subtrees, so the leaf we find only
+        // starts with a key greater than the supplied key if it's the first leaf in the map.
+        let i = match node
+            .tail
+            .binary_search_by(|probe| probe.key.borrow().cmp(k))
+        {
+            Ok(i) => i,
+            Err(0) => return None,
+            Err(i) => i - 1,
+        };
+        let entry = &node.tail[i];
+        Some((&entry.key, &entry.value))
     }
 
     /// Returns `true` if the map contains no elements.
@@ -1,10 +1,11 @@
 //! A helper type that archives index data for hashed collections using
 //! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
 
-use crate::{Archive, Archived, RelPtr};
+use crate::{collections::util::prefetch, Archive, Archived, RelPtr};
 use core::{
     fmt,
     hash::{Hash, Hasher},
+    marker::PhantomData,
     slice,
 };
 
@@ -14,40 +15,80 @@ pub use seahash::SeaHasher as HashBuilder;
 #[cfg(feature = "validation")]
 pub mod validation;
 
+/// A deterministic hasher that can be used to build and access archived hash indexes.
+///
+/// Archived hash indexes must hash keys identically when they are built and every time they are
+/// accessed afterward, possibly on a different machine. Seeds for displaced keys are hashed into a
+/// fresh hasher, so every hasher returned from [`make_hasher`](ArchiveHasher::make_hasher) must
+/// start in the same state.
+///
+/// This is implemented for [`HashBuilder`], which is the default hasher for all archived hash
+/// indexes.
+pub trait ArchiveHasher: Hasher + Clone {
+    /// Makes a new hasher. This must always return a hasher in the same state.
+    fn make_hasher() -> Self;
+}
+
+impl ArchiveHasher for HashBuilder {
+    #[inline]
+    fn make_hasher() -> Self {
+        HashBuilder::with_seeds(
+            0x08576fb6170b5f5f,
+            0x587775eeb84a7e46,
+            0xac701115428ee569,
+            0x910feb91b92bb1cd,
+        )
+    }
+}
+
 /// An archived hash index.
 #[cfg_attr(feature = "strict", repr(C))]
-pub struct ArchivedHashIndex {
+pub struct ArchivedHashIndex<H = HashBuilder> {
     len: Archived<usize>,
+    #[cfg(feature = "hash_map_buckets")]
+    buckets: Archived<usize>,
     displace: RelPtr<Archived<u32>>,
+    _phantom: PhantomData<H>,
 }
 
-impl ArchivedHashIndex {
+impl<H: ArchiveHasher> ArchivedHashIndex<H> {
     /// Gets the number of items in the hash index.
     #[inline]
     pub const fn len(&self) -> usize {
         from_archived!(self.len) as usize
     }
 
+    /// Gets the number of buckets in the hash index.
+    ///
+    /// This is the same as the number of items unless the hash index was built with a different
+    /// bucket count.
     #[inline]
-    fn make_hasher() -> HashBuilder {
-        HashBuilder::with_seeds(
-            0x08576fb6170b5f5f,
-            0x587775eeb84a7e46,
-            0xac701115428ee569,
-            0x910feb91b92bb1cd,
-        )
+    pub const fn buckets(&self) -> usize {
+        #[cfg(not(feature = "hash_map_buckets"))]
+        {
+            self.len()
+        }
+        #[cfg(feature = "hash_map_buckets")]
+        {
+            from_archived!(self.buckets) as usize
+        }
+    }
+
+    #[inline]
+    fn make_hasher() -> H {
+        H::make_hasher()
     }
 
-    /// Gets the hasher for this hash index. The hasher for all archived hash indexes is the same
-    /// for reproducibility.
+    /// Gets the hasher for this hash index. The hasher for all archived hash indexes of the same
+    /// type is the same for reproducibility.
     #[inline]
-    pub fn hasher(&self) -> HashBuilder {
+    pub fn hasher(&self) -> H {
         Self::make_hasher()
     }
 
     #[inline]
     fn displace_slice(&self) -> &[Archived<u32>] {
-        unsafe { slice::from_raw_parts(self.displace.as_ptr(), self.len()) }
+        unsafe { slice::from_raw_parts(self.displace.as_ptr(), self.buckets()) }
     }
 
     #[inline]
@@ -63,7 +104,21 @@ impl ArchivedHashIndex {
     pub fn index<K: Hash + ?Sized>(&self, k: &K) -> Option<usize> {
         let mut hasher = self.hasher();
         k.hash(&mut hasher);
-        let displace_index = hasher.finish() % self.len() as u64;
+        self.index_with_hash(k, hasher.finish())
+    }
+
+    /// Returns the index where a key may be located in the hash index using a precomputed hash of
+    /// the key.
+    ///
+    /// The hash must be computed by hashing the key with a fresh [`hasher`](Self::hasher). If it
+    /// was computed some other way, the returned index will simply not match the key.
+    #[inline]
+    pub fn index_with_hash<K: Hash + ?Sized>(&self, k: &K, hash: u64) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let displace_index = hash % self.buckets() as u64;
         let displace = self.displace(displace_index as usize);
 
         if displace == u32::MAX {
@@ -79,12 +134,62 @@ impl ArchivedHashIndex {
         }
     }
 
+    /// Hints to the processor that the displacement read by
+    /// [`index_with_hash`](Self::index_with_hash) for the given hash will be needed soon.
+    ///
+    /// Prefetching the displacements of upcoming keys lets their memory loads overlap when looking
+    /// up many keys at once.
+    #[inline]
+    pub fn prefetch_with_hash(&self, hash: u64) {
+        if !self.is_empty() {
+            let displace_index = (hash % self.buckets() as u64) as usize;
+            prefetch(self.displace.as_ptr().wrapping_add(displace_index));
+        }
+    }
+
     /// Returns whether there are no items in the hash index.
     #[inline]
     pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
+    /// Computes statistics about the displacements stored in the hash index.
+    ///
+    /// Keys that share a bucket are placed by searching for a seed that maps all of them to free
+    /// slots. The displacement of such a bucket is the number of seeds that were tried before one
+    /// succeeded, and is zero for buckets with one or no keys. High displacements mean that
+    /// building the index took longer, but do not affect the size of the index or lookup speed.
+    pub fn displacement_stats(&self) -> DisplacementStats {
+        let mut max = 0;
+        let mut total = 0u64;
+        let mut nonzero_count = 0;
+
+        for displace in self.displace_slice() {
+            let displace = from_archived!(*displace);
+            let displacement = if displace != u32::MAX && displace & 0x80_00_00_00 != 0 {
+                displace & 0x7f_ff_ff_ff
+            } else {
+                0
+            };
+
+            max = max.max(displacement);
+            total += displacement as u64;
+            if displacement != 0 {
+                nonzero_count += 1;
+            }
+        }
+
+        DisplacementStats {
+            max,
+            mean: if self.buckets() == 0 {
+                0.0
+            } else {
+                total as f64 / self.buckets() as f64
+            },
+            nonzero_count,
+        }
+    }
+
     /// Resolves an archived hash index from a given length and parameters.
     ///
     /// # Safety
@@ -96,12 +201,23 @@ impl ArchivedHashIndex {
     pub unsafe fn resolve_from_len(
         len: usize,
         pos: usize,
-        resolver: HashIndexResolver,
+        resolver: HashIndexResolver<H>,
         out: *mut Self,
     ) {
+        debug_assert_eq!(
+            len, resolver.len,
+            "the length of a hash index must match the number of entries it was built with",
+        );
+
         let (fp, fo) = out_field!(out.len);
         len.resolve(pos + fp, (), fo);
 
+        #[cfg(feature = "hash_map_buckets")]
+        {
+            let (fp, fo) = out_field!(out.buckets);
+            resolver.buckets.resolve(pos + fp, (), fo);
+        }
+
         let (fp, fo) = out_field!(out.displace);
         RelPtr::emplace(pos + fp, resolver.displace_pos, fo);
     }
@@ -120,9 +236,60 @@ const _: () = {
         mem::{size_of, MaybeUninit},
     };
 
-    impl ArchivedHashIndex {
+    impl<H: ArchiveHasher> ArchivedHashIndex<H> {
+        #[inline]
+        fn hash_displace<K: Hash + ?Sized>(key: &K, buckets: usize) -> u32 {
+            let mut hasher = Self::make_hasher();
+            key.hash(&mut hasher);
+            (hasher.finish() % buckets as u64) as u32
+        }
+
+        /// Attempts to place every key of a bucket using the given seed, recording the chosen
+        /// indices in `assignments`. Returns whether all of the keys were placed without
+        /// collisions.
+        #[inline]
+        fn try_seed<K: Hash, V>(
+            seed: u32,
+            bucket: &[(u32, (&K, &V))],
+            occupied: &[bool],
+            assignments: &mut Vec<u32>,
+        ) -> bool {
+            let len = occupied.len();
+            let mut base_hasher = Self::make_hasher();
+            seed.hash(&mut base_hasher);
+
+            assignments.clear();
+
+            for &(_, (key, _)) in bucket.iter() {
+                let mut hasher = base_hasher.clone();
+                key.hash(&mut hasher);
+                let index = (hasher.finish() % len as u64) as u32;
+                if occupied[index as usize] || assignments.contains(&index) {
+                    return false;
+                } else {
+                    assignments.push(index);
+                }
+            }
+
+            true
+        }
+
+        /// Finds the first seed that places every key of the bucket without collisions.
+        #[inline]
+        fn find_seed<K: Hash, V>(bucket: &[(u32, (&K, &V))], occupied: &[bool]) -> Option<u32> {
+            let mut assignments = Vec::with_capacity(bucket.len());
+            (0x80_00_00_00u32..=0xFF_FF_FF_FFu32)
+                .find(|&seed| Self::try_seed(seed, bucket, occupied, &mut assignments))
+        }
+
         /// Builds and serializes a hash index from an iterator of key-value pairs.
         ///
+        /// # Panics
+        ///
+        /// Panics if no displacement can be found for one of the buckets. See
+        /// [`build_and_serialize_with_buckets`](ArchivedHashIndex::build_and_serialize_with_buckets)
+        /// for a version that returns an error instead.
+        ///
         /// # Safety
         ///
         /// - The keys returned by the iterator must be unique.
@@ -132,27 +299,94 @@ const _: () = {
             iter: I,
             serializer: &mut S,
             entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
-        ) -> Result<HashIndexResolver, S::Error>
+        ) -> Result<HashIndexResolver<H>, S::Error>
         where
             K: 'a + Hash,
             V: 'a,
             S: Serializer + ScratchSpace + ?Sized,
             I: ExactSizeIterator<Item = (&'a K, &'a V)>,
         {
-            let len = iter.len();
+            let buckets = iter.len();
+            Self::build_and_serialize_with_buckets(iter, buckets, serializer, entries)
+                .map_err(HashIndexBuildError::unwrap_serializer_error)
+        }
 
-            let mut bucket_size = ScratchVec::new(serializer, len)?;
-            for _ in 0..len {
-                bucket_size.push(0u32);
-            }
+        /// Builds and serializes a hash index with the given number of buckets from an iterator of
+        /// key-value pairs.
+        ///
+        /// Using more buckets than keys makes it more likely that each bucket holds at most one key,
+        /// so fewer displacements need to be searched for when building the index. This costs an
+        /// extra `u32` per bucket. Using fewer buckets than keys makes the search slow and likely to
+        /// fail.
+        ///
+        /// Returns an error if no displacement can be found for one of the buckets.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `entries` must have a capacity of `iter.len()` entries.
+        /// - `buckets` must be nonzero if the iterator is not empty.
+        /// - If the `hash_map_buckets` feature is not enabled, the bucket count is not archived and
+        ///   `buckets` must be equal to `iter.len()`.
+        #[allow(clippy::type_complexity)]
+        pub unsafe fn build_and_serialize_with_buckets<'a, K, V, S, I>(
+            iter: I,
+            buckets: usize,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+        ) -> Result<HashIndexResolver<H>, HashIndexBuildError<S::Error>>
+        where
+            K: 'a + Hash,
+            V: 'a,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            Self::build_and_serialize_with(
+                iter,
+                buckets,
+                serializer,
+                entries,
+                |displaces, buckets| {
+                    for (displace, (key, _)) in displaces.iter_mut() {
+                        *displace = Self::hash_displace(*key, buckets);
+                    }
+                },
+                Self::find_seed,
+            )
+        }
+
+        /// Builds and serializes a hash index, using the given functions to hash the keys into
+        /// buckets and to find the seed for each bucket with more than one key.
+        #[allow(clippy::type_complexity)]
+        unsafe fn build_and_serialize_with<'a, K, V, S, I, D, F>(
+            iter: I,
+            buckets: usize,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+            hash_displaces: D,
+            find_seed: F,
+        ) -> Result<HashIndexResolver<H>, HashIndexBuildError<S::Error>>
+        where
+            K: 'a + Hash,
+            V: 'a,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+            D: FnOnce(&mut [(u32, (&'a K, &'a V))], usize),
+            F: Fn(&[(u32, (&'a K, &'a V))], &[bool]) -> Option<u32>,
+        {
+            let len = iter.len();
 
             let mut displaces = ScratchVec::new(serializer, len)?;
+            for entry in iter {
+                displaces.push((0u32, entry));
+            }
+            hash_displaces(displaces.as_mut_slice(), buckets);
 
-            for (key, value) in iter {
-                let mut hasher = Self::make_hasher();
-                key.hash(&mut hasher);
-                let displace = (hasher.finish() % len as u64) as u32;
-                displaces.push((displace, (key, value)));
+            let mut bucket_size = ScratchVec::new(serializer, buckets)?;
+            for _ in 0..buckets {
+                bucket_size.push(0u32);
+            }
+            for &(displace, _) in displaces.iter() {
                 bucket_size[displace as usize] += 1;
             }
 
@@ -164,8 +398,8 @@ const _: () = {
                 occupied.push(false);
             }
 
-            let mut displacements = ScratchVec::new(serializer, len)?;
-            for _ in 0..len {
+            let mut displacements = ScratchVec::new(serializer, buckets)?;
+            for _ in 0..buckets {
                 displacements.push(to_archived!(u32::MAX));
             }
 
@@ -181,32 +415,16 @@ const _: () = {
                 start = end;
 
                 if bucket_size > 1 {
-                    'find_seed: for seed in 0x80_00_00_00u32..=0xFF_FF_FF_FFu32 {
-                        let mut base_hasher = Self::make_hasher();
-                        seed.hash(&mut base_hasher);
-
-                        assignments.clear();
-
-                        for &(_, (key, _)) in bucket.iter() {
-                            let mut hasher = base_hasher;
-                            key.hash(&mut hasher);
-                            let index = (hasher.finish() % len as u64) as u32;
-                            if occupied[index as usize] || assignments.contains(&index) {
-                                continue 'find_seed;
-                            } else {
-                                assignments.push(index);
-                            }
-                        }
-
-                        for i in 0..bucket_size {
-                            occupied[assignments[i] as usize] = true;
-                            entries[assignments[i] as usize]
-                                .as_mut_ptr()
-                                .write(bucket[i].1);
-                        }
-                        displacements[displace as usize] = to_archived!(seed);
-                        break;
+                    let seed = find_seed(bucket, occupied.as_slice())
+                        .ok_or(HashIndexBuildError::DisplacementNotFound { bucket_size })?;
+                    Self::try_seed(seed, bucket, occupied.as_slice(), &mut assignments);
+                    for i in 0..bucket_size {
+                        occupied[assignments[i] as usize] = true;
+                        entries[assignments[i] as usize]
+                            .as_mut_ptr()
+                            .write(bucket[i].1);
                     }
+                    displacements[displace as usize] = to_archived!(seed);
                 } else {
                     let offset = occupied[first_empty..]
                         .iter()
@@ -224,28 +442,195 @@ const _: () = {
             let displace_pos = serializer.align_for::<Archived<u32>>()?;
             let displacements_slice = slice::from_raw_parts(
                 displacements.as_ptr().cast::<u8>(),
-                len * size_of::<Archived<u32>>(),
+                buckets * size_of::<Archived<u32>>(),
             );
             serializer.write(displacements_slice)?;
 
             // Free scratch vecs
             displacements.free(serializer)?;
             occupied.free(serializer)?;
-            displaces.free(serializer)?;
             bucket_size.free(serializer)?;
+            displaces.free(serializer)?;
+
+            Ok(HashIndexResolver {
+                displace_pos,
+                #[cfg(debug_assertions)]
+                len,
+                #[cfg(feature = "hash_map_buckets")]
+                buckets,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    impl<H: ArchiveHasher> ArchivedHashIndex<H> {
+        /// Builds and serializes a hash index from an iterator of key-value pairs, hashing the keys
+        /// into buckets and searching for displacements in parallel.
+        ///
+        /// Buckets are still placed one at a time, because each bucket's search depends on the
+        /// slots claimed by the buckets before it. The seeds for each bucket with more than one key
+        /// are tried in parallel once the first few fail.
+        ///
+        /// The resulting layout is identical to the one produced by
+        /// [`build_and_serialize`](ArchivedHashIndex::build_and_serialize).
+        ///
+        /// # Panics
+        ///
+        /// Panics if no displacement can be found for one of the buckets.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `entries` must have a capacity of `iter.len()` entries.
+        #[allow(clippy::type_complexity)]
+        pub unsafe fn build_and_serialize_par<'a, K, V, S, I>(
+            iter: I,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+        ) -> Result<HashIndexResolver<H>, S::Error>
+        where
+            K: 'a + Hash + Sync,
+            V: 'a + Sync,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            use rayon::prelude::*;
+
+            let buckets = iter.len();
+            Self::build_and_serialize_with(
+                iter,
+                buckets,
+                serializer,
+                entries,
+                |displaces, buckets| {
+                    displaces.par_iter_mut().for_each(|(displace, (key, _))| {
+                        *displace = Self::hash_displace(*key, buckets);
+                    });
+                },
+                Self::find_seed_par,
+            )
+            .map_err(HashIndexBuildError::unwrap_serializer_error)
+        }
 
-            Ok(HashIndexResolver { displace_pos })
+        /// Finds the first seed that places every key of the bucket without collisions, trying
+        /// seeds in parallel after the first few.
+        ///
+        /// This finds the same seed as [`find_seed`](ArchivedHashIndex::find_seed).
+        fn find_seed_par<K: Hash + Sync, V: Sync>(
+            bucket: &[(u32, (&K, &V))],
+            occupied: &[bool],
+        ) -> Option<u32> {
+            use rayon::prelude::*;
+
+            // Most buckets are placed within a few seeds, which isn't worth splitting up
+            const SEQUENTIAL_SEEDS: u32 = 16;
+            const FIRST_SEED: u32 = 0x80_00_00_00;
+
+            let mut assignments = Vec::with_capacity(bucket.len());
+            (FIRST_SEED..FIRST_SEED + SEQUENTIAL_SEEDS)
+                .find(|&seed| Self::try_seed(seed, bucket, occupied, &mut assignments))
+                .or_else(|| {
+                    (FIRST_SEED + SEQUENTIAL_SEEDS..=0xFF_FF_FF_FF)
+                        .into_par_iter()
+                        .map_init(
+                            || Vec::with_capacity(bucket.len()),
+                            |assignments, seed| {
+                                (seed, Self::try_seed(seed, bucket, occupied, assignments))
+                            },
+                        )
+                        .find_first(|&(_, found)| found)
+                        .map(|(seed, _)| seed)
+                })
         }
     }
 };
 
-impl fmt::Debug for ArchivedHashIndex {
+impl<H: ArchiveHasher> fmt::Debug for ArchivedHashIndex<H> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.displace_slice()).finish()
     }
 }
 
+/// Statistics about the displacements of an [`ArchivedHashIndex`].
+///
+/// This is returned by [`ArchivedHashIndex::displacement_stats`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplacementStats {
+    /// The largest displacement of any bucket
+    pub max: u32,
+    /// The mean displacement over all buckets
+    pub mean: f64,
+    /// The number of buckets with a nonzero displacement
+    pub nonzero_count: usize,
+}
+
+/// An error that can occur while building a hash index.
+#[derive(Debug)]
+pub enum HashIndexBuildError<E> {
+    /// The serializer returned an error
+    SerializerError(E),
+    /// No displacement placed every key of a bucket without collisions
+    DisplacementNotFound {
+        /// The number of keys in the bucket
+        bucket_size: usize,
+    },
+}
+
+impl<E> HashIndexBuildError<E> {
+    /// Returns the serializer error, panicking if a displacement could not be found.
+    #[inline]
+    pub(crate) fn unwrap_serializer_error(self) -> E {
+        match self {
+            HashIndexBuildError::SerializerError(e) => e,
+            HashIndexBuildError::DisplacementNotFound { bucket_size } => panic!(
+                "failed to find a displacement for a hash index bucket with {} keys",
+                bucket_size
+            ),
+        }
+    }
+}
+
+impl<E> From<E> for HashIndexBuildError<E> {
+    #[inline]
+    fn from(e: E) -> Self {
+        Self::SerializerError(e)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for HashIndexBuildError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashIndexBuildError::SerializerError(e) => e.fmt(f),
+            HashIndexBuildError::DisplacementNotFound { bucket_size } => write!(
+                f,
+                "failed to find a displacement for a hash index bucket with {} keys",
+                bucket_size
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for HashIndexBuildError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                HashIndexBuildError::SerializerError(e) => Some(e as &dyn Error),
+                HashIndexBuildError::DisplacementNotFound { .. } => None,
+            }
+        }
+    }
+};
+
 /// The resolver for an archived hash index.
-pub struct HashIndexResolver {
+pub struct HashIndexResolver<H = HashBuilder> {
     displace_pos: usize,
+    #[cfg(debug_assertions)]
+    len: usize,
+    #[cfg(feature = "hash_map_buckets")]
+    buckets: usize,
+    _phantom: PhantomData<H>,
 }
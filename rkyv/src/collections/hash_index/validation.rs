@@ -1,6 +1,10 @@
 //! Validation implementation for ArchivedHashIndex.
 
-use crate::{collections::ArchivedHashIndex, validation::ArchiveContext, Archived, RelPtr};
+use crate::{
+    collections::{hash_index::ArchiveHasher, ArchivedHashIndex},
+    validation::ArchiveContext,
+    Archived, RelPtr,
+};
 use bytecheck::{CheckBytes, Error, SliceCheckError};
 use core::{
     alloc::{Layout, LayoutError},
@@ -20,6 +24,14 @@ pub enum HashIndexError<C> {
         /// The value of the entry at the invalid location
         value: u32,
     },
+    /// The hash index has entries but no buckets to look them up in
+    #[cfg(feature = "hash_map_buckets")]
+    InvalidBucketCount {
+        /// The number of entries in the hash index
+        len: usize,
+        /// The number of buckets in the hash index
+        buckets: usize,
+    },
     /// A bounds error occurred
     ContextError(C),
 }
@@ -54,6 +66,12 @@ impl<C: fmt::Display> fmt::Display for HashIndexError<C> {
                 "invalid displacement: value {} at index {}",
                 value, index,
             ),
+            #[cfg(feature = "hash_map_buckets")]
+            HashIndexError::InvalidBucketCount { len, buckets } => write!(
+                f,
+                "invalid bucket count: {} buckets for {} entries",
+                buckets, len,
+            ),
             HashIndexError::ContextError(e) => e.fmt(f),
         }
     }
@@ -68,13 +86,15 @@ const _: () = {
             match self {
                 HashIndexError::LayoutError(e) => Some(e as &dyn Error),
                 HashIndexError::InvalidDisplacement { .. } => None,
+                #[cfg(feature = "hash_map_buckets")]
+                HashIndexError::InvalidBucketCount { .. } => None,
                 HashIndexError::ContextError(e) => Some(e as &dyn Error),
             }
         }
     }
 };
 
-impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedHashIndex
+impl<H: ArchiveHasher, C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedHashIndex<H>
 where
     C::Error: Error,
 {
@@ -88,7 +108,19 @@ where
             ptr::addr_of!((*value).len),
             context,
         )?) as usize;
-        Layout::array::<Archived<u32>>(len)?;
+        #[cfg(not(feature = "hash_map_buckets"))]
+        let buckets = len;
+        #[cfg(feature = "hash_map_buckets")]
+        let buckets = from_archived!(*Archived::<usize>::check_bytes(
+            ptr::addr_of!((*value).buckets),
+            context,
+        )?) as usize;
+        // Lookups take the hash modulo the bucket count
+        #[cfg(feature = "hash_map_buckets")]
+        if buckets == 0 && len != 0 {
+            return Err(HashIndexError::InvalidBucketCount { len, buckets });
+        }
+        Layout::array::<Archived<u32>>(buckets)?;
 
         let displace_rel_ptr =
             RelPtr::manual_check_bytes(ptr::addr_of!((*value).displace), context)?;
@@ -96,7 +128,7 @@ where
             .check_subtree_ptr::<[Archived<u32>]>(
                 displace_rel_ptr.base(),
                 displace_rel_ptr.offset(),
-                len,
+                buckets,
             )
             .map_err(HashIndexError::ContextError)?;
 
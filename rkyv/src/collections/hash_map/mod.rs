@@ -2,47 +2,99 @@
 //!
 //! During archiving, hashmaps are built into minimal perfect hashmaps using
 //! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//!
+//! Only building archived hash maps requires the `alloc` feature. Lookups and iteration work
+//! without an allocator, so archived hash maps can be read from archives embedded in `no_std`
+//! programs.
 
 #[cfg(feature = "validation")]
 pub mod validation;
 
+#[cfg(any(feature = "hash_map_insertion_order", feature = "hash_map_tombstones"))]
+use crate::Archived;
 use crate::{
     collections::{
-        hash_index::{ArchivedHashIndex, HashIndexResolver},
+        hash_index::{
+            ArchiveHasher, ArchivedHashIndex, HashBuilder, HashIndexBuildError, HashIndexResolver,
+        },
         util::Entry,
     },
     RelPtr,
 };
 #[cfg(feature = "alloc")]
 use crate::{
-    ser::{ScratchSpace, Serializer},
+    ser::{LazySerialize, ScratchSpace, Serializer},
     Serialize,
 };
+#[cfg(feature = "hash_map_tombstones")]
+use crate::{Archive, FixedUsize};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::boxed::Box;
 use core::{
-    borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, marker::PhantomData, ops::Index, pin::Pin,
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::Index,
+    pin::Pin,
+    slice,
 };
 
+/// The number of keys that [`ArchivedHashMap::get_batch`] and the batched lookups of
+/// [`ArchivedHashSet`](crate::collections::ArchivedHashSet) look ahead when prefetching.
+///
+/// Each key's displacement is prefetched this many keys before its entry is located, and its entry
+/// is prefetched this many keys before it is compared.
+pub const PREFETCH_DISTANCE: usize = 8;
+
 /// An archived `HashMap`.
+///
+/// Keys are hashed with `H`, which defaults to the same [`HashBuilder`] used by all other archived
+/// hashed collections. Archived hash maps can only be accessed with the hasher that they were
+/// serialized with.
 #[cfg_attr(feature = "strict", repr(C))]
-pub struct ArchivedHashMap<K, V> {
-    index: ArchivedHashIndex,
+pub struct ArchivedHashMap<K, V, H = HashBuilder> {
+    index: ArchivedHashIndex<H>,
     entries: RelPtr<Entry<K, V>>,
+    #[cfg(feature = "hash_map_insertion_order")]
+    order: RelPtr<Archived<u32>>,
+    #[cfg(feature = "hash_map_tombstones")]
+    live: Archived<usize>,
+    #[cfg(feature = "hash_map_tombstones")]
+    present: RelPtr<u8>,
 }
 
-impl<K, V> ArchivedHashMap<K, V> {
+impl<K, V, H: ArchiveHasher> ArchivedHashMap<K, V, H> {
     /// Gets the number of items in the hash map.
+    ///
+    /// Tombstoned entries are not counted.
     #[inline]
     pub const fn len(&self) -> usize {
-        self.index.len()
+        #[cfg(not(feature = "hash_map_tombstones"))]
+        {
+            self.index.len()
+        }
+        #[cfg(feature = "hash_map_tombstones")]
+        {
+            from_archived!(self.live) as usize
+        }
     }
 
-    /// Gets the hasher for this hashmap. The hasher for all archived hashmaps is the same for
-    /// reproducibility.
+    /// Gets the hasher for this hashmap. The hasher for all archived hashmaps of the same type is
+    /// the same for reproducibility.
     #[inline]
-    pub fn hasher(&self) -> seahash::SeaHasher {
+    pub fn hasher(&self) -> H {
         self.index.hasher()
     }
 
+    /// Gets the hash index used to locate the entries of the hash map.
+    #[inline]
+    pub fn hash_index(&self) -> &ArchivedHashIndex<H> {
+        &self.index
+    }
+
     #[inline]
     unsafe fn entry(&self, index: usize) -> &Entry<K, V> {
         &*self.entries.as_ptr().add(index)
@@ -54,27 +106,56 @@ impl<K, V> ArchivedHashMap<K, V> {
     }
 
     #[inline]
-    fn find<Q: ?Sized>(&self, k: &Q) -> Option<usize>
+    fn presence(&self) -> Presence {
+        Presence {
+            #[cfg(feature = "hash_map_tombstones")]
+            bits: self.present.as_ptr(),
+        }
+    }
+
+    #[inline]
+    fn find<Q>(&self, k: &Q) -> Option<usize>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
-        self.index.index(k).and_then(|i| {
-            let entry = unsafe { self.entry(i) };
-            if entry.key.borrow() == k {
-                Some(i)
-            } else {
-                None
-            }
-        })
+        let mut hasher = self.hasher();
+        k.hash(&mut hasher);
+        self.find_with_hash(k, hasher.finish())
+    }
+
+    #[inline]
+    fn find_with_hash<Q>(&self, k: &Q, hash: u64) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index
+            .index_with_hash(k, hash)
+            .and_then(|i| self.check_index(k, i))
+    }
+
+    /// Checks whether the entry at an index returned by the hash index holds the given key.
+    #[inline]
+    pub(crate) fn check_index<Q>(&self, k: &Q, i: usize) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entry = unsafe { self.entry(i) };
+        if entry.key.borrow() == k && unsafe { self.presence().contains(i) } {
+            Some(i)
+        } else {
+            None
+        }
     }
 
     /// Finds the key-value entry for a key.
     #[inline]
-    pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k).map(move |index| {
             let entry = unsafe { self.entry(index) };
@@ -84,10 +165,10 @@ impl<K, V> ArchivedHashMap<K, V> {
 
     /// Finds the mutable key-value entry for a key.
     #[inline]
-    pub fn get_key_value_pin<Q: ?Sized>(self: Pin<&mut Self>, k: &Q) -> Option<(&K, Pin<&mut V>)>
+    pub fn get_key_value_pin<Q>(self: Pin<&mut Self>, k: &Q) -> Option<(&K, Pin<&mut V>)>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         unsafe {
             let hash_map = self.get_unchecked_mut();
@@ -100,31 +181,71 @@ impl<K, V> ArchivedHashMap<K, V> {
 
     /// Returns whether a key is present in the hash map.
     #[inline]
-    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k).is_some()
     }
 
     /// Gets the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type. For maps with `ArchivedString` keys,
+    /// this means that string slices can be used directly (e.g. `map.get("key")`), and owned keys
+    /// like `String` and `Cow<str>` can be passed by dereferencing them to a `&str`.
     #[inline]
-    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k)
             .map(|index| unsafe { &self.entry(index).value })
     }
 
+    /// Gets the value associated with the given key, or `default` if the key is not present.
+    #[inline]
+    pub fn get_or<'a, Q>(&'a self, k: &Q, default: &'a V) -> &'a V
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(k).unwrap_or(default)
+    }
+
+    /// Gets the value associated with the given key, or the result of calling `f` if the key is
+    /// not present.
+    #[inline]
+    pub fn get_or_else<'a, Q, F>(&'a self, k: &Q, f: F) -> &'a V
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce() -> &'a V,
+    {
+        self.get(k).unwrap_or_else(f)
+    }
+
+    /// Gets the value associated with the given key using a precomputed hash of the key.
+    ///
+    /// The hash must be computed by hashing the key with a fresh [`hasher`](Self::hasher). If it
+    /// was computed some other way, this will simply return `None`.
+    #[inline]
+    pub fn get_with_hash<Q>(&self, k: &Q, hash: u64) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_with_hash(k, hash)
+            .map(|index| unsafe { &self.entry(index).value })
+    }
+
     /// Gets the mutable value associated with the given key.
     #[inline]
-    pub fn get_pin<Q: ?Sized>(self: Pin<&mut Self>, k: &Q) -> Option<Pin<&mut V>>
+    pub fn get_pin<Q>(self: Pin<&mut Self>, k: &Q) -> Option<Pin<&mut V>>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         unsafe {
             let hash_map = self.get_unchecked_mut();
@@ -134,6 +255,60 @@ impl<K, V> ArchivedHashMap<K, V> {
         }
     }
 
+    /// Gets the entry for the given key for in-place manipulation.
+    ///
+    /// Archived hash maps have a fixed size, so a vacant entry only records that the key is not
+    /// present and cannot be inserted into.
+    #[inline]
+    pub fn entry_pin<Q>(self: Pin<&mut Self>, k: &Q) -> EntryPin<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        unsafe {
+            let hash_map = self.get_unchecked_mut();
+            match hash_map.find(k) {
+                Some(index) => EntryPin::Occupied(OccupiedEntryPin {
+                    entry: hash_map.entry_mut(index),
+                }),
+                None => EntryPin::Vacant,
+            }
+        }
+    }
+
+    /// Gets the mutable values associated with several keys at once.
+    ///
+    /// Returns `None` if any of the keys are missing or if any two keys refer to the same entry.
+    #[inline]
+    pub fn get_many_pin_mut<Q, const N: usize>(
+        self: Pin<&mut Self>,
+        keys: [&Q; N],
+    ) -> Option<[Pin<&mut V>; N]>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        unsafe {
+            let hash_map = self.get_unchecked_mut();
+
+            let mut indices = [0; N];
+            for (i, k) in keys.iter().enumerate() {
+                let index = hash_map.find(k)?;
+                if indices[..i].contains(&index) {
+                    return None;
+                }
+                indices[i] = index;
+            }
+
+            let entries = hash_map.entries.as_mut_ptr();
+            let mut values = MaybeUninit::<[MaybeUninit<Pin<&mut V>>; N]>::uninit().assume_init();
+            for (value, &index) in values.iter_mut().zip(indices.iter()) {
+                *value = MaybeUninit::new(Pin::new_unchecked(&mut (*entries.add(index)).value));
+            }
+            Some(values.as_ptr().cast::<[Pin<&mut V>; N]>().read())
+        }
+    }
+
     /// Returns `true` if the map contains no elements.
     #[inline]
     pub const fn is_empty(&self) -> bool {
@@ -141,21 +316,71 @@ impl<K, V> ArchivedHashMap<K, V> {
     }
 
     #[inline]
-    fn raw_iter(&self) -> RawIter<K, V> {
-        RawIter::new(self.entries.as_ptr().cast(), self.len())
+    fn raw_iter(&self) -> RawIter<'_, K, V> {
+        RawIter::new(
+            self.entries.as_ptr().cast(),
+            self.len(),
+            self.index.len(),
+            self.presence(),
+        )
     }
 
     #[inline]
-    fn raw_iter_pin(self: Pin<&mut Self>) -> RawIterPin<K, V> {
+    fn raw_iter_pin(self: Pin<&mut Self>) -> RawIterPin<'_, K, V> {
         unsafe {
             let hash_map = self.get_unchecked_mut();
-            RawIterPin::new(hash_map.entries.as_mut_ptr().cast(), hash_map.len())
+            let presence = hash_map.presence();
+            RawIterPin::new(
+                hash_map.entries.as_mut_ptr().cast(),
+                hash_map.len(),
+                hash_map.index.len(),
+                presence,
+            )
+        }
+    }
+
+    /// Gets the entries of the hash map as a contiguous slice.
+    ///
+    /// Entries are in the order chosen by the hash index, which is unrelated to the order they were
+    /// serialized in. If the `hash_map_tombstones` feature is enabled, this also includes entries
+    /// that have been tombstoned and may be longer than [`len`](Self::len).
+    #[inline]
+    pub fn entries_slice(&self) -> &[Entry<K, V>] {
+        unsafe { slice::from_raw_parts(self.entries.as_ptr(), self.index.len()) }
+    }
+
+    /// Checks that the length of the hash map matches its entries.
+    ///
+    /// Every present entry must be found at its own position by the hash index, and the number of
+    /// present entries must equal [`len`](Self::len). Hash maps in validated archives always pass
+    /// this check. It's intended for archives that are accessed without validation, so that a
+    /// corrupted length can be caught before the hash map is deserialized.
+    pub fn check_len(&self) -> Result<(), HashMapLengthError>
+    where
+        K: Hash,
+    {
+        let presence = self.presence();
+        let mut present = 0;
+        for i in 0..self.index.len() {
+            if unsafe { presence.contains(i) } {
+                let entry = unsafe { self.entry(i) };
+                if self.index.index(&entry.key) != Some(i) {
+                    return Err(HashMapLengthError { len: self.len() });
+                }
+                present += 1;
+            }
+        }
+
+        if present != self.len() {
+            Err(HashMapLengthError { len: self.len() })
+        } else {
+            Ok(())
         }
     }
 
     /// Gets an iterator over the key-value entries in the hash map.
     #[inline]
-    pub fn iter(&self) -> Iter<K, V> {
+    pub fn iter(&self) -> Iter<'_, K, V> {
         Iter {
             inner: self.raw_iter(),
         }
@@ -163,23 +388,73 @@ impl<K, V> ArchivedHashMap<K, V> {
 
     /// Gets an iterator over the mutable key-value entries in the hash map.
     #[inline]
-    pub fn iter_pin(self: Pin<&mut Self>) -> IterPin<K, V> {
+    pub fn iter_pin(self: Pin<&mut Self>) -> IterPin<'_, K, V> {
         IterPin {
             inner: self.raw_iter_pin(),
         }
     }
 
+    /// Gets an iterator over the mutable key-value entries in the hash map.
+    ///
+    /// Because the values are `Unpin`, they can be mutated directly instead of through a `Pin`.
+    #[inline]
+    pub fn iter_mut(self: Pin<&mut Self>) -> IterMut<'_, K, V>
+    where
+        V: Unpin,
+    {
+        IterMut {
+            inner: self.raw_iter_pin(),
+        }
+    }
+
     /// Gets an iterator over the keys in the hash map.
     #[inline]
-    pub fn keys(&self) -> Keys<K, V> {
+    pub fn keys(&self) -> Keys<'_, K, V> {
         Keys {
             inner: self.raw_iter(),
         }
     }
 
+    /// Gets an iterator over the keys in the hash map in sorted order, using `order` to hold the
+    /// sorted positions of the entries.
+    ///
+    /// The keys are not copied. Instead, the positions of the entries are sorted by key once before
+    /// iterating, which costs O(n log n) time. `order` must be at least as long as the hash map and
+    /// can be reused between calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is shorter than the hash map.
+    #[inline]
+    pub fn sorted_keys_in<'a>(&'a self, order: &'a mut [usize]) -> SortedKeys<'a, K, V>
+    where
+        K: Ord,
+    {
+        let order = &mut order[..self.len()];
+        self.sort_order(order);
+        SortedKeys {
+            entries: self.entries.as_ptr(),
+            order: SortOrder::Borrowed(order),
+            pos: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn sort_order(&self, order: &mut [usize])
+    where
+        K: Ord,
+    {
+        let entries = self.entries.as_ptr();
+        for (i, entry) in order.iter_mut().zip(self.raw_iter()) {
+            *i = unsafe { entry.offset_from(entries) as usize };
+        }
+        order.sort_unstable_by(|&a, &b| unsafe { self.entry(a).key.cmp(&self.entry(b).key) });
+    }
+
     /// Gets an iterator over the values in the hash map.
     #[inline]
-    pub fn values(&self) -> Values<K, V> {
+    pub fn values(&self) -> Values<'_, K, V> {
         Values {
             inner: self.raw_iter(),
         }
@@ -187,12 +462,70 @@ impl<K, V> ArchivedHashMap<K, V> {
 
     /// Gets an iterator over the mutable values in the hash map.
     #[inline]
-    pub fn values_pin(self: Pin<&mut Self>) -> ValuesPin<K, V> {
+    pub fn values_pin(self: Pin<&mut Self>) -> ValuesPin<'_, K, V> {
         ValuesPin {
             inner: self.raw_iter_pin(),
         }
     }
 
+    /// Gets an iterator over the mutable values in the hash map.
+    ///
+    /// Because the values are `Unpin`, they can be mutated directly instead of through a `Pin`.
+    #[inline]
+    pub fn values_mut(self: Pin<&mut Self>) -> ValuesMut<'_, K, V>
+    where
+        V: Unpin,
+    {
+        ValuesMut {
+            inner: self.raw_iter_pin(),
+        }
+    }
+
+    #[cfg(feature = "hash_map_insertion_order")]
+    #[inline]
+    fn order_slice(&self) -> &[Archived<u32>] {
+        unsafe { core::slice::from_raw_parts(self.order.as_ptr(), self.index.len()) }
+    }
+
+    /// Gets an iterator over the key-value entries in the hash map in the order that they were
+    /// serialized in.
+    #[cfg(feature = "hash_map_insertion_order")]
+    #[inline]
+    pub fn iter_insertion_order(&self) -> IterInsertionOrder<K, V> {
+        IterInsertionOrder {
+            entries: self.entries.as_ptr(),
+            order: self.order_slice().iter(),
+            presence: self.presence(),
+            remaining: self.len(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Tombstones every entry for which the predicate returns `true`.
+    ///
+    /// Archived hash maps have a fixed size, so entries cannot actually be removed. Instead,
+    /// tombstoned entries are marked absent and are skipped by all lookups and iterators.
+    #[cfg(feature = "hash_map_tombstones")]
+    pub fn tombstone_if(self: Pin<&mut Self>, mut f: impl FnMut(&K, &V) -> bool) {
+        unsafe {
+            let hash_map = self.get_unchecked_mut();
+            let bits = hash_map.present.as_mut_ptr();
+            let mut live = hash_map.len();
+            for i in 0..hash_map.index.len() {
+                let byte = &mut *bits.add(i / 8);
+                let mask = 1 << (i % 8);
+                if *byte & mask != 0 {
+                    let entry = hash_map.entry(i);
+                    if f(&entry.key, &entry.value) {
+                        *byte &= !mask;
+                        live -= 1;
+                    }
+                }
+            }
+            hash_map.live = to_archived!(live as FixedUsize);
+        }
+    }
+
     /// Resolves an archived hash map from a given length and parameters.
     ///
     /// # Safety
@@ -200,51 +533,442 @@ impl<K, V> ArchivedHashMap<K, V> {
     /// - `len` must be the number of elements that were serialized
     /// - `pos` must be the position of `out` within the archive
     /// - `resolver` must be the result of serializing a hash map
+    ///
+    /// In debug builds, this asserts that `len` matches the resolver and that the entries of the
+    /// hash map were written before `pos`.
     #[inline]
     pub unsafe fn resolve_from_len(
         len: usize,
         pos: usize,
-        resolver: HashMapResolver,
+        resolver: HashMapResolver<H>,
         out: *mut Self,
     ) {
         let (fp, fo) = out_field!(out.index);
         ArchivedHashIndex::resolve_from_len(len, pos + fp, resolver.index_resolver, fo);
 
+        // The entries must have been written before the hash map that points to them
+        let entries_pos = resolver.entries_pos;
+        debug_assert!(
+            matches!(
+                len.checked_mul(core::mem::size_of::<Entry<K, V>>())
+                    .and_then(|size| entries_pos.checked_add(size)),
+                Some(end) if end <= pos
+            ),
+            "the entries of a hash map must be written before the hash map is resolved",
+        );
+
         let (fp, fo) = out_field!(out.entries);
-        RelPtr::emplace(pos + fp, resolver.entries_pos, fo);
+        RelPtr::emplace(pos + fp, entries_pos, fo);
+
+        #[cfg(feature = "hash_map_insertion_order")]
+        {
+            let (fp, fo) = out_field!(out.order);
+            RelPtr::emplace(pos + fp, resolver.order_pos, fo);
+        }
+
+        #[cfg(feature = "hash_map_tombstones")]
+        {
+            let (fp, fo) = out_field!(out.live);
+            len.resolve(pos + fp, (), fo);
+
+            let (fp, fo) = out_field!(out.present);
+            RelPtr::emplace(pos + fp, resolver.present_pos, fo);
+        }
     }
 }
 
 #[cfg(feature = "alloc")]
 const _: () = {
-    impl<K, V> ArchivedHashMap<K, V> {
+    use crate::{collections::util::prefetch, ScratchVec};
+    #[cfg(not(feature = "std"))]
+    use alloc::{vec, vec::Vec};
+
+    impl<K, V, H: ArchiveHasher> ArchivedHashMap<K, V, H> {
+        /// Gets an iterator over the keys in the hash map in sorted order.
+        ///
+        /// This allocates a buffer for the sorted positions of the entries, see
+        /// [`sorted_keys_in`](ArchivedHashMap::sorted_keys_in) for more information. Sorting the
+        /// positions costs O(n log n) time once before iterating.
+        #[inline]
+        pub fn sorted_keys(&self) -> SortedKeys<'_, K, V>
+        where
+            K: Ord,
+        {
+            let mut order = vec![0; self.len()].into_boxed_slice();
+            self.sort_order(&mut order);
+            SortedKeys {
+                entries: self.entries.as_ptr(),
+                order: SortOrder::Owned(order),
+                pos: 0,
+                _phantom: PhantomData,
+            }
+        }
+
+        /// Gets the values associated with each of the given keys.
+        ///
+        /// This returns the same results as calling [`get`](ArchivedHashMap::get) for each key, but
+        /// prefetches the memory for upcoming keys so that lookups overlap instead of waiting on
+        /// each other. The lookahead is set by [`PREFETCH_DISTANCE`].
+        pub fn get_batch<Q>(&self, keys: &[&Q]) -> Vec<Option<&V>>
+        where
+            K: Borrow<Q>,
+            Q: Hash + Eq + ?Sized,
+        {
+            const D: usize = PREFETCH_DISTANCE;
+
+            let len = keys.len();
+            let mut hashes = Vec::with_capacity(len);
+            let mut indices = Vec::with_capacity(len);
+            let mut result = Vec::with_capacity(len);
+
+            // Each key moves through three stages, each one running D keys behind the last
+            for i in 0..len + 2 * D {
+                // Hash the key and prefetch its displacement
+                if i < len {
+                    let mut hasher = self.hasher();
+                    keys[i].hash(&mut hasher);
+                    let hash = hasher.finish();
+                    self.index.prefetch_with_hash(hash);
+                    hashes.push(hash);
+                }
+
+                // Find the index of the key and prefetch its entry
+                if let Some(j) = i.checked_sub(D).filter(|&j| j < len) {
+                    let index = self.index.index_with_hash(keys[j], hashes[j]);
+                    if let Some(index) = index {
+                        prefetch(self.entries.as_ptr().wrapping_add(index));
+                    }
+                    indices.push(index);
+                }
+
+                // Compare the key to its entry
+                if let Some(j) = i.checked_sub(2 * D).filter(|&j| j < len) {
+                    let index = indices[j].and_then(|index| self.check_index(keys[j], index));
+                    result.push(index.map(|index| unsafe { &self.entry(index).value }));
+                }
+            }
+
+            result
+        }
+
         /// Serializes an iterator of key-value pairs as a hash map.
         ///
+        /// # Panics
+        ///
+        /// Panics if no displacement can be found for one of the buckets of the hash index.
+        ///
         /// # Safety
         ///
         /// The keys returned by the iterator must be unique.
+        #[inline]
         pub unsafe fn serialize_from_iter<'a, KU, VU, S, I>(
             iter: I,
             serializer: &mut S,
-        ) -> Result<HashMapResolver, S::Error>
+        ) -> Result<HashMapResolver<H>, S::Error>
         where
             KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
             VU: 'a + Serialize<S, Archived = V>,
             S: Serializer + ScratchSpace + ?Sized,
             I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
         {
-            use crate::ScratchVec;
+            let buckets = iter.len();
+            Self::serialize_from_iter_buckets(iter, buckets, serializer)
+                .map_err(HashIndexBuildError::unwrap_serializer_error)
+        }
 
+        /// Serializes an iterator of key-value pairs as a hash map, building its hash index with
+        /// the given options.
+        ///
+        /// With the default options, this produces the same archive as
+        /// [`serialize_from_iter`](ArchivedHashMap::serialize_from_iter). Returns an error if no
+        /// displacement can be found for one of the buckets of the hash index.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the options don't produce a valid bucket count. See
+        /// [`HashMapBuildOptions::buckets`].
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        #[cfg(feature = "hash_map_buckets")]
+        #[inline]
+        pub unsafe fn serialize_from_iter_with<'a, KU, VU, S, I>(
+            iter: I,
+            options: HashMapBuildOptions,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, HashIndexBuildError<S::Error>>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
+            let buckets = options
+                .buckets(iter.len())
+                .expect("invalid bucket ratio for hash map");
+            Self::serialize_from_iter_buckets(iter, buckets, serializer)
+        }
+
+        /// Serializes an iterator of key-value pairs as a hash map with the given number of
+        /// buckets in its hash index.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `buckets` must be a valid bucket count for
+        ///   [`build_and_serialize_with_buckets`](ArchivedHashIndex::build_and_serialize_with_buckets).
+        unsafe fn serialize_from_iter_buckets<'a, KU, VU, S, I>(
+            iter: I,
+            buckets: usize,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, HashIndexBuildError<S::Error>>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
             let len = iter.len();
 
+            #[cfg(feature = "hash_map_insertion_order")]
+            let mut order = InsertionOrder::new(serializer, len)?;
+            #[cfg(feature = "hash_map_insertion_order")]
+            let iter = order.track(iter);
+
             let mut entries = ScratchVec::new(serializer, len)?;
             entries.set_len(len);
-            let index_resolver =
-                ArchivedHashIndex::build_and_serialize(iter, serializer, &mut entries)?;
+            let index_resolver = ArchivedHashIndex::<H>::build_and_serialize_with_buckets(
+                iter,
+                buckets,
+                serializer,
+                &mut entries,
+            )?;
+
+            #[cfg(feature = "hash_map_insertion_order")]
+            order.resolve::<H, _>(&entries);
+            let entries_pos = Self::serialize_entries(entries, serializer)?;
+
+            Ok(HashMapResolver {
+                index_resolver,
+                entries_pos,
+                #[cfg(feature = "hash_map_insertion_order")]
+                order_pos: order.serialize_and_free(serializer)?,
+                #[cfg(feature = "hash_map_tombstones")]
+                present_pos: serialize_presence::<Self, S>(len, serializer)?,
+            })
+        }
+
+        /// Serializes an iterator of key-value pairs as a hash map, checking that the keys are
+        /// unique.
+        ///
+        /// Unlike [`serialize_from_iter`](ArchivedHashMap::serialize_from_iter), this buffers the
+        /// keys and returns a [`DuplicateKeyError`] if any two of them are equal. Serializers must
+        /// have an error type satisfying `<S as Fallible>::Error: From<DuplicateKeyError>` to use
+        /// it.
+        pub fn serialize_from_iter_checked<'a, KU, VU, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<DuplicateKeyError>,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
+            unsafe {
+                let len = iter.len();
+
+                let mut pairs = ScratchVec::new(serializer, len)?;
+                for pair in iter {
+                    pairs.push(pair);
+                }
+
+                // Equal keys must have equal hashes, so only keys with the same hash need to be
+                // compared with each other.
+                let mut hashes = ScratchVec::new(serializer, len)?;
+                for (i, (key, _)) in pairs.iter().enumerate() {
+                    let mut hasher = H::make_hasher();
+                    key.hash(&mut hasher);
+                    hashes.push((hasher.finish(), i));
+                }
+                hashes.sort_unstable_by_key(|&(hash, _)| hash);
+
+                let mut has_duplicate = false;
+                let mut start = 0;
+                while start < hashes.len() && !has_duplicate {
+                    let hash = hashes[start].0;
+                    let end = start
+                        + hashes[start..]
+                            .iter()
+                            .take_while(|&&(h, _)| h == hash)
+                            .count();
+                    let run = &hashes[start..end];
+                    has_duplicate = run.iter().enumerate().any(|(i, &(_, a))| {
+                        run[i + 1..].iter().any(|&(_, b)| pairs[a].0 == pairs[b].0)
+                    });
+                    start = end;
+                }
+
+                hashes.free(serializer)?;
+
+                if has_duplicate {
+                    pairs.free(serializer)?;
+                    return Err(DuplicateKeyError.into());
+                }
+
+                let resolver = Self::serialize_from_iter(pairs.iter().copied(), serializer)?;
+                pairs.free(serializer)?;
+
+                Ok(resolver)
+            }
+        }
+
+        /// Serializes an iterator of values as a hash map, keying each value by a projection of it.
+        ///
+        /// Each value is serialized whole as the entry's value, and `key_fn` picks the key to
+        /// store alongside it (for example, `|record| &record.id`). This avoids collecting the
+        /// values into a separate map before serializing.
+        ///
+        /// # Safety
+        ///
+        /// The keys projected from the values must be unique.
+        #[inline]
+        pub unsafe fn serialize_from_iter_by_key<'a, KU, VU, S, I, F>(
+            iter: I,
+            key_fn: F,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = &'a VU>,
+            F: Fn(&'a VU) -> &'a KU,
+        {
+            Self::serialize_from_iter(iter.map(|value| (key_fn(value), value)), serializer)
+        }
+
+        /// Serializes an iterator of values as a hash map, keying each value by a projection of it
+        /// and checking that the projected keys are unique.
+        ///
+        /// This is the checked version of
+        /// [`serialize_from_iter_by_key`](ArchivedHashMap::serialize_from_iter_by_key). See
+        /// [`serialize_from_iter_checked`](ArchivedHashMap::serialize_from_iter_checked) for the
+        /// requirements on the serializer.
+        #[inline]
+        pub fn serialize_from_iter_by_key_checked<'a, KU, VU, S, I, F>(
+            iter: I,
+            key_fn: F,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<DuplicateKeyError>,
+            I: ExactSizeIterator<Item = &'a VU>,
+            F: Fn(&'a VU) -> &'a KU,
+        {
+            Self::serialize_from_iter_checked(iter.map(|value| (key_fn(value), value)), serializer)
+        }
+
+        /// Serializes an iterator of keys and lazily-produced values as a hash map.
+        ///
+        /// Each value is materialized with [`LazySerialize::materialize`] only once this map is
+        /// being serialized, so the values don't need to exist until they're needed. The
+        /// materialized values are held in scratch space until the map has been resolved.
+        /// References to values that already exist implement `LazySerialize`, so an iterator like
+        /// `map.iter()` can also be used.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter_lazy<'a, KU, VL, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VL: LazySerialize<S>,
+            VL::Value: Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, VL)>,
+        {
+            let len = iter.len();
+
+            let mut values = ScratchVec::new(serializer, len)?;
+            let mut keys = ScratchVec::new(serializer, len)?;
+            for (key, value) in iter {
+                keys.push(key);
+                values.push(value.materialize());
+            }
+
+            let resolver = Self::serialize_from_iter(
+                keys.iter().copied().zip(values.iter().map(Borrow::borrow)),
+                serializer,
+            )?;
+
+            keys.free(serializer)?;
+            values.free(serializer)?;
+
+            Ok(resolver)
+        }
+
+        /// Serializes an iterator of owned key-value pairs as a hash map.
+        ///
+        /// This is like [`serialize_from_iter`](ArchivedHashMap::serialize_from_iter), but for
+        /// iterators that produce their keys and values by value. The pairs are held in scratch
+        /// space until the map has been serialized, so they don't need to be collected first.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter_owned<KU, VU, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, S::Error>
+        where
+            KU: Serialize<S, Archived = K> + Hash + Eq,
+            VU: Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (KU, VU)>,
+        {
+            let mut pairs = ScratchVec::new(serializer, iter.len())?;
+            for pair in iter {
+                pairs.push(pair);
+            }
+
+            let resolver = Self::serialize_from_iter(
+                pairs.iter().map(|(key, value)| (key, value)),
+                serializer,
+            )?;
+
+            pairs.free(serializer)?;
+
+            Ok(resolver)
+        }
+
+        /// Serializes the entries placed by a hash index and frees the scratch space holding them.
+        /// Returns the position of the serialized entries.
+        ///
+        /// # Safety
+        ///
+        /// `entries` must be fully initialized by building a hash index.
+        unsafe fn serialize_entries<'a, KU, VU, S>(
+            entries: ScratchVec<MaybeUninit<(&'a KU, &'a VU)>>,
+            serializer: &mut S,
+        ) -> Result<usize, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K>,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+        {
             let mut entries = entries.assume_init();
 
             // Serialize entries
-            let mut resolvers = ScratchVec::new(serializer, len)?;
+            let mut resolvers = ScratchVec::new(serializer, entries.len())?;
             for (key, value) in entries.iter() {
                 resolvers.push((key.serialize(serializer)?, value.serialize(serializer)?));
             }
@@ -261,24 +985,206 @@ const _: () = {
             resolvers.free(serializer)?;
             entries.free(serializer)?;
 
+            Ok(entries_pos)
+        }
+    }
+
+    /// Writes a presence bitset with every entry marked present, then pads the serializer to the
+    /// alignment of `T` so the hash map can be resolved right after it. Returns the position of
+    /// the written bitset.
+    #[cfg(feature = "hash_map_tombstones")]
+    fn serialize_presence<T, S: Serializer + ?Sized>(
+        len: usize,
+        serializer: &mut S,
+    ) -> Result<usize, S::Error> {
+        const ALL_PRESENT: [u8; 64] = [u8::MAX; 64];
+
+        let present_pos = serializer.pos();
+        let mut full = len / 8;
+        while full > 0 {
+            let chunk = full.min(ALL_PRESENT.len());
+            serializer.write(&ALL_PRESENT[..chunk])?;
+            full -= chunk;
+        }
+        if len % 8 != 0 {
+            serializer.write(&[(1 << (len % 8)) - 1])?;
+        }
+        serializer.align_for::<T>()?;
+
+        Ok(present_pos)
+    }
+
+    /// Records the order that entries are serialized in so it can be written after the entries.
+    #[cfg(feature = "hash_map_insertion_order")]
+    struct InsertionOrder<'a, KU> {
+        keys: ScratchVec<(u64, &'a KU, u32)>,
+        order: ScratchVec<Archived<u32>>,
+    }
+
+    #[cfg(feature = "hash_map_insertion_order")]
+    impl<'a, KU: Hash + Eq> InsertionOrder<'a, KU> {
+        unsafe fn new<S: ScratchSpace + ?Sized>(
+            serializer: &mut S,
+            len: usize,
+        ) -> Result<Self, S::Error> {
+            let keys = ScratchVec::new(serializer, len)?;
+            let mut order = ScratchVec::new(serializer, len)?;
+            order.set_len(len);
+            Ok(Self { keys, order })
+        }
+
+        /// Records each key and its position as it is yielded from the iterator.
+        fn track<'b, VU, I>(
+            &'b mut self,
+            iter: I,
+        ) -> impl ExactSizeIterator<Item = (&'a KU, &'a VU)> + 'b
+        where
+            VU: 'a,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)> + 'b,
+        {
+            let keys = &mut self.keys;
+            iter.enumerate().map(move |(i, (key, value))| {
+                keys.push((0, key, i as u32));
+                (key, value)
+            })
+        }
+
+        /// Maps the position of each key in the iterator to its index in the entries.
+        ///
+        /// Keys are matched by value. The recorded keys are sorted by hash so that only the keys
+        /// with the same hash as an entry's key need to be compared with it.
+        ///
+        /// # Safety
+        ///
+        /// `entries` must be fully initialized by building a hash index from a tracked iterator.
+        unsafe fn resolve<H: ArchiveHasher, VU>(&mut self, entries: &[MaybeUninit<(&KU, &VU)>]) {
+            let hash = |key: &KU| {
+                let mut hasher = H::make_hasher();
+                key.hash(&mut hasher);
+                hasher.finish()
+            };
+
+            for entry in self.keys.iter_mut() {
+                entry.0 = hash(entry.1);
+            }
+            self.keys.sort_unstable_by_key(|&(hash, _, _)| hash);
+
+            for (i, entry) in entries.iter().enumerate() {
+                let key = (*entry.as_ptr()).0;
+                let hash = hash(key);
+                let start = self.keys.partition_point(|&(h, _, _)| h < hash);
+                let position = self.keys[start..]
+                    .iter()
+                    .take_while(|&&(h, _, _)| h == hash)
+                    .find(|&&(_, k, _)| k == key)
+                    .map(|&(_, _, position)| position)
+                    .unwrap();
+                self.order[position as usize] = to_archived!(i as u32);
+            }
+        }
+
+        /// Writes the insertion order and frees the scratch space. Returns the position of the
+        /// written insertion order.
+        unsafe fn serialize_and_free<S: Serializer + ScratchSpace + ?Sized>(
+            self,
+            serializer: &mut S,
+        ) -> Result<usize, S::Error> {
+            let order_pos = serializer.align_for::<Archived<u32>>()?;
+            serializer.write(core::slice::from_raw_parts(
+                self.order.as_ptr().cast::<u8>(),
+                self.order.len() * core::mem::size_of::<Archived<u32>>(),
+            ))?;
+
+            self.order.free(serializer)?;
+            self.keys.free(serializer)?;
+
+            Ok(order_pos)
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    impl<K, V, H: ArchiveHasher> ArchivedHashMap<K, V, H> {
+        /// Serializes an iterator of key-value pairs as a hash map, hashing the keys in parallel.
+        ///
+        /// The serialized bytes are identical to those written by
+        /// [`serialize_from_iter`](ArchivedHashMap::serialize_from_iter). Only hashing the keys
+        /// into the buckets of the hash index is parallelized; keys and values are still serialized
+        /// sequentially.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter_par<'a, KU, VU, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver<H>, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq + Sync,
+            VU: 'a + Serialize<S, Archived = V> + Sync,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
+            let len = iter.len();
+
+            #[cfg(feature = "hash_map_insertion_order")]
+            let mut order = InsertionOrder::new(serializer, len)?;
+            #[cfg(feature = "hash_map_insertion_order")]
+            let iter = order.track(iter);
+
+            let mut entries = ScratchVec::new(serializer, len)?;
+            entries.set_len(len);
+            let index_resolver =
+                ArchivedHashIndex::<H>::build_and_serialize_par(iter, serializer, &mut entries)?;
+
+            #[cfg(feature = "hash_map_insertion_order")]
+            order.resolve::<H, _>(&entries);
+            let entries_pos = Self::serialize_entries(entries, serializer)?;
+
             Ok(HashMapResolver {
                 index_resolver,
                 entries_pos,
+                #[cfg(feature = "hash_map_insertion_order")]
+                order_pos: order.serialize_and_free(serializer)?,
+                #[cfg(feature = "hash_map_tombstones")]
+                present_pos: serialize_presence::<Self, S>(len, serializer)?,
             })
         }
     }
 };
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedHashMap<K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, H: ArchiveHasher> fmt::Debug for ArchivedHashMap<K, V, H> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
 }
 
-impl<K: Hash + Eq, V: Eq> Eq for ArchivedHashMap<K, V> {}
+impl<K: Hash + Eq, V: Eq, H: ArchiveHasher> Eq for ArchivedHashMap<K, V, H> {}
 
-impl<K: Eq + Hash + Borrow<Q>, Q: Eq + Hash + ?Sized, V> Index<&'_ Q> for ArchivedHashMap<K, V> {
+impl<K: Hash, V: Hash, H: ArchiveHasher> Hash for ArchivedHashMap<K, V, H> {
+    /// Hashes the entries of the hash map independently of their order.
+    ///
+    /// Each entry is hashed separately with the hasher of the hash map and the results are summed,
+    /// so maps that compare equal hash the same regardless of where their entries were placed.
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        let sum = self.iter().fold(0u64, |sum, (key, value)| {
+            let mut hasher = H::make_hasher();
+            key.hash(&mut hasher);
+            value.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        state.write_usize(self.len());
+        state.write_u64(sum);
+    }
+}
+
+impl<K, Q, V, H> Index<&'_ Q> for ArchivedHashMap<K, V, H>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    H: ArchiveHasher,
+{
     type Output = V;
 
     #[inline]
@@ -287,21 +1193,51 @@ impl<K: Eq + Hash + Borrow<Q>, Q: Eq + Hash + ?Sized, V> Index<&'_ Q> for Archiv
     }
 }
 
-impl<K: Hash + Eq, V: PartialEq> PartialEq for ArchivedHashMap<K, V> {
+impl<K: Hash + Eq, V: PartialEq, H: ArchiveHasher> PartialEq for ArchivedHashMap<K, V, H> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         if self.len() != other.len() {
             false
         } else {
             self.iter()
-                .all(|(key, value)| other.get(key).map_or(false, |v| *value == *v))
+                .all(|(key, value)| other.get(key) == Some(value))
+        }
+    }
+}
+
+/// The entries of a hash map that have not been tombstoned.
+#[derive(Clone, Copy)]
+struct Presence {
+    #[cfg(feature = "hash_map_tombstones")]
+    bits: *const u8,
+}
+
+impl Presence {
+    /// Returns whether the entry at the given index is present.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the number of entries in the hash map.
+    #[inline]
+    unsafe fn contains(&self, index: usize) -> bool {
+        #[cfg(not(feature = "hash_map_tombstones"))]
+        {
+            let _ = index;
+            true
+        }
+        #[cfg(feature = "hash_map_tombstones")]
+        {
+            *self.bits.add(index / 8) & (1 << (index % 8)) != 0
         }
     }
 }
 
 struct RawIter<'a, K, V> {
     current: *const Entry<K, V>,
+    index: usize,
+    capacity: usize,
     remaining: usize,
+    presence: Presence,
     _phantom: PhantomData<(&'a K, &'a V)>,
 }
 
@@ -309,10 +1245,13 @@ unsafe impl<'a, K, V> Send for RawIter<'a, K, V> {}
 
 impl<'a, K, V> RawIter<'a, K, V> {
     #[inline]
-    fn new(pairs: *const Entry<K, V>, len: usize) -> Self {
+    fn new(pairs: *const Entry<K, V>, len: usize, capacity: usize, presence: Presence) -> Self {
         Self {
             current: pairs,
-            remaining: len,
+            index: 0,
+            capacity,
+            remaining: len.min(capacity),
+            presence,
             _phantom: PhantomData,
         }
     }
@@ -327,8 +1266,22 @@ impl<'a, K, V> Iterator for RawIter<'a, K, V> {
             if self.remaining == 0 {
                 None
             } else {
+                // A corrupted length may claim more entries than are present, so stop at the end of
+                // the entries instead of reading past them
+                loop {
+                    if self.index >= self.capacity {
+                        self.remaining = 0;
+                        return None;
+                    }
+                    if self.presence.contains(self.index) {
+                        break;
+                    }
+                    self.current = self.current.add(1);
+                    self.index += 1;
+                }
                 let result = self.current;
                 self.current = self.current.add(1);
+                self.index += 1;
                 self.remaining -= 1;
                 Some(result)
             }
@@ -346,16 +1299,22 @@ impl<'a, K, V> FusedIterator for RawIter<'a, K, V> {}
 
 struct RawIterPin<'a, K, V> {
     current: *mut Entry<K, V>,
+    index: usize,
+    capacity: usize,
     remaining: usize,
+    presence: Presence,
     _phantom: PhantomData<(&'a K, Pin<&'a mut V>)>,
 }
 
 impl<'a, K, V> RawIterPin<'a, K, V> {
     #[inline]
-    fn new(pairs: *mut Entry<K, V>, len: usize) -> Self {
+    fn new(pairs: *mut Entry<K, V>, len: usize, capacity: usize, presence: Presence) -> Self {
         Self {
             current: pairs,
-            remaining: len,
+            index: 0,
+            capacity,
+            remaining: len.min(capacity),
+            presence,
             _phantom: PhantomData,
         }
     }
@@ -370,8 +1329,22 @@ impl<'a, K, V> Iterator for RawIterPin<'a, K, V> {
             if self.remaining == 0 {
                 None
             } else {
+                // A corrupted length may claim more entries than are present, so stop at the end of
+                // the entries instead of reading past them
+                loop {
+                    if self.index >= self.capacity {
+                        self.remaining = 0;
+                        return None;
+                    }
+                    if self.presence.contains(self.index) {
+                        break;
+                    }
+                    self.current = self.current.add(1);
+                    self.index += 1;
+                }
                 let result = self.current;
                 self.current = self.current.add(1);
+                self.index += 1;
                 self.remaining -= 1;
                 Some(result)
             }
@@ -439,6 +1412,71 @@ impl<'a, K, V> Iterator for IterPin<'a, K, V> {
 impl<K, V> ExactSizeIterator for IterPin<'_, K, V> {}
 impl<K, V> FusedIterator for IterPin<'_, K, V> {}
 
+/// An iterator over the mutable key-value pairs of a hash map with `Unpin` values.
+#[repr(transparent)]
+pub struct IterMut<'a, K, V> {
+    inner: RawIterPin<'a, K, V>,
+}
+
+impl<'a, K, V: Unpin> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| unsafe {
+            let pair = &mut *x;
+            (&pair.key, &mut pair.value)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V: Unpin> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V: Unpin> FusedIterator for IterMut<'_, K, V> {}
+
+/// An iterator over the key-value pairs of a hash map in the order that they were serialized in.
+#[cfg(feature = "hash_map_insertion_order")]
+pub struct IterInsertionOrder<'a, K, V> {
+    entries: *const Entry<K, V>,
+    order: core::slice::Iter<'a, Archived<u32>>,
+    presence: Presence,
+    remaining: usize,
+    _phantom: PhantomData<(&'a K, &'a V)>,
+}
+
+#[cfg(feature = "hash_map_insertion_order")]
+impl<'a, K, V> Iterator for IterInsertionOrder<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = from_archived!(*self.order.next()?) as usize;
+            unsafe {
+                if self.presence.contains(index) {
+                    self.remaining -= 1;
+                    let pair = &*self.entries.add(index);
+                    return Some((&pair.key, &pair.value));
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(feature = "hash_map_insertion_order")]
+impl<K, V> ExactSizeIterator for IterInsertionOrder<'_, K, V> {}
+#[cfg(feature = "hash_map_insertion_order")]
+impl<K, V> FusedIterator for IterInsertionOrder<'_, K, V> {}
+
 /// An iterator over the keys of a hash map.
 #[repr(transparent)]
 pub struct Keys<'a, K, V> {
@@ -465,6 +1503,51 @@ impl<'a, K, V> Iterator for Keys<'a, K, V> {
 impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
 impl<K, V> FusedIterator for Keys<'_, K, V> {}
 
+enum SortOrder<'a> {
+    Borrowed(&'a [usize]),
+    #[cfg(feature = "alloc")]
+    Owned(Box<[usize]>),
+}
+
+impl SortOrder<'_> {
+    #[inline]
+    fn as_slice(&self) -> &[usize] {
+        match self {
+            SortOrder::Borrowed(order) => order,
+            #[cfg(feature = "alloc")]
+            SortOrder::Owned(order) => order,
+        }
+    }
+}
+
+/// An iterator over the keys of a hash map in sorted order.
+pub struct SortedKeys<'a, K, V> {
+    entries: *const Entry<K, V>,
+    order: SortOrder<'a>,
+    pos: usize,
+    _phantom: PhantomData<(&'a K, &'a V)>,
+}
+
+impl<'a, K, V> Iterator for SortedKeys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = *self.order.as_slice().get(self.pos)?;
+        self.pos += 1;
+        unsafe { Some(&(*self.entries.add(index)).key) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.order.as_slice().len() - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for SortedKeys<'_, K, V> {}
+impl<K, V> FusedIterator for SortedKeys<'_, K, V> {}
+
 /// An iterator over the values of a hash map.
 #[repr(transparent)]
 pub struct Values<'a, K, V> {
@@ -517,8 +1600,205 @@ impl<'a, K, V> Iterator for ValuesPin<'a, K, V> {
 impl<K, V> ExactSizeIterator for ValuesPin<'_, K, V> {}
 impl<K, V> FusedIterator for ValuesPin<'_, K, V> {}
 
+/// An iterator over the mutable values of a hash map with `Unpin` values.
+#[repr(transparent)]
+pub struct ValuesMut<'a, K, V> {
+    inner: RawIterPin<'a, K, V>,
+}
+
+impl<'a, K, V: Unpin> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| unsafe {
+            let pair = &mut *x;
+            &mut pair.value
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V: Unpin> ExactSizeIterator for ValuesMut<'_, K, V> {}
+impl<K, V: Unpin> FusedIterator for ValuesMut<'_, K, V> {}
+
+/// A view into a single entry of a pinned hash map, which may either be occupied or vacant.
+///
+/// This is constructed from the [`entry_pin`](ArchivedHashMap::entry_pin) method on
+/// [`ArchivedHashMap`].
+pub enum EntryPin<'a, K, V> {
+    /// An occupied entry.
+    Occupied(OccupiedEntryPin<'a, K, V>),
+    /// A vacant entry.
+    Vacant,
+}
+
+impl<'a, K, V> EntryPin<'a, K, V> {
+    /// Returns `true` if the entry is occupied.
+    #[inline]
+    pub fn is_occupied(&self) -> bool {
+        matches!(self, EntryPin::Occupied(_))
+    }
+
+    /// Returns `true` if the entry is vacant.
+    #[inline]
+    pub fn is_vacant(&self) -> bool {
+        matches!(self, EntryPin::Vacant)
+    }
+}
+
+/// A view into an occupied entry of a pinned hash map.
+pub struct OccupiedEntryPin<'a, K, V> {
+    entry: &'a mut Entry<K, V>,
+}
+
+impl<'a, K, V> OccupiedEntryPin<'a, K, V> {
+    /// Gets a reference to the key in the entry.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.entry.key
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.entry.value
+    }
+
+    /// Gets a pinned mutable reference to the value in the entry.
+    #[inline]
+    pub fn get_pin(&mut self) -> Pin<&mut V> {
+        unsafe { Pin::new_unchecked(&mut self.entry.value) }
+    }
+
+    /// Converts the entry into a pinned mutable reference to its value with the lifetime of the
+    /// map.
+    #[inline]
+    pub fn into_pin(self) -> Pin<&'a mut V> {
+        unsafe { Pin::new_unchecked(&mut self.entry.value) }
+    }
+
+    /// Converts the entry into a reference to its key and a pinned mutable reference to its value
+    /// with the lifetime of the map.
+    #[inline]
+    pub fn into_key_value_pin(self) -> (&'a K, Pin<&'a mut V>) {
+        let entry = self.entry;
+        unsafe { (&entry.key, Pin::new_unchecked(&mut entry.value)) }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for EntryPin<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryPin::Occupied(entry) => f.debug_tuple("Occupied").field(entry).finish(),
+            EntryPin::Vacant => f.write_str("Vacant"),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OccupiedEntryPin<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntryPin")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// An error that occurs when serializing a hash map from an iterator that yields the same key more
+/// than once.
+///
+/// This is returned by `ArchivedHashMap::serialize_from_iter_checked`.
+#[derive(Debug)]
+pub struct DuplicateKeyError;
+
+impl fmt::Display for DuplicateKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duplicate key in hash map")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DuplicateKeyError {}
+
+/// An error that occurs when the length of an archived hash map doesn't match its entries.
+///
+/// This is returned by `ArchivedHashMap::check_len`.
+#[derive(Debug)]
+pub struct HashMapLengthError {
+    /// The length of the hash map
+    pub len: usize,
+}
+
+impl fmt::Display for HashMapLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hash map length {} does not match its entries", self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HashMapLengthError {}
+
+/// Options for building the hash index of an archived hash map.
+///
+/// These are passed to `ArchivedHashMap::serialize_from_iter_with`.
+#[cfg(feature = "hash_map_buckets")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HashMapBuildOptions {
+    /// The number of buckets in the hash index relative to the number of entries.
+    ///
+    /// Raising this above the default of `1.0` spreads keys out over more buckets so that fewer of
+    /// them collide, at the cost of an extra `u32` per bucket.
+    pub bucket_ratio: f32,
+}
+
+#[cfg(feature = "hash_map_buckets")]
+impl HashMapBuildOptions {
+    /// Returns the number of buckets to use for a hash map with `len` entries.
+    ///
+    /// Returns `None` if the bucket ratio is less than `1.0`, NaN, or infinite, or if it's so
+    /// large that the bucket count can't be archived.
+    #[inline]
+    pub fn buckets(&self, len: usize) -> Option<usize> {
+        // Fewer buckets than keys crowds keys into the same buckets, which can make searching for
+        // their displacements fail
+        if !self.bucket_ratio.is_finite() || self.bucket_ratio < 1.0 {
+            None
+        } else if len == 0 {
+            Some(0)
+        } else {
+            // Displacements are computed as a `u32` modulo the bucket count
+            let max = (crate::FixedUsize::MAX as u64).min(u32::MAX as u64);
+            let buckets = (len as f64 * self.bucket_ratio as f64).ceil();
+            if buckets > max as f64 {
+                None
+            } else {
+                Some(buckets as usize)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "hash_map_buckets")]
+impl Default for HashMapBuildOptions {
+    #[inline]
+    fn default() -> Self {
+        Self { bucket_ratio: 1.0 }
+    }
+}
+
 /// The resolver for archived hash maps.
-pub struct HashMapResolver {
-    index_resolver: HashIndexResolver,
+pub struct HashMapResolver<H = HashBuilder> {
+    index_resolver: HashIndexResolver<H>,
     entries_pos: usize,
+    #[cfg(feature = "hash_map_insertion_order")]
+    order_pos: usize,
+    #[cfg(feature = "hash_map_tombstones")]
+    present_pos: usize,
 }
@@ -2,7 +2,7 @@
 
 use crate::{
     collections::{
-        hash_index::validation::HashIndexError,
+        hash_index::{validation::HashIndexError, ArchiveHasher},
         hash_map::ArchivedHashMap,
         util::{validation::ArchivedEntryError, Entry},
         ArchivedHashIndex,
@@ -28,11 +28,28 @@ pub enum HashMapError<K, V, C> {
     LayoutError(LayoutError),
     /// An error occurred while checking the entries
     CheckEntryError(SliceCheckError<ArchivedEntryError<K, V>>),
-    /// A key is not located at the correct position
+    /// A key is not located at the correct position, meaning that the hash index does not map it
+    /// back to its own entry
     InvalidKeyPosition {
         /// The index of the key when iterating
         index: usize,
     },
+    /// An entry of the insertion order is out of bounds
+    #[cfg(feature = "hash_map_insertion_order")]
+    InvalidInsertionOrder {
+        /// The position in the insertion order
+        index: usize,
+        /// The out-of-bounds entry index
+        value: u32,
+    },
+    /// The number of live entries does not match the number of entries marked present
+    #[cfg(feature = "hash_map_tombstones")]
+    InvalidLiveCount {
+        /// The number of live entries
+        live: usize,
+        /// The number of entries marked present
+        present: usize,
+    },
     /// A bounds error occurred
     ContextError(C),
 }
@@ -46,6 +63,18 @@ impl<K: fmt::Display, V: fmt::Display, E: fmt::Display> fmt::Display for HashMap
             HashMapError::InvalidKeyPosition { index } => {
                 write!(f, "invalid key position: at index {}", index)
             }
+            #[cfg(feature = "hash_map_insertion_order")]
+            HashMapError::InvalidInsertionOrder { index, value } => write!(
+                f,
+                "invalid insertion order: value {} at index {}",
+                value, index
+            ),
+            #[cfg(feature = "hash_map_tombstones")]
+            HashMapError::InvalidLiveCount { live, present } => write!(
+                f,
+                "invalid live count: {} live entries but {} marked present",
+                live, present
+            ),
             HashMapError::ContextError(e) => e.fmt(f),
         }
     }
@@ -67,6 +96,10 @@ const _: () = {
                 HashMapError::LayoutError(e) => Some(e as &dyn Error),
                 HashMapError::CheckEntryError(e) => Some(e as &dyn Error),
                 HashMapError::InvalidKeyPosition { .. } => None,
+                #[cfg(feature = "hash_map_insertion_order")]
+                HashMapError::InvalidInsertionOrder { .. } => None,
+                #[cfg(feature = "hash_map_tombstones")]
+                HashMapError::InvalidLiveCount { .. } => None,
                 HashMapError::ContextError(e) => Some(e as &dyn Error),
             }
         }
@@ -107,10 +140,11 @@ impl<K, V, C> From<SliceCheckError<ArchivedEntryError<K, V>>> for HashMapError<K
     }
 }
 
-impl<K, V, C> CheckBytes<C> for ArchivedHashMap<K, V>
+impl<K, V, H, C> CheckBytes<C> for ArchivedHashMap<K, V, H>
 where
     K: CheckBytes<C> + Eq + Hash,
     V: CheckBytes<C>,
+    H: ArchiveHasher,
     C: ArchiveContext + ?Sized,
     C::Error: Error,
 {
@@ -140,12 +174,76 @@ where
             .pop_prefix_range(range)
             .map_err(HashMapError::ContextError)?;
 
+        // Every key must hash back to its own entry through the hash index. This is what keeps a
+        // crafted displacement from routing lookups to the wrong entry, so it is always checked.
         for (i, entry) in entries.iter().enumerate() {
             if index.index(&entry.key) != Some(i) {
                 return Err(HashMapError::InvalidKeyPosition { index: i });
             }
         }
 
+        #[cfg(feature = "hash_map_insertion_order")]
+        {
+            use crate::Archived;
+
+            Layout::array::<Archived<u32>>(index.len())?;
+
+            let order_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).order), context)?;
+            let order_ptr = context
+                .check_subtree_ptr::<[Archived<u32>]>(
+                    order_rel_ptr.base(),
+                    order_rel_ptr.offset(),
+                    index.len(),
+                )
+                .map_err(HashMapError::ContextError)?;
+
+            let range = context
+                .push_prefix_subtree(order_ptr)
+                .map_err(HashMapError::ContextError)?;
+            let order = <[Archived<u32>]>::check_bytes(order_ptr, context)?;
+            context
+                .pop_prefix_range(range)
+                .map_err(HashMapError::ContextError)?;
+
+            for (i, &value) in order.iter().enumerate() {
+                let value = from_archived!(value);
+                if value as usize >= index.len() {
+                    return Err(HashMapError::InvalidInsertionOrder { index: i, value });
+                }
+            }
+        }
+
+        #[cfg(feature = "hash_map_tombstones")]
+        {
+            #[allow(clippy::manual_div_ceil)]
+            let present_len = (index.len() + 7) / 8;
+            let present_rel_ptr =
+                RelPtr::manual_check_bytes(ptr::addr_of!((*value).present), context)?;
+            let present_ptr = context
+                .check_subtree_ptr::<[u8]>(
+                    present_rel_ptr.base(),
+                    present_rel_ptr.offset(),
+                    present_len,
+                )
+                .map_err(HashMapError::ContextError)?;
+
+            let range = context
+                .push_prefix_subtree(present_ptr)
+                .map_err(HashMapError::ContextError)?;
+            let present_bits = <[u8]>::check_bytes(present_ptr, context)?;
+            context
+                .pop_prefix_range(range)
+                .map_err(HashMapError::ContextError)?;
+
+            let present = (0..index.len())
+                .filter(|i| present_bits[i / 8] & (1 << (i % 8)) != 0)
+                .count();
+            let live = from_archived!(*ptr::addr_of!((*value).live)) as usize;
+            if live != present {
+                return Err(HashMapError::InvalidLiveCount { live, present });
+            }
+        }
+
         Ok(&*value)
     }
 }
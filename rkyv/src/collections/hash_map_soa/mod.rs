@@ -0,0 +1,281 @@
+//! Archived hash map implementation with separate key and value arrays.
+//!
+//! An [`ArchivedHashMapSoA`] uses the same hash index as an
+//! [`ArchivedHashMap`](crate::collections::ArchivedHashMap), but stores its keys and values in two
+//! separate arrays instead of one array of key-value entries. All of the keys are contiguous, so
+//! scanning the keys doesn't have to skip over the values.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    collections::hash_index::{ArchivedHashIndex, HashBuilder, HashIndexResolver},
+    RelPtr,
+};
+use core::{borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, iter::Zip, ops::Index, slice};
+
+/// An archived `HashMap` that stores its keys and values in separate arrays.
+///
+/// This has the same lookup API as an [`ArchivedHashMap`](crate::collections::ArchivedHashMap).
+/// The key at each position of [`keys_slice`](ArchivedHashMapSoA::keys_slice) is associated with
+/// the value at the same position of [`values_slice`](ArchivedHashMapSoA::values_slice).
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedHashMapSoA<K, V> {
+    index: ArchivedHashIndex,
+    keys: RelPtr<K>,
+    values: RelPtr<V>,
+}
+
+impl<K, V> ArchivedHashMapSoA<K, V> {
+    #[inline]
+    fn find<Q>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.index(k).and_then(|index| {
+            let key = unsafe { &*self.keys.as_ptr().add(index) };
+            if key.borrow() == k {
+                Some(index)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Gets the key-value pair associated with the given key.
+    #[inline]
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(k)
+            .map(|index| (&self.keys_slice()[index], &self.values_slice()[index]))
+    }
+
+    /// Returns whether a key is present in the hash map.
+    #[inline]
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(k).is_some()
+    }
+
+    /// Gets the value associated with the given key.
+    #[inline]
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(k).map(|index| &self.values_slice()[index])
+    }
+
+    /// Gets the hasher for this hash map.
+    #[inline]
+    pub fn hasher(&self) -> HashBuilder {
+        self.index.hasher()
+    }
+
+    /// Gets the number of items in the hash map.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns whether there are no items in the hash map.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the keys of the hash map as a contiguous slice.
+    ///
+    /// Keys are in the order chosen by the hash index, which is unrelated to the order they were
+    /// serialized in.
+    #[inline]
+    pub fn keys_slice(&self) -> &[K] {
+        unsafe { slice::from_raw_parts(self.keys.as_ptr(), self.len()) }
+    }
+
+    /// Gets the values of the hash map as a contiguous slice.
+    ///
+    /// Each value is at the same position as its key in [`keys_slice`](Self::keys_slice).
+    #[inline]
+    pub fn values_slice(&self) -> &[V] {
+        unsafe { slice::from_raw_parts(self.values.as_ptr(), self.len()) }
+    }
+
+    /// Gets an iterator over the key-value entries in the hash map.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.keys_slice().iter().zip(self.values_slice().iter()),
+        }
+    }
+
+    /// Gets an iterator over the keys in the hash map.
+    #[inline]
+    pub fn keys(&self) -> slice::Iter<'_, K> {
+        self.keys_slice().iter()
+    }
+
+    /// Gets an iterator over the values in the hash map.
+    #[inline]
+    pub fn values(&self) -> slice::Iter<'_, V> {
+        self.values_slice().iter()
+    }
+
+    /// Resolves an archived hash map from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a hash map
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: HashMapSoAResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.index);
+        ArchivedHashIndex::resolve_from_len(len, pos + fp, resolver.index_resolver, fo);
+
+        let (fp, fo) = out_field!(out.keys);
+        RelPtr::emplace(pos + fp, resolver.keys_pos, fo);
+
+        let (fp, fo) = out_field!(out.values);
+        RelPtr::emplace(pos + fp, resolver.values_pos, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        ScratchVec, Serialize,
+    };
+
+    impl<K, V> ArchivedHashMapSoA<K, V> {
+        /// Serializes an iterator of key-value pairs as a hash map with separate key and value
+        /// arrays.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter<'a, KU, VU, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<HashMapSoAResolver, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
+            let len = iter.len();
+
+            let mut entries = ScratchVec::new(serializer, len)?;
+            entries.set_len(len);
+            let index_resolver =
+                ArchivedHashIndex::build_and_serialize(iter, serializer, &mut entries)?;
+            let mut entries = entries.assume_init();
+
+            // Each array is written right after the dependencies of its elements so that the
+            // archive can be validated one array at a time
+            let mut resolvers = ScratchVec::new(serializer, len)?;
+            for (key, _) in entries.iter() {
+                resolvers.push(key.serialize(serializer)?);
+            }
+            let keys_pos = serializer.align_for::<K>()?;
+            for ((key, _), resolver) in entries.iter().zip(resolvers.drain(..)) {
+                serializer.resolve_aligned(*key, resolver)?;
+            }
+            resolvers.free(serializer)?;
+
+            let mut resolvers = ScratchVec::new(serializer, len)?;
+            for (_, value) in entries.iter() {
+                resolvers.push(value.serialize(serializer)?);
+            }
+            let values_pos = serializer.align_for::<V>()?;
+            for ((_, value), resolver) in entries.drain(..).zip(resolvers.drain(..)) {
+                serializer.resolve_aligned(value, resolver)?;
+            }
+            resolvers.free(serializer)?;
+
+            entries.free(serializer)?;
+
+            Ok(HashMapSoAResolver {
+                index_resolver,
+                keys_pos,
+                values_pos,
+            })
+        }
+    }
+};
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedHashMapSoA<K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for ArchivedHashMapSoA<K, V> {}
+
+impl<K, Q, V> Index<&'_ Q> for ArchivedHashMapSoA<K, V>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+{
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for ArchivedHashMapSoA<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+/// An iterator over the key-value pairs of a hash map with separate key and value arrays.
+pub struct Iter<'a, K, V> {
+    inner: Zip<slice::Iter<'a, K>, slice::Iter<'a, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// The resolver for an [`ArchivedHashMapSoA`].
+pub struct HashMapSoAResolver {
+    index_resolver: HashIndexResolver,
+    keys_pos: usize,
+    values_pos: usize,
+}
@@ -0,0 +1,153 @@
+//! Validation implementation for ArchivedHashMapSoA.
+
+use crate::{
+    collections::{
+        hash_index::validation::HashIndexError, hash_map_soa::ArchivedHashMapSoA, ArchivedHashIndex,
+    },
+    validation::ArchiveContext,
+    RelPtr,
+};
+use bytecheck::{CheckBytes, Error, SliceCheckError};
+use core::{
+    alloc::{Layout, LayoutError},
+    convert::Infallible,
+    fmt,
+    hash::Hash,
+    ptr,
+};
+
+/// Errors that can occur while checking an archived hash map with separate key and value arrays.
+#[derive(Debug)]
+pub enum HashMapSoAError<K, V, C> {
+    /// An error occurred while checking the hash index
+    HashIndexError(HashIndexError<C>),
+    /// An error occurred while checking the layouts of the keys or values
+    LayoutError(LayoutError),
+    /// An error occurred while checking the keys
+    CheckKeyError(SliceCheckError<K>),
+    /// An error occurred while checking the values
+    CheckValueError(SliceCheckError<V>),
+    /// A key is not located at the correct position, meaning that the hash index does not map it
+    /// back to its own position
+    InvalidKeyPosition {
+        /// The index of the key when iterating
+        index: usize,
+    },
+    /// A bounds error occurred
+    ContextError(C),
+}
+
+impl<K: fmt::Display, V: fmt::Display, E: fmt::Display> fmt::Display for HashMapSoAError<K, V, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashMapSoAError::HashIndexError(e) => write!(f, "hash index check error: {}", e),
+            HashMapSoAError::LayoutError(e) => write!(f, "layout error: {}", e),
+            HashMapSoAError::CheckKeyError(e) => write!(f, "key check error: {}", e),
+            HashMapSoAError::CheckValueError(e) => write!(f, "value check error: {}", e),
+            HashMapSoAError::InvalidKeyPosition { index } => {
+                write!(f, "invalid key position: at index {}", index)
+            }
+            HashMapSoAError::ContextError(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<K, V, C> Error for HashMapSoAError<K, V, C>
+    where
+        K: Error + 'static,
+        V: Error + 'static,
+        C: Error + 'static,
+    {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                HashMapSoAError::HashIndexError(e) => Some(e as &dyn Error),
+                HashMapSoAError::LayoutError(e) => Some(e as &dyn Error),
+                HashMapSoAError::CheckKeyError(e) => Some(e as &dyn Error),
+                HashMapSoAError::CheckValueError(e) => Some(e as &dyn Error),
+                HashMapSoAError::InvalidKeyPosition { .. } => None,
+                HashMapSoAError::ContextError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+impl<K, V, C> From<Infallible> for HashMapSoAError<K, V, C> {
+    #[inline]
+    fn from(_: Infallible) -> Self {
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+}
+
+impl<K, V, C> From<HashIndexError<C>> for HashMapSoAError<K, V, C> {
+    #[inline]
+    fn from(e: HashIndexError<C>) -> Self {
+        Self::HashIndexError(e)
+    }
+}
+
+impl<K, V, C> From<LayoutError> for HashMapSoAError<K, V, C> {
+    #[inline]
+    fn from(e: LayoutError) -> Self {
+        Self::LayoutError(e)
+    }
+}
+
+impl<K, V, C> CheckBytes<C> for ArchivedHashMapSoA<K, V>
+where
+    K: CheckBytes<C> + Eq + Hash,
+    V: CheckBytes<C>,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = HashMapSoAError<K::Error, V::Error, C::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let index = ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).index), context)?;
+
+        // Keys
+        Layout::array::<K>(index.len())?;
+        let keys_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).keys), context)?;
+        let keys_ptr = context
+            .check_subtree_ptr::<[K]>(keys_rel_ptr.base(), keys_rel_ptr.offset(), index.len())
+            .map_err(HashMapSoAError::ContextError)?;
+
+        let range = context
+            .push_prefix_subtree(keys_ptr)
+            .map_err(HashMapSoAError::ContextError)?;
+        let keys = <[K]>::check_bytes(keys_ptr, context).map_err(HashMapSoAError::CheckKeyError)?;
+        context
+            .pop_prefix_range(range)
+            .map_err(HashMapSoAError::ContextError)?;
+
+        // Values
+        Layout::array::<V>(index.len())?;
+        let values_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).values), context)?;
+        let values_ptr = context
+            .check_subtree_ptr::<[V]>(values_rel_ptr.base(), values_rel_ptr.offset(), index.len())
+            .map_err(HashMapSoAError::ContextError)?;
+
+        let range = context
+            .push_prefix_subtree(values_ptr)
+            .map_err(HashMapSoAError::ContextError)?;
+        <[V]>::check_bytes(values_ptr, context).map_err(HashMapSoAError::CheckValueError)?;
+        context
+            .pop_prefix_range(range)
+            .map_err(HashMapSoAError::ContextError)?;
+
+        // Every key must hash back to its own position through the hash index
+        for (i, key) in keys.iter().enumerate() {
+            if index.index(key) != Some(i) {
+                return Err(HashMapSoAError::InvalidKeyPosition { index: i });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
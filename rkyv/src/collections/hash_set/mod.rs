@@ -0,0 +1,455 @@
+//! Archived hash set implementation.
+//!
+//! During archiving, hashsets are built into minimal perfect hashsets using
+//! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+
+#[cfg(all(feature = "validation", feature = "hash_set_keys_only"))]
+pub mod validation;
+
+#[cfg(not(feature = "hash_set_keys_only"))]
+use crate::collections::hash_map::{ArchivedHashMap, HashMapResolver, Keys};
+use crate::collections::{
+    hash_index::{ArchivedHashIndex, HashBuilder},
+    hash_map::PREFETCH_DISTANCE,
+    util::prefetch,
+};
+#[cfg(feature = "hash_set_keys_only")]
+use crate::{collections::hash_index::HashIndexResolver, RelPtr};
+#[cfg(feature = "alloc")]
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    Serialize,
+};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+};
+#[cfg(feature = "hash_set_keys_only")]
+use core::{iter::FusedIterator, slice};
+
+/// An archived `HashSet`. This is a wrapper around a hash map with the same key and a value of
+/// `()`.
+///
+/// With the `hash_set_keys_only` feature, hash sets are stored without the hash map instead. See
+/// below for that layout.
+#[cfg(not(feature = "hash_set_keys_only"))]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedHashSet<K>(ArchivedHashMap<K, ()>);
+
+/// An archived `HashSet`.
+///
+/// The keys of the set are stored in a single array indexed by a minimal perfect hash, the same
+/// way that [`ArchivedHashMap`](crate::collections::ArchivedHashMap) stores its entries. Because it
+/// doesn't store any values, it also doesn't carry any of the per-entry data that archived hash
+/// maps may be configured to store.
+#[cfg(feature = "hash_set_keys_only")]
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedHashSet<K> {
+    index: ArchivedHashIndex,
+    keys: RelPtr<K>,
+}
+
+impl<K> ArchivedHashSet<K> {
+    /// Gets the number of items in the hash set.
+    #[cfg(not(feature = "hash_set_keys_only"))]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Gets the number of items in the hash set.
+    #[cfg(feature = "hash_set_keys_only")]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    #[cfg(feature = "hash_set_keys_only")]
+    #[inline]
+    fn keys(&self) -> &[K] {
+        unsafe { slice::from_raw_parts(self.keys.as_ptr(), self.len()) }
+    }
+
+    #[inline]
+    fn hash_index(&self) -> &ArchivedHashIndex {
+        #[cfg(not(feature = "hash_set_keys_only"))]
+        {
+            self.0.hash_index()
+        }
+        #[cfg(feature = "hash_set_keys_only")]
+        {
+            &self.index
+        }
+    }
+
+    /// Gets a pointer to the slot at an index returned by the hash index.
+    #[inline]
+    fn slot_ptr(&self, i: usize) -> *const u8 {
+        #[cfg(not(feature = "hash_set_keys_only"))]
+        {
+            self.0.entries_slice().as_ptr().wrapping_add(i).cast()
+        }
+        #[cfg(feature = "hash_set_keys_only")]
+        {
+            self.keys.as_ptr().wrapping_add(i).cast()
+        }
+    }
+
+    /// Checks whether the key at an index returned by the hash index is the given key.
+    #[inline]
+    fn check_index<Q>(&self, k: &Q, i: usize) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        #[cfg(not(feature = "hash_set_keys_only"))]
+        {
+            self.0.check_index(k, i)
+        }
+        #[cfg(feature = "hash_set_keys_only")]
+        {
+            if self.keys()[i].borrow() == k {
+                Some(i)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Looks up each of the given keys in order and calls `f` with each key and whether it was
+    /// found, stopping early if `f` returns `false`.
+    ///
+    /// Lookups are pipelined the same way as [`ArchivedHashMap::get_batch`]: each key's
+    /// displacement and then its slot are prefetched [`PREFETCH_DISTANCE`] keys ahead of when they
+    /// are read.
+    ///
+    /// [`ArchivedHashMap::get_batch`]: crate::collections::ArchivedHashMap::get_batch
+    fn find_batch<'q, Q, F>(&self, keys: &[&'q Q], mut f: F) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnMut(&'q Q, bool) -> bool,
+    {
+        const D: usize = PREFETCH_DISTANCE;
+
+        let len = keys.len();
+        let mut hashes = [0u64; D];
+        let mut indices = [None; D];
+
+        // Each key moves through three stages, each one running D keys behind the last. The
+        // stages run in reverse so that each one reads its slot of the ring buffers before the
+        // stage ahead of it overwrites the slot.
+        for i in 0..len + 2 * D {
+            // Compare the key to its slot
+            if let Some(j) = i.checked_sub(2 * D).filter(|&j| j < len) {
+                let found = indices[j % D]
+                    .and_then(|index| self.check_index(keys[j], index))
+                    .is_some();
+                if !f(keys[j], found) {
+                    return false;
+                }
+            }
+
+            // Find the index of the key and prefetch its slot
+            if let Some(j) = i.checked_sub(D).filter(|&j| j < len) {
+                let index = self.hash_index().index_with_hash(keys[j], hashes[j % D]);
+                if let Some(index) = index {
+                    prefetch(self.slot_ptr(index));
+                }
+                indices[j % D] = index;
+            }
+
+            // Hash the key and prefetch its displacement
+            if i < len {
+                let mut hasher = self.hasher();
+                keys[i].hash(&mut hasher);
+                let hash = hasher.finish();
+                self.hash_index().prefetch_with_hash(hash);
+                hashes[i % D] = hash;
+            }
+        }
+
+        true
+    }
+
+    /// Gets the key corresponding to the given key in the hash set.
+    #[inline]
+    pub fn get<Q>(&self, k: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        #[cfg(not(feature = "hash_set_keys_only"))]
+        {
+            self.0.get_key_value(k).map(|(k, _)| k)
+        }
+        #[cfg(feature = "hash_set_keys_only")]
+        {
+            self.index
+                .index(k)
+                .and_then(|i| self.check_index(k, i))
+                .map(|i| &self.keys()[i])
+        }
+    }
+
+    /// Returns whether the given key is in the hash set.
+    #[inline]
+    pub fn contains<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(k).is_some()
+    }
+
+    /// Returns whether all of the given keys are in the hash set.
+    ///
+    /// This returns the same result as calling [`contains`](ArchivedHashSet::contains) for each
+    /// key, but prefetches the memory for upcoming keys so that lookups overlap instead of waiting
+    /// on each other. It stops at the first key that is missing, and returns `true` if `keys` is
+    /// empty.
+    pub fn contains_all<Q>(&self, keys: &[&Q]) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_batch(keys, |_, found| found)
+    }
+
+    /// Returns the given keys that are not in the hash set, in the order they were given.
+    ///
+    /// Like [`contains_all`](ArchivedHashSet::contains_all), this prefetches the memory for
+    /// upcoming keys so that lookups overlap.
+    #[cfg(feature = "alloc")]
+    pub fn missing<'q, Q>(&self, keys: &[&'q Q]) -> Vec<&'q Q>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut result = Vec::new();
+        self.find_batch(keys, |key, found| {
+            if !found {
+                result.push(key);
+            }
+            true
+        });
+        result
+    }
+
+    /// Gets the hasher for this hash set.
+    #[inline]
+    pub fn hasher(&self) -> HashBuilder {
+        self.hash_index().hasher()
+    }
+
+    /// Returns whether there are no items in the hash set.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets an iterator over the keys of the underlying hash map.
+    #[cfg(not(feature = "hash_set_keys_only"))]
+    #[inline]
+    pub fn iter(&self) -> Keys<'_, K, ()> {
+        self.0.keys()
+    }
+
+    /// Gets an iterator over the keys of the hash set.
+    #[cfg(feature = "hash_set_keys_only")]
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter {
+            inner: self.keys().iter(),
+        }
+    }
+
+    /// Resolves an archived hash set from the given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a hash set
+    #[cfg(not(feature = "hash_set_keys_only"))]
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: HashSetResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.0);
+        ArchivedHashMap::resolve_from_len(len, pos + fp, resolver.0, fo);
+    }
+
+    /// Resolves an archived hash set from the given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a hash set
+    #[cfg(feature = "hash_set_keys_only")]
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: HashSetResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.index);
+        ArchivedHashIndex::resolve_from_len(len, pos + fp, resolver.index_resolver, fo);
+
+        let (fp, fo) = out_field!(out.keys);
+        RelPtr::emplace(pos + fp, resolver.keys_pos, fo);
+    }
+
+    /// Serializes an iterator of keys as a hash set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no displacement can be found for one of the buckets of the hash index.
+    ///
+    /// # Safety
+    ///
+    /// The keys returned by the iterator must be unique.
+    #[cfg(all(feature = "alloc", not(feature = "hash_set_keys_only")))]
+    #[inline]
+    pub unsafe fn serialize_from_iter<'a, KU, S, I>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<HashSetResolver, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+        S: Serializer + ScratchSpace + ?Sized,
+        I: ExactSizeIterator<Item = &'a KU>,
+    {
+        Ok(HashSetResolver(ArchivedHashMap::serialize_from_iter(
+            iter.map(|x| (x, &())),
+            serializer,
+        )?))
+    }
+
+    /// Serializes an iterator of keys as a hash set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no displacement can be found for one of the buckets of the hash index.
+    ///
+    /// # Safety
+    ///
+    /// The keys returned by the iterator must be unique.
+    #[cfg(all(feature = "alloc", feature = "hash_set_keys_only"))]
+    pub unsafe fn serialize_from_iter<'a, KU, S, I>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<HashSetResolver, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+        S: Serializer + ScratchSpace + ?Sized,
+        I: ExactSizeIterator<Item = &'a KU>,
+    {
+        use crate::ScratchVec;
+
+        let len = iter.len();
+
+        let mut entries = ScratchVec::new(serializer, len)?;
+        entries.set_len(len);
+        let index_resolver = ArchivedHashIndex::build_and_serialize(
+            iter.map(|k| (k, &())),
+            serializer,
+            &mut entries,
+        )?;
+        let mut entries = entries.assume_init();
+
+        // Serialize keys
+        let mut resolvers = ScratchVec::new(serializer, len)?;
+        for (key, _) in entries.iter() {
+            resolvers.push(key.serialize(serializer)?);
+        }
+
+        let keys_pos = serializer.align_for::<K>()?;
+        for ((key, _), resolver) in entries.drain(..).zip(resolvers.drain(..)) {
+            serializer.resolve_aligned(key, resolver)?;
+        }
+
+        // Free scratch vecs
+        resolvers.free(serializer)?;
+        entries.free(serializer)?;
+
+        Ok(HashSetResolver {
+            index_resolver,
+            keys_pos,
+        })
+    }
+}
+
+impl<K: fmt::Debug> fmt::Debug for ArchivedHashSet<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K> IntoIterator for &'a ArchivedHashSet<K> {
+    type Item = &'a K;
+    #[cfg(not(feature = "hash_set_keys_only"))]
+    type IntoIter = Keys<'a, K, ()>;
+    #[cfg(feature = "hash_set_keys_only")]
+    type IntoIter = Iter<'a, K>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Hash + Eq> PartialEq for ArchivedHashSet<K> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|key| other.contains(key))
+    }
+}
+
+impl<K: Hash + Eq> Eq for ArchivedHashSet<K> {}
+
+/// An iterator over the keys of an archived hash set.
+#[cfg(feature = "hash_set_keys_only")]
+pub struct Iter<'a, K> {
+    inner: slice::Iter<'a, K>,
+}
+
+#[cfg(feature = "hash_set_keys_only")]
+impl<'a, K> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "hash_set_keys_only")]
+impl<K> ExactSizeIterator for Iter<'_, K> {}
+#[cfg(feature = "hash_set_keys_only")]
+impl<K> FusedIterator for Iter<'_, K> {}
+
+/// The resolver for archived hash sets.
+#[cfg(not(feature = "hash_set_keys_only"))]
+pub struct HashSetResolver(HashMapResolver);
+
+/// The resolver for archived hash sets.
+#[cfg(feature = "hash_set_keys_only")]
+pub struct HashSetResolver {
+    index_resolver: HashIndexResolver,
+    keys_pos: usize,
+}
@@ -0,0 +1,137 @@
+//! Validation implementation for ArchivedHashSet.
+
+use crate::{
+    collections::{
+        hash_index::validation::HashIndexError, hash_set::ArchivedHashSet, ArchivedHashIndex,
+    },
+    validation::ArchiveContext,
+    RelPtr,
+};
+use bytecheck::{CheckBytes, Error, SliceCheckError};
+use core::{
+    alloc::{Layout, LayoutError},
+    convert::Infallible,
+    fmt,
+    hash::Hash,
+    ptr,
+};
+
+/// Errors that can occur while checking an archived hash set.
+#[derive(Debug)]
+pub enum HashSetError<K, C> {
+    /// An error occurred while checking the hash index
+    HashIndexError(HashIndexError<C>),
+    /// An error occurred while checking the layout of the keys
+    LayoutError(LayoutError),
+    /// An error occurred while checking the keys
+    CheckKeyError(SliceCheckError<K>),
+    /// A key is not located at the correct position, meaning that the hash index does not map it
+    /// back to itself
+    InvalidKeyPosition {
+        /// The index of the key when iterating
+        index: usize,
+    },
+    /// A bounds error occurred
+    ContextError(C),
+}
+
+impl<K: fmt::Display, E: fmt::Display> fmt::Display for HashSetError<K, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashSetError::HashIndexError(e) => write!(f, "hash index check error: {}", e),
+            HashSetError::LayoutError(e) => write!(f, "layout error: {}", e),
+            HashSetError::CheckKeyError(e) => write!(f, "key check error: {}", e),
+            HashSetError::InvalidKeyPosition { index } => {
+                write!(f, "invalid key position: at index {}", index)
+            }
+            HashSetError::ContextError(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<K, C> Error for HashSetError<K, C>
+    where
+        K: Error + 'static,
+        C: Error + 'static,
+    {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                HashSetError::HashIndexError(e) => Some(e as &dyn Error),
+                HashSetError::LayoutError(e) => Some(e as &dyn Error),
+                HashSetError::CheckKeyError(e) => Some(e as &dyn Error),
+                HashSetError::InvalidKeyPosition { .. } => None,
+                HashSetError::ContextError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+impl<K, C> From<Infallible> for HashSetError<K, C> {
+    fn from(_: Infallible) -> Self {
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+}
+
+impl<K, C> From<HashIndexError<C>> for HashSetError<K, C> {
+    #[inline]
+    fn from(e: HashIndexError<C>) -> Self {
+        Self::HashIndexError(e)
+    }
+}
+
+impl<K, C> From<LayoutError> for HashSetError<K, C> {
+    #[inline]
+    fn from(e: LayoutError) -> Self {
+        Self::LayoutError(e)
+    }
+}
+
+impl<K, C> From<SliceCheckError<K>> for HashSetError<K, C> {
+    #[inline]
+    fn from(e: SliceCheckError<K>) -> Self {
+        Self::CheckKeyError(e)
+    }
+}
+
+impl<K, C> CheckBytes<C> for ArchivedHashSet<K>
+where
+    K: CheckBytes<C> + Eq + Hash,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = HashSetError<K::Error, C::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let index = ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).index), context)?;
+        Layout::array::<K>(index.len())?;
+
+        let keys_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).keys), context)?;
+        let keys_ptr = context
+            .check_subtree_ptr::<[K]>(keys_rel_ptr.base(), keys_rel_ptr.offset(), index.len())
+            .map_err(HashSetError::ContextError)?;
+
+        let range = context
+            .push_prefix_subtree(keys_ptr)
+            .map_err(HashSetError::ContextError)?;
+        let keys = <[K]>::check_bytes(keys_ptr, context)?;
+        context
+            .pop_prefix_range(range)
+            .map_err(HashSetError::ContextError)?;
+
+        // Every key must hash back to its own position through the hash index
+        for (i, key) in keys.iter().enumerate() {
+            if index.index(key) != Some(i) {
+                return Err(HashSetError::InvalidKeyPosition { index: i });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
@@ -35,10 +35,10 @@ impl<K, V> ArchivedIndexMap<K, V> {
     }
 
     #[inline]
-    fn find<Q: ?Sized>(&self, k: &Q) -> Option<usize>
+    fn find<Q>(&self, k: &Q) -> Option<usize>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.index.index(k).and_then(|pivot_index| {
             let index = unsafe { self.pivot(pivot_index) };
@@ -53,10 +53,10 @@ impl<K, V> ArchivedIndexMap<K, V> {
 
     /// Returns whether a key is present in the hash map.
     #[inline]
-    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k).is_some()
     }
@@ -74,10 +74,10 @@ impl<K, V> ArchivedIndexMap<K, V> {
 
     /// Gets the value associated with the given key.
     #[inline]
-    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k)
             .map(|index| unsafe { &self.entry(index).value })
@@ -85,10 +85,10 @@ impl<K, V> ArchivedIndexMap<K, V> {
 
     /// Gets the index, key, and value associated with the given key.
     #[inline]
-    pub fn get_full<Q: ?Sized>(&self, k: &Q) -> Option<(usize, &K, &V)>
+    pub fn get_full<Q>(&self, k: &Q) -> Option<(usize, &K, &V)>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k).map(|index| {
             let entry = unsafe { &self.entry(index) };
@@ -109,20 +109,20 @@ impl<K, V> ArchivedIndexMap<K, V> {
 
     /// Gets the index of a key if it exists in the map.
     #[inline]
-    pub fn get_index_of<Q: ?Sized>(&self, key: &Q) -> Option<usize>
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(key)
     }
 
     /// Gets the key-value pair associated with the given key.
     #[inline]
-    pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.find(k).map(|index| {
             let entry = unsafe { &self.entry(index) };
@@ -1,19 +1,31 @@
 //! Archived versions of standard library containers.
 
+pub mod binary_heap;
+pub mod bit_set;
 pub mod btree_map;
 pub mod btree_set;
 pub mod hash_index;
 pub mod hash_map;
+pub mod hash_map_soa;
 pub mod hash_set;
 // TODO: move these into a separate crate when indexmap adds rkyv support
 pub mod index_map;
 pub mod index_set;
+pub mod packed_bools;
+pub mod sorted_map;
 pub mod util;
+pub mod vec_deque;
 
+pub use self::binary_heap::ArchivedBinaryHeap;
+pub use self::bit_set::ArchivedBitSet;
 pub use self::btree_map::ArchivedBTreeMap;
 pub use self::hash_index::ArchivedHashIndex;
 pub use self::hash_map::ArchivedHashMap;
+pub use self::hash_map_soa::ArchivedHashMapSoA;
 pub use self::hash_set::ArchivedHashSet;
 // TODO: move these into a separate crate when indexmap adds rkyv support
 pub use self::index_map::ArchivedIndexMap;
 pub use self::index_set::ArchivedIndexSet;
+pub use self::packed_bools::ArchivedPackedBools;
+pub use self::sorted_map::ArchivedSortedMap;
+pub use self::vec_deque::ArchivedVecDeque;
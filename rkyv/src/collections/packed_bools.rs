@@ -0,0 +1,230 @@
+//! An archived vector of bools packed into bits.
+//!
+//! Use the [`PackedBools`](crate::with::PackedBools) wrapper to archive a `Vec<bool>` as an
+//! [`ArchivedPackedBools`].
+
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Archived,
+};
+use core::{fmt, iter::FusedIterator};
+
+/// An archived vector of bools packed eight to a byte.
+///
+/// Bool `i` is stored in byte `i / 8` at bit `i % 8`. The bits of the last byte past the length of
+/// the vector are ignored.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedPackedBools {
+    bytes: ArchivedVec<u8>,
+    len: Archived<usize>,
+}
+
+impl ArchivedPackedBools {
+    /// Returns the number of bools in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns whether the vector contains no bools.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bool at `index`.
+    ///
+    /// Indices past the length of the vector are always `false`.
+    #[inline]
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len()
+            && matches!(self.bytes.get(index / 8), Some(byte) if byte & (1 << (index % 8)) != 0)
+    }
+
+    /// Gets an iterator over the bools in the vector.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            bools: self,
+            index: 0,
+        }
+    }
+
+    /// Returns the bytes that the bools are packed into.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Resolves an archived packed bool vector from a slice of bools.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `bools`
+    #[inline]
+    pub unsafe fn resolve_from_slice(
+        bools: &[bool],
+        pos: usize,
+        resolver: PackedBoolsResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.bytes);
+        #[allow(clippy::manual_div_ceil)]
+        ArchivedVec::resolve_from_len((bools.len() + 7) / 8, pos + fp, resolver.bytes, fo);
+        let (fp, fo) = out_field!(out.len);
+        bools.len().resolve(pos + fp, (), fo);
+    }
+
+    /// Serializes an archived packed bool vector from a slice of bools.
+    #[inline]
+    pub fn serialize_from_slice<S: ScratchSpace + Serializer + ?Sized>(
+        bools: &[bool],
+        serializer: &mut S,
+    ) -> Result<PackedBoolsResolver, S::Error> {
+        let bytes = bools.chunks(8).map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, &b)| byte | (b as u8) << i)
+        });
+        Ok(PackedBoolsResolver {
+            bytes: ArchivedVec::<u8>::serialize_from_iter::<u8, _, _, _>(bytes, serializer)?,
+        })
+    }
+}
+
+impl fmt::Debug for ArchivedPackedBools {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq<[bool]> for ArchivedPackedBools {
+    #[inline]
+    fn eq(&self, other: &[bool]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl<'a> IntoIterator for &'a ArchivedPackedBools {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The resolver for an [`ArchivedPackedBools`].
+pub struct PackedBoolsResolver {
+    bytes: VecResolver,
+}
+
+/// An iterator over the bools of an [`ArchivedPackedBools`].
+///
+/// This `struct` is created by the [`ArchivedPackedBools::iter`] function.
+pub struct Iter<'a> {
+    bools: &'a ArchivedPackedBools,
+    index: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.bools.len() {
+            let result = self.bools.get(self.index);
+            self.index += 1;
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.bools.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+impl FusedIterator for Iter<'_> {}
+
+/// Errors that can occur while checking an archived packed bool vector.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum PackedBoolsError<E> {
+    /// An error occurred while checking the bytes of the vector
+    CheckBytesError(E),
+    /// The length of the vector is longer than its bytes can hold
+    InvalidLength {
+        /// The length of the vector
+        len: usize,
+        /// The number of bytes that the bools are packed into
+        bytes: usize,
+    },
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for PackedBoolsError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackedBoolsError::CheckBytesError(e) => write!(f, "bytes check error: {}", e),
+            PackedBoolsError::InvalidLength { len, bytes } => write!(
+                f,
+                "invalid length: {} bools do not fit in {} bytes",
+                len, bytes
+            ),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for PackedBoolsError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                PackedBoolsError::CheckBytesError(e) => Some(e as &dyn Error),
+                PackedBoolsError::InvalidLength { .. } => None,
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::CheckBytes;
+    use core::ptr;
+
+    impl<C: ?Sized> CheckBytes<C> for ArchivedPackedBools
+    where
+        ArchivedVec<u8>: CheckBytes<C>,
+    {
+        type Error = PackedBoolsError<<ArchivedVec<u8> as CheckBytes<C>>::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let bytes = ArchivedVec::<u8>::check_bytes(ptr::addr_of!((*value).bytes), context)
+                .map_err(PackedBoolsError::CheckBytesError)?;
+            let len = from_archived!(*ptr::addr_of!((*value).len)) as usize;
+            if len > bytes.len().saturating_mul(8) {
+                return Err(PackedBoolsError::InvalidLength {
+                    len,
+                    bytes: bytes.len(),
+                });
+            }
+            Ok(&*value)
+        }
+    }
+};
@@ -0,0 +1,379 @@
+//! Archived sorted map implementation.
+//!
+//! Entries are stored in a single array sorted by key. Lookups use a binary search, and ranges of
+//! keys can be iterated without scanning the whole map.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{collections::util::Entry, Archive, Archived, RelPtr};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    iter::FusedIterator,
+    ops::{Bound, Index, RangeBounds},
+    slice,
+};
+
+/// An archived map with entries sorted by key.
+///
+/// The keys of the map are expected to have the same ordering when archived as they did when they
+/// were serialized.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedSortedMap<K, V> {
+    len: Archived<usize>,
+    entries: RelPtr<Entry<K, V>>,
+}
+
+impl<K, V> ArchivedSortedMap<K, V> {
+    #[inline]
+    fn entries(&self) -> &[Entry<K, V>] {
+        unsafe { slice::from_raw_parts(self.entries.as_ptr(), self.len()) }
+    }
+
+    #[inline]
+    fn find<Q: Ord + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.entries()
+            .binary_search_by(|entry| entry.key.borrow().cmp(key))
+            .ok()
+    }
+
+    /// Returns the number of entries with keys for which `pred` returns `true`, which must be a
+    /// prefix of the entries.
+    #[inline]
+    fn partition_point<F: Fn(&K) -> bool>(&self, pred: F) -> usize {
+        let result = self.entries().binary_search_by(|entry| {
+            if pred(&entry.key) {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        });
+        match result {
+            Ok(i) | Err(i) => i,
+        }
+    }
+
+    /// Returns whether a key is present in the map.
+    #[inline]
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns the key-value pair with the smallest key.
+    #[inline]
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.entries()
+            .first()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Gets the value associated with the given key.
+    #[inline]
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).map(|index| &self.entries()[index].value)
+    }
+
+    /// Gets a key-value pair by its index in sorted order.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.entries()
+            .get(index)
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Gets the key-value pair associated with the given key.
+    #[inline]
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).map(|index| {
+            let entry = &self.entries()[index];
+            (&entry.key, &entry.value)
+        })
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the key-value pairs of the map in order by key.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries().iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map in order.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Returns the key-value pair with the largest key.
+    #[inline]
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entries()
+            .last()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Gets the number of items in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns an iterator over the key-value pairs with keys in the given range, in order by key.
+    ///
+    /// Unlike `BTreeMap::range`, this does not panic if the start of the range is greater than
+    /// the end. The returned iterator is empty instead.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.partition_point(|k| k.borrow() < start),
+            Bound::Excluded(start) => self.partition_point(|k| k.borrow() <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.partition_point(|k| k.borrow() <= end),
+            Bound::Excluded(end) => self.partition_point(|k| k.borrow() < end),
+            Bound::Unbounded => self.len(),
+        };
+
+        Iter {
+            inner: self.entries()[start..end.max(start)].iter(),
+        }
+    }
+
+    /// Returns an iterator over the values of the map in order by key.
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Resolves an archived sorted map from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a sorted map
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: SortedMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.len);
+        len.resolve(pos + fp, (), fo);
+
+        let (fp, fo) = out_field!(out.entries);
+        RelPtr::emplace(pos + fp, resolver.entries_pos, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        ScratchVec, Serialize,
+    };
+
+    impl<K, V> ArchivedSortedMap<K, V> {
+        /// Serializes an iterator of key-value pairs as a sorted map.
+        ///
+        /// The pairs may be returned in any order, they are sorted by key before being written.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter<'a, UK, UV, I, S>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<SortedMapResolver, S::Error>
+        where
+            UK: 'a + Serialize<S, Archived = K> + Ord,
+            UV: 'a + Serialize<S, Archived = V>,
+            I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
+            S: Serializer + ScratchSpace + ?Sized,
+        {
+            let len = iter.len();
+
+            let mut pairs = ScratchVec::new(serializer, len)?;
+            for pair in iter {
+                pairs.push(pair);
+            }
+            pairs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+            // Serialize entries
+            let mut resolvers = ScratchVec::new(serializer, len)?;
+            for (key, value) in pairs.iter() {
+                resolvers.push((key.serialize(serializer)?, value.serialize(serializer)?));
+            }
+
+            let entries_pos = serializer.align_for::<Entry<K, V>>()?;
+            for (&(key, value), resolver) in pairs.iter().zip(resolvers.drain(..)) {
+                serializer.resolve_aligned(&Entry { key, value }, resolver)?;
+            }
+
+            // Free scratch vecs
+            resolvers.free(serializer)?;
+            pairs.free(serializer)?;
+
+            Ok(SortedMapResolver { entries_pos })
+        }
+    }
+};
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedSortedMap<K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, Q, V> Index<&'_ Q> for ArchivedSortedMap<K, V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ArchivedSortedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedSortedMap<K, V> {}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedSortedMap<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+/// An iterator over the key-value pairs of a sorted map.
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// An iterator over the keys of a sorted map.
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of a sorted map.
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// The resolver for an [`ArchivedSortedMap`].
+pub struct SortedMapResolver {
+    entries_pos: usize,
+}
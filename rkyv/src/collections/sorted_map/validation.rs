@@ -0,0 +1,133 @@
+//! Validation implementation for ArchivedSortedMap.
+
+use crate::{
+    collections::{
+        sorted_map::ArchivedSortedMap,
+        util::{validation::ArchivedEntryError, Entry},
+    },
+    validation::ArchiveContext,
+    Archived, RelPtr,
+};
+use bytecheck::{CheckBytes, Error, SliceCheckError};
+use core::{
+    alloc::{Layout, LayoutError},
+    convert::Infallible,
+    fmt, ptr,
+};
+
+/// Errors that can occur while checking an archived sorted map.
+#[derive(Debug)]
+pub enum SortedMapError<K, V, C> {
+    /// An error occurred while checking the layout of the entries
+    LayoutError(LayoutError),
+    /// An error occurred while checking the entries
+    CheckEntryError(SliceCheckError<ArchivedEntryError<K, V>>),
+    /// A key was not strictly greater than the key before it
+    UnsortedKeys {
+        /// The index of the out-of-order key
+        index: usize,
+    },
+    /// A bounds error occurred
+    ContextError(C),
+}
+
+impl<K: fmt::Display, V: fmt::Display, E: fmt::Display> fmt::Display for SortedMapError<K, V, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortedMapError::LayoutError(e) => write!(f, "layout error: {}", e),
+            SortedMapError::CheckEntryError(e) => write!(f, "entry check error: {}", e),
+            SortedMapError::UnsortedKeys { index } => {
+                write!(f, "unsorted keys: key at index {} is out of order", index)
+            }
+            SortedMapError::ContextError(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<K, V, C> Error for SortedMapError<K, V, C>
+    where
+        K: Error + 'static,
+        V: Error + 'static,
+        C: Error + 'static,
+    {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                SortedMapError::LayoutError(e) => Some(e as &dyn Error),
+                SortedMapError::CheckEntryError(e) => Some(e as &dyn Error),
+                SortedMapError::UnsortedKeys { .. } => None,
+                SortedMapError::ContextError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+impl<K, V, C> From<Infallible> for SortedMapError<K, V, C> {
+    fn from(_: Infallible) -> Self {
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+}
+
+impl<K, V, C> From<LayoutError> for SortedMapError<K, V, C> {
+    #[inline]
+    fn from(e: LayoutError) -> Self {
+        Self::LayoutError(e)
+    }
+}
+
+impl<K, V, C> From<SliceCheckError<ArchivedEntryError<K, V>>> for SortedMapError<K, V, C> {
+    #[inline]
+    fn from(e: SliceCheckError<ArchivedEntryError<K, V>>) -> Self {
+        Self::CheckEntryError(e)
+    }
+}
+
+impl<K, V, C> CheckBytes<C> for ArchivedSortedMap<K, V>
+where
+    K: CheckBytes<C> + Ord,
+    V: CheckBytes<C>,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = SortedMapError<K::Error, V::Error, C::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let len = from_archived!(*Archived::<usize>::check_bytes(
+            ptr::addr_of!((*value).len),
+            context
+        )?) as usize;
+
+        Layout::array::<Entry<K, V>>(len)?;
+        let entries_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).entries), context)?;
+        let entries_ptr = context
+            .check_subtree_ptr::<[Entry<K, V>]>(
+                entries_rel_ptr.base(),
+                entries_rel_ptr.offset(),
+                len,
+            )
+            .map_err(SortedMapError::ContextError)?;
+
+        let range = context
+            .push_prefix_subtree(entries_ptr)
+            .map_err(SortedMapError::ContextError)?;
+        let entries = <[Entry<K, V>]>::check_bytes(entries_ptr, context)?;
+        context
+            .pop_prefix_range(range)
+            .map_err(SortedMapError::ContextError)?;
+
+        // Lookups binary search the entries, so the keys must be sorted and unique
+        for (i, pair) in entries.windows(2).enumerate() {
+            if pair[0].key >= pair[1].key {
+                return Err(SortedMapError::UnsortedKeys { index: i + 1 });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
@@ -41,6 +41,29 @@ impl<K: Serialize<S>, V: Serialize<S>, S: Fallible + ?Sized> Serialize<S> for En
     }
 }
 
+/// Hints to the processor that the memory at `ptr` will be read soon.
+///
+/// This never dereferences `ptr`, so it may be dangling or out of bounds. On targets without a
+/// supported prefetch instruction, it does nothing.
+#[inline(always)]
+pub(crate) fn prefetch<T>(ptr: *const T) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse"))]
+    unsafe {
+        use core::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch(ptr.cast(), _MM_HINT_T0);
+    }
+    #[cfg(all(target_arch = "x86", target_feature = "sse"))]
+    unsafe {
+        use core::arch::x86::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch(ptr.cast(), _MM_HINT_T0);
+    }
+    #[cfg(not(all(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        target_feature = "sse"
+    )))]
+    let _ = ptr;
+}
+
 impl<K, V, UK, UV> PartialEq<Entry<UK, UV>> for Entry<K, V>
 where
     K: PartialEq<UK>,
@@ -0,0 +1,132 @@
+//! [`Archive`](crate::Archive) implementation for double-ended queues.
+
+use crate::vec::{ArchivedVec, VecResolver};
+use core::{fmt, ops::Index, slice};
+
+/// An archived `VecDeque`. This is a wrapper around an archived vec of the queue's elements.
+///
+/// The elements are archived contiguously from front to back, regardless of how they were laid out
+/// in the ring buffer of the queue.
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct ArchivedVecDeque<T>(pub(crate) ArchivedVec<T>);
+
+impl<T> ArchivedVecDeque<T> {
+    /// Returns the element at the front of the queue, or `None` if it is empty.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.0.as_slice().first()
+    }
+
+    /// Returns the element at the back of the queue, or `None` if it is empty.
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.0.as_slice().last()
+    }
+
+    /// Returns the element at the given index from the front of the queue, or `None` if the index
+    /// is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.0.as_slice().get(index)
+    }
+
+    /// Returns the number of elements in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets the elements of the queue as a slice, from front to back.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.0.as_slice()
+    }
+
+    /// Gets an iterator over the elements of the queue, from front to back.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.0.as_slice().iter()
+    }
+
+    /// Resolves a queue from its length.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a queue
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: VecDequeResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.0);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.0, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        Serialize,
+    };
+
+    impl<T> ArchivedVecDeque<T> {
+        /// Serializes a queue from an iterator over its elements from front to back.
+        #[inline]
+        pub fn serialize_from_iter<'a, U, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<VecDequeResolver, S::Error>
+        where
+            U: 'a + Serialize<S, Archived = T>,
+            S: ScratchSpace + Serializer + ?Sized,
+            I: ExactSizeIterator<Item = &'a U>,
+        {
+            Ok(VecDequeResolver(ArchivedVec::serialize_from_iter::<
+                U,
+                _,
+                _,
+                _,
+            >(iter, serializer)?))
+        }
+    }
+};
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedVecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<usize> for ArchivedVecDeque<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        &self.0.as_slice()[index]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedVecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The resolver for archived queues.
+pub struct VecDequeResolver(VecResolver);
@@ -1,16 +1,19 @@
 //! Adapters wrap deserializers and add support for deserializer traits.
 
 use crate::{
-    de::{SharedDeserializeRegistry, SharedPointer},
+    de::{AllocationTracker, SharedDeserializeRegistry, SharedPointer, StringInterner},
     Fallible,
 };
 #[cfg(not(feature = "std"))]
-use alloc::boxed::Box;
-use core::fmt;
+use alloc::{boxed::Box, rc::Rc};
+use core::{convert::Infallible, fmt};
 #[cfg(not(feature = "std"))]
-use hashbrown::hash_map;
+use hashbrown::{hash_map, hash_set};
 #[cfg(feature = "std")]
-use std::collections::hash_map;
+use std::{
+    collections::{hash_map, hash_set},
+    rc::Rc,
+};
 
 /// An error that can occur while deserializing shared pointers.
 #[derive(Debug)]
@@ -43,6 +46,10 @@ const _: () = {
 };
 
 /// An adapter that adds shared deserialization support to a deserializer.
+///
+/// The map tracks the archived position of each shared value it deserializes. Archived shared
+/// pointers to the same value are deserialized into clones of the same shared pointer, so the
+/// sharing of the original values is preserved.
 pub struct SharedDeserializeMap {
     shared_pointers: hash_map::HashMap<*const u8, Box<dyn SharedPointer>>,
 }
@@ -101,3 +108,128 @@ impl SharedDeserializeRegistry for SharedDeserializeMap {
         }
     }
 }
+
+/// An adapter that interns deserialized strings so that equal strings share one allocation.
+///
+/// Use it with the [`Intern`](crate::with::Intern) wrapper to deserialize repeated strings as
+/// shared `Rc<str>`s.
+#[derive(Debug, Default)]
+pub struct StringInternMap {
+    strings: hash_set::HashSet<Rc<str>>,
+}
+
+impl StringInternMap {
+    /// Creates a new, empty string intern map.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            strings: hash_set::HashSet::new(),
+        }
+    }
+
+    /// Returns the number of unique strings that have been interned.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns whether no strings have been interned.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl Fallible for StringInternMap {
+    type Error = Infallible;
+}
+
+impl StringInterner for StringInternMap {
+    fn intern(&mut self, value: &str) -> Result<Rc<str>, Self::Error> {
+        if let Some(interned) = self.strings.get(value) {
+            Ok(interned.clone())
+        } else {
+            let interned = Rc::<str>::from(value);
+            self.strings.insert(interned.clone());
+            Ok(interned)
+        }
+    }
+}
+
+/// An error that occurs when a deserializer would allocate more than its allocation limit.
+#[derive(Debug)]
+pub struct AllocationLimitExceeded {
+    /// The number of bytes that the deserializer was allowed to allocate
+    pub limit: usize,
+    /// The total number of bytes that would have been allocated
+    pub requested: usize,
+}
+
+impl fmt::Display for AllocationLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "allocation limit exceeded: {} bytes requested with a limit of {} bytes",
+            self.requested, self.limit
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for AllocationLimitExceeded {}
+};
+
+/// A deserializer that caps the total number of bytes allocated while deserializing.
+///
+/// Use it with the [`Budgeted`](crate::with::Budgeted) wrapper to reject archives that declare
+/// vectors or strings larger than the limit before allocating memory for them.
+#[derive(Debug)]
+pub struct AllocationLimiter {
+    limit: usize,
+    allocated: usize,
+}
+
+impl AllocationLimiter {
+    /// Creates a new allocation limiter that allows at most `limit` bytes to be allocated.
+    #[inline]
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            allocated: 0,
+        }
+    }
+
+    /// Returns the number of bytes that have been allocated so far.
+    #[inline]
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Returns the number of bytes that can still be allocated.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.limit - self.allocated
+    }
+}
+
+impl Fallible for AllocationLimiter {
+    type Error = AllocationLimitExceeded;
+}
+
+impl AllocationTracker for AllocationLimiter {
+    fn track_allocation(&mut self, bytes: usize) -> Result<(), Self::Error> {
+        match self.allocated.checked_add(bytes) {
+            Some(requested) if requested <= self.limit => {
+                self.allocated = requested;
+                Ok(())
+            }
+            requested => Err(AllocationLimitExceeded {
+                limit: self.limit,
+                requested: requested.unwrap_or(usize::MAX),
+            }),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! A deserializer that allocates in a bump arena.
+
+use crate::{de::ArenaDeserializer, Fallible};
+use bumpalo::Bump;
+use core::convert::Infallible;
+
+/// A deserializer that allocates `bumpalo` boxes, vectors, and strings in a bump arena.
+///
+/// Types that don't come from `bumpalo` are still deserialized normally, so a `std` `Box` inside
+/// the deserialized value will be allocated with the global allocator.
+///
+/// ## Examples
+/// ```
+/// use bumpalo::{collections::String, Bump};
+/// use rkyv::{de::deserializers::BumpDeserializer, Deserialize};
+///
+/// let bump = Bump::new();
+/// let value = String::from_str_in("hello world", &bump);
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<String>(&bytes[..]) };
+///
+/// let arena = Bump::new();
+/// let deserialized: String = archived
+///     .deserialize(&mut BumpDeserializer::new(&arena))
+///     .unwrap();
+/// assert_eq!(deserialized, "hello world");
+/// ```
+#[derive(Debug)]
+pub struct BumpDeserializer<'a> {
+    bump: &'a Bump,
+}
+
+impl<'a> BumpDeserializer<'a> {
+    /// Creates a new bump deserializer that allocates in the given arena.
+    #[inline]
+    pub fn new(bump: &'a Bump) -> Self {
+        Self { bump }
+    }
+}
+
+impl Fallible for BumpDeserializer<'_> {
+    type Error = Infallible;
+}
+
+impl<'a> ArenaDeserializer<'a> for BumpDeserializer<'a> {
+    #[inline]
+    fn arena(&self) -> &'a Bump {
+        self.bump
+    }
+}
@@ -2,7 +2,12 @@
 
 #[cfg(feature = "alloc")]
 mod alloc;
+#[cfg(feature = "bumpalo")]
+mod bumpalo;
 
 #[doc(inline)]
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
+#[doc(inline)]
+#[cfg(feature = "bumpalo")]
+pub use self::bumpalo::*;
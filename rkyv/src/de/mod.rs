@@ -5,9 +5,11 @@ pub mod deserializers;
 #[cfg(feature = "alloc")]
 use crate::{ArchiveUnsized, DeserializeUnsized, Fallible};
 #[cfg(all(feature = "alloc", not(feature = "std")))]
-use ::alloc::boxed::Box;
+use ::alloc::{boxed::Box, rc::Rc};
 #[cfg(feature = "alloc")]
 use ::core::alloc::Layout;
+#[cfg(feature = "std")]
+use ::std::rc::Rc;
 
 /// A deserializable shared pointer type.
 #[cfg(feature = "alloc")]
@@ -65,3 +67,34 @@ pub trait SharedDeserializeRegistry: Fallible {
         }
     }
 }
+
+/// A cache of deserialized strings that shares the allocations of strings with the same contents.
+///
+/// This trait is required to deserialize strings with the [`Intern`](crate::with::Intern)
+/// wrapper.
+#[cfg(feature = "alloc")]
+pub trait StringInterner: Fallible {
+    /// Returns a shared string with the given contents, allocating a new one only if this is the
+    /// first time the contents have been interned.
+    fn intern(&mut self, value: &str) -> Result<Rc<str>, Self::Error>;
+}
+
+/// A deserializer that keeps track of how many bytes have been allocated while deserializing.
+///
+/// This trait is required to deserialize vectors and strings with the
+/// [`Budgeted`](crate::with::Budgeted) wrapper.
+#[cfg(feature = "alloc")]
+pub trait AllocationTracker: Fallible {
+    /// Records that `bytes` more bytes are about to be allocated, returning an error if the
+    /// allocation should not be made.
+    fn track_allocation(&mut self, bytes: usize) -> Result<(), Self::Error>;
+}
+
+/// A deserializer that can allocate deserialized values in a bump arena.
+///
+/// This trait is required to deserialize `bumpalo` types.
+#[cfg(feature = "bumpalo")]
+pub trait ArenaDeserializer<'a>: crate::Fallible {
+    /// Returns the arena to allocate deserialized values in.
+    fn arena(&self) -> &'a bumpalo::Bump;
+}
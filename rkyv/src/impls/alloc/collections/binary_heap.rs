@@ -0,0 +1,46 @@
+use crate::{
+    collections::binary_heap::{ArchivedBinaryHeap, BinaryHeapResolver},
+    ser::{ScratchSpace, Serializer},
+    vec::ArchivedVec,
+    Archive, Deserialize, Fallible, Serialize,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BinaryHeap, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BinaryHeap;
+
+impl<T: Archive + Ord> Archive for BinaryHeap<T> {
+    type Archived = ArchivedBinaryHeap<T::Archived>;
+    type Resolver = BinaryHeapResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedBinaryHeap::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for BinaryHeap<T>
+where
+    T: Serialize<S> + Ord,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Iterating a binary heap visits the elements in the order of its backing vector
+        ArchivedBinaryHeap::serialize_from_iter(self.iter(), serializer)
+    }
+}
+
+impl<T, D> Deserialize<BinaryHeap<T>, D> for ArchivedBinaryHeap<T::Archived>
+where
+    T: Archive + Ord,
+    ArchivedVec<T::Archived>: Deserialize<Vec<T>, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<BinaryHeap<T>, D::Error> {
+        // The elements are already in heap order, so rebuilding the heap doesn't move any of them
+        let vec: Vec<T> = self.0.deserialize(deserializer)?;
+        Ok(BinaryHeap::from(vec))
+    }
+}
@@ -1,10 +1,13 @@
 use crate::{
-    collections::btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+    collections::{
+        btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+        hash_map::ArchivedHashMap,
+    },
     ser::Serializer,
     Archive, Deserialize, Fallible, Serialize,
 };
 #[cfg(not(feature = "std"))]
-use alloc::collections::BTreeMap;
+use alloc::{collections::BTreeMap, vec::Vec};
 #[cfg(feature = "std")]
 use std::collections::BTreeMap;
 
@@ -50,6 +53,30 @@ where
     }
 }
 
+/// Deserializes an archived hash map directly into a `BTreeMap`.
+///
+/// The entries are deserialized into a buffer and sorted by key before being collected, which lets
+/// the map be built in a single pass instead of going through an intermediate `HashMap`.
+impl<K: Archive + Ord, V: Archive, D: Fallible + ?Sized> Deserialize<BTreeMap<K, V>, D>
+    for ArchivedHashMap<K::Archived, V::Archived>
+where
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut entries = Vec::with_capacity(self.len());
+        for (key, value) in self.iter() {
+            entries.push((
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            ));
+        }
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries.into_iter().collect())
+    }
+}
+
 impl<K, V, AK: PartialEq<K>, AV: PartialEq<V>> PartialEq<BTreeMap<K, V>>
     for ArchivedBTreeMap<AK, AV>
 {
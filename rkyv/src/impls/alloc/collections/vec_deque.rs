@@ -0,0 +1,58 @@
+use crate::{
+    collections::vec_deque::{ArchivedVecDeque, VecDequeResolver},
+    ser::{ScratchSpace, Serializer},
+    vec::ArchivedVec,
+    Archive, Deserialize, Fallible, Serialize,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{collections::VecDeque, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+impl<T: Archive> Archive for VecDeque<T> {
+    type Archived = ArchivedVecDeque<T::Archived>;
+    type Resolver = VecDequeResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVecDeque::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for VecDeque<T>
+where
+    T: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVecDeque::serialize_from_iter(self.iter(), serializer)
+    }
+}
+
+impl<T, D> Deserialize<VecDeque<T>, D> for ArchivedVecDeque<T::Archived>
+where
+    T: Archive,
+    ArchivedVec<T::Archived>: Deserialize<Vec<T>, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<VecDeque<T>, D::Error> {
+        let vec: Vec<T> = self.0.deserialize(deserializer)?;
+        Ok(VecDeque::from(vec))
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<VecDeque<U>> for ArchivedVecDeque<T> {
+    #[inline]
+    fn eq(&self, other: &VecDeque<U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.eq(b))
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<ArchivedVecDeque<U>> for VecDeque<T> {
+    #[inline]
+    fn eq(&self, other: &ArchivedVecDeque<U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.eq(b))
+    }
+}
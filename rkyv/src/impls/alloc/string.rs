@@ -4,6 +4,7 @@ use crate::{
 };
 #[cfg(not(feature = "std"))]
 use ::alloc::string::{String, ToString};
+use ::core::cmp;
 
 impl Archive for String {
     type Archived = ArchivedString;
@@ -48,3 +49,17 @@ impl PartialEq<ArchivedString> for String {
         PartialEq::eq(other.as_str(), self.as_str())
     }
 }
+
+impl PartialOrd<String> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &String) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl PartialOrd<ArchivedString> for String {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    boxed::{ArchivedBox, BoxResolver},
+    de::ArenaDeserializer,
+    ser::{ScratchSpace, Serializer},
+    string::{ArchivedString, StringResolver},
+    vec::{ArchivedVec, VecResolver},
+    Archive, ArchiveUnsized, Archived, Deserialize, DeserializeUnsized, Fallible, Serialize,
+    SerializeUnsized,
+};
+use bumpalo::{
+    boxed::Box,
+    collections::{String, Vec},
+};
+
+// Box
+
+impl<T: ArchiveUnsized + ?Sized> Archive for Box<'_, T> {
+    type Archived = ArchivedBox<T::Archived>;
+    type Resolver = BoxResolver<T::MetadataResolver>;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedBox::resolve_from_ref(self.as_ref(), pos, resolver, out);
+    }
+}
+
+impl<T: SerializeUnsized<S> + ?Sized, S: Fallible + ?Sized> Serialize<S> for Box<'_, T> {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedBox::serialize_from_ref(self.as_ref(), serializer)
+    }
+}
+
+impl<'a, T, D> Deserialize<Box<'a, T>, D> for ArchivedBox<T::Archived>
+where
+    T: ArchiveUnsized + ?Sized,
+    T::Archived: DeserializeUnsized<T, D>,
+    D: ArenaDeserializer<'a> + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<Box<'a, T>, D::Error> {
+        let arena = deserializer.arena();
+        unsafe {
+            let data_address = self
+                .get()
+                .deserialize_unsized(deserializer, |layout| arena.alloc_layout(layout).as_ptr())?;
+            let metadata = self.get().deserialize_metadata(deserializer)?;
+            let ptr = ptr_meta::from_raw_parts_mut(data_address, metadata);
+            Ok(Box::from_raw(ptr))
+        }
+    }
+}
+
+// Vec
+
+impl<T: Archive> Archive for Vec<'_, T> {
+    type Archived = ArchivedVec<Archived<T>>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_slice(self.as_slice(), pos, resolver, out);
+    }
+}
+
+impl<T: Serialize<S>, S: ScratchSpace + Serializer + ?Sized> Serialize<S> for Vec<'_, T> {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(self.as_slice(), serializer)
+    }
+}
+
+impl<'a, T, D> Deserialize<Vec<'a, T>, D> for ArchivedVec<Archived<T>>
+where
+    T: Archive,
+    Archived<T>: Deserialize<T, D>,
+    D: ArenaDeserializer<'a> + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<Vec<'a, T>, D::Error> {
+        let mut result = Vec::with_capacity_in(self.len(), deserializer.arena());
+        for item in self.as_slice() {
+            result.push(item.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
+// String
+
+impl Archive for String<'_> {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedString::resolve_from_str(self.as_str(), pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for String<'_>
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(self.as_str(), serializer)
+    }
+}
+
+impl<'a, D: ArenaDeserializer<'a> + ?Sized> Deserialize<String<'a>, D> for ArchivedString {
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<String<'a>, D::Error> {
+        Ok(String::from_str_in(self.as_str(), deserializer.arena()))
+    }
+}
+
+impl PartialEq<String<'_>> for ArchivedString {
+    #[inline]
+    fn eq(&self, other: &String<'_>) -> bool {
+        PartialEq::eq(self.as_str(), other.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archived_root,
+        de::deserializers::BumpDeserializer,
+        ser::{serializers::AllocSerializer, Serializer},
+        Deserialize,
+    };
+    use bumpalo::{
+        boxed::Box,
+        collections::{String, Vec},
+        vec, Bump,
+    };
+
+    type Graph<'a> = (
+        String<'a>,
+        Box<'a, (u32, String<'a>)>,
+        Vec<'a, Vec<'a, String<'a>>>,
+        Box<'a, [u32]>,
+    );
+
+    fn in_arena<T: ?Sized>(arena: &Bump, value: *const T) -> bool {
+        let ptr = value as *const u8;
+        unsafe {
+            arena
+                .iter_allocated_chunks_raw()
+                .any(|(start, len)| ptr >= start as *const u8 && ptr < start.add(len) as *const u8)
+        }
+    }
+
+    #[test]
+    fn deserialize_into_bump() {
+        let source = Bump::new();
+        let value: Graph = (
+            String::from_str_in("the root string is long enough to be out of line", &source),
+            Box::new_in((42, String::from_str_in("boxed", &source)), &source),
+            vec![in &source;
+                vec![in &source; String::from_str_in("a", &source)],
+                Vec::new_in(&source),
+                vec![in &source;
+                    String::from_str_in("b", &source),
+                    String::from_str_in("c", &source),
+                ],
+            ],
+            vec![in &source; 1, 2, 3].into_boxed_slice(),
+        );
+
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Graph>(buf.as_ref()) };
+
+        let arena = Bump::new();
+        let deserialized: Graph = archived
+            .deserialize(&mut BumpDeserializer::new(&arena))
+            .unwrap();
+
+        assert_eq!(deserialized.0, value.0);
+        assert_eq!(deserialized.1 .0, 42);
+        assert_eq!(deserialized.1 .1, "boxed");
+        assert_eq!(deserialized.2, value.2);
+        assert_eq!(&*deserialized.3, &[1, 2, 3]);
+
+        assert!(in_arena(&arena, deserialized.0.as_ptr()));
+        assert!(in_arena(&arena, &*deserialized.1));
+        assert!(in_arena(&arena, deserialized.1 .1.as_ptr()));
+        assert!(in_arena(&arena, deserialized.2.as_ptr()));
+        assert!(in_arena(&arena, deserialized.2[2].as_ptr()));
+        assert!(in_arena(&arena, deserialized.2[2][1].as_ptr()));
+        assert!(in_arena(&arena, &*deserialized.3));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::vec::ArchivedVec;
+use bytes::Bytes;
+
+impl ArchivedVec<u8> {
+    /// Returns a [`Bytes`] that shares the bytes of the archived vec without copying them.
+    ///
+    /// This requires the archive to live for the rest of the program, for example because it is
+    /// stored in a `static`, was leaked, or is memory-mapped and never unmapped. If the archive
+    /// may be dropped or reused, use [`copy_to_bytes`](ArchivedVec::copy_to_bytes) instead.
+    #[inline]
+    pub fn as_static_bytes(&'static self) -> Bytes {
+        Bytes::from_static(self.as_slice())
+    }
+
+    /// Copies the bytes of the archived vec into a new [`Bytes`].
+    ///
+    /// The returned `Bytes` does not borrow from the archive, so the archive may be dropped or
+    /// reused afterward.
+    #[inline]
+    pub fn copy_to_bytes(&self) -> Bytes {
+        Bytes::copy_from_slice(self.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archived_root,
+        ser::{serializers::AllocSerializer, Serializer},
+        AlignedVec,
+    };
+
+    #[test]
+    fn archived_vec_to_bytes() {
+        let value = (0..=255u8).collect::<Vec<_>>();
+
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Vec<u8>>(buf.as_ref()) };
+        let copied = archived.copy_to_bytes();
+        assert_eq!(copied.as_ref(), value.as_slice());
+        drop(buf);
+        assert_eq!(copied.as_ref(), value.as_slice());
+
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf: &'static AlignedVec =
+            Box::leak(Box::new(serializer.into_serializer().into_inner()));
+
+        let archived = unsafe { archived_root::<Vec<u8>>(buf.as_ref()) };
+        let shared = archived.as_static_bytes();
+        assert_eq!(shared.as_ref(), value.as_slice());
+        assert_eq!(shared.as_ptr(), archived.as_ptr());
+    }
+}
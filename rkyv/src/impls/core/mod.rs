@@ -1,5 +1,7 @@
 #[cfg(feature = "copy")]
 use crate::copy::ArchiveCopyOptimize;
+#[cfg(not(feature = "strict"))]
+use crate::tuple::{ArchivedTuple13, ArchivedTuple14, ArchivedTuple15, ArchivedTuple16};
 use crate::{
     ser::{ScratchSpace, Serializer},
     Archive, ArchivePointee, ArchiveUnsized, Archived, ArchivedMetadata, Deserialize,
@@ -8,6 +10,7 @@ use crate::{
 use core::{alloc::Layout, ptr, str};
 use ptr_meta::Pointee;
 
+pub mod num;
 pub mod ops;
 pub mod option;
 pub mod primitive;
@@ -125,6 +128,65 @@ macro_rules! impl_tuple {
 #[cfg(not(feature = "strict"))]
 impl_tuple! { T11 11, T10 10, T9 9, T8 8, T7 7, T6 6, T5 5, T4 4, T3 3, T2 2, T1 1, T0 0, }
 
+// bytecheck only implements `CheckBytes` for tuples with up to 12 elements, so longer tuples are
+// archived as tuple structs that can be validated.
+#[cfg(not(feature = "strict"))]
+macro_rules! impl_long_tuple {
+    ($archived:ident, $($type:ident $index:tt,)+) => {
+        impl<$($type: Archive),+> Archive for ($($type,)+) {
+            type Archived = $archived<$($type::Archived,)+>;
+            type Resolver = ($($type::Resolver,)+);
+
+            #[inline]
+            unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+                $(
+                    let (fp, fo) = out_field!(out.$index);
+                    self.$index.resolve(pos + fp, resolver.$index, fo);
+                )+
+            }
+        }
+
+        impl<$($type: Serialize<S>),+, S: Fallible + ?Sized> Serialize<S> for ($($type,)+) {
+            #[inline]
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                Ok(($(self.$index.serialize(serializer)?,)+))
+            }
+        }
+
+        impl<D: Fallible + ?Sized, $($type: Archive),+> Deserialize<($($type,)+), D>
+            for $archived<$($type::Archived,)+>
+        where
+            $($type::Archived: Deserialize<$type, D>,)+
+        {
+            #[inline]
+            fn deserialize(&self, deserializer: &mut D) -> Result<($($type,)+), D::Error> {
+                Ok(($(self.$index.deserialize(deserializer)?,)+))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "strict"))]
+impl_long_tuple! {
+    ArchivedTuple13, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11,
+    T12 12,
+}
+#[cfg(not(feature = "strict"))]
+impl_long_tuple! {
+    ArchivedTuple14, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11,
+    T12 12, T13 13,
+}
+#[cfg(not(feature = "strict"))]
+impl_long_tuple! {
+    ArchivedTuple15, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11,
+    T12 12, T13 13, T14 14,
+}
+#[cfg(not(feature = "strict"))]
+impl_long_tuple! {
+    ArchivedTuple16, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11,
+    T12 12, T13 13, T14 14, T15 15,
+}
+
 impl<T: Archive, const N: usize> Archive for [T; N] {
     type Archived = [T::Archived; N];
     type Resolver = [T::Resolver; N];
@@ -358,7 +420,7 @@ impl<D: Fallible + ?Sized> DeserializeUnsized<str, D> for <str as ArchiveUnsized
         mut alloc: impl FnMut(Layout) -> *mut u8,
     ) -> Result<*mut (), D::Error> {
         if self.is_empty() {
-            Ok(ptr::null_mut())
+            Ok(ptr::NonNull::<u8>::dangling().as_ptr().cast())
         } else {
             let bytes = alloc(Layout::array::<u8>(self.len()).unwrap());
             assert!(!bytes.is_null());
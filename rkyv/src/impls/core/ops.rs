@@ -1,11 +1,19 @@
+#[cfg(feature = "control_flow")]
+use crate::ops::ArchivedControlFlow;
 use crate::{
     ops::{
-        ArchivedRange, ArchivedRangeFrom, ArchivedRangeInclusive, ArchivedRangeTo,
+        ArchivedBound, ArchivedRange, ArchivedRangeFrom, ArchivedRangeInclusive, ArchivedRangeTo,
         ArchivedRangeToInclusive,
     },
     Archive, Archived, Deserialize, Fallible, Serialize,
 };
-use core::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+#[cfg(feature = "control_flow")]
+use core::ops::ControlFlow;
+use core::{
+    hint::unreachable_unchecked,
+    ops::{Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive},
+    ptr,
+};
 
 // RangeFull
 
@@ -247,3 +255,188 @@ impl<T, U: PartialEq<T>> PartialEq<RangeToInclusive<T>> for ArchivedRangeToInclu
         self.end.eq(&other.end)
     }
 }
+
+// Bound
+
+#[allow(dead_code)]
+#[repr(u8)]
+enum ArchivedBoundTag {
+    Included,
+    Excluded,
+    Unbounded,
+}
+
+#[repr(C)]
+struct ArchivedBoundVariant<T>(ArchivedBoundTag, T);
+
+impl<T: Archive> Archive for Bound<T> {
+    type Archived = ArchivedBound<T::Archived>;
+    type Resolver = Bound<T::Resolver>;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        match resolver {
+            Bound::Included(resolver) => {
+                let out = out.cast::<ArchivedBoundVariant<T::Archived>>();
+                ptr::addr_of_mut!((*out).0).write(ArchivedBoundTag::Included);
+
+                let (fp, fo) = out_field!(out.1);
+                match self {
+                    Bound::Included(value) => value.resolve(pos + fp, resolver, fo),
+                    _ => unreachable_unchecked(),
+                }
+            }
+            Bound::Excluded(resolver) => {
+                let out = out.cast::<ArchivedBoundVariant<T::Archived>>();
+                ptr::addr_of_mut!((*out).0).write(ArchivedBoundTag::Excluded);
+
+                let (fp, fo) = out_field!(out.1);
+                match self {
+                    Bound::Excluded(value) => value.resolve(pos + fp, resolver, fo),
+                    _ => unreachable_unchecked(),
+                }
+            }
+            Bound::Unbounded => {
+                out.cast::<ArchivedBoundTag>()
+                    .write(ArchivedBoundTag::Unbounded);
+            }
+        }
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + ?Sized> Serialize<S> for Bound<T> {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(match self {
+            Bound::Included(value) => Bound::Included(value.serialize(serializer)?),
+            Bound::Excluded(value) => Bound::Excluded(value.serialize(serializer)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    }
+}
+
+impl<T: Archive, D: Fallible + ?Sized> Deserialize<Bound<T>, D> for ArchivedBound<T::Archived>
+where
+    T::Archived: Deserialize<T, D>,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<Bound<T>, D::Error> {
+        Ok(match self {
+            ArchivedBound::Included(value) => Bound::Included(value.deserialize(deserializer)?),
+            ArchivedBound::Excluded(value) => Bound::Excluded(value.deserialize(deserializer)?),
+            ArchivedBound::Unbounded => Bound::Unbounded,
+        })
+    }
+}
+
+impl<T, U: PartialEq<T>> PartialEq<Bound<T>> for ArchivedBound<U> {
+    #[inline]
+    fn eq(&self, other: &Bound<T>) -> bool {
+        match (self, other) {
+            (ArchivedBound::Included(a), Bound::Included(b)) => a.eq(b),
+            (ArchivedBound::Excluded(a), Bound::Excluded(b)) => a.eq(b),
+            (ArchivedBound::Unbounded, Bound::Unbounded) => true,
+            _ => false,
+        }
+    }
+}
+
+// ControlFlow
+
+#[cfg(feature = "control_flow")]
+#[allow(dead_code)]
+#[repr(u8)]
+enum ArchivedControlFlowTag {
+    Continue,
+    Break,
+}
+
+#[cfg(feature = "control_flow")]
+#[repr(C)]
+struct ArchivedControlFlowVariant<T>(ArchivedControlFlowTag, T);
+
+#[cfg(feature = "control_flow")]
+impl<B: Archive, C: Archive> Archive for ControlFlow<B, C> {
+    type Archived = ArchivedControlFlow<B::Archived, C::Archived>;
+    type Resolver = ControlFlow<B::Resolver, C::Resolver>;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        match resolver {
+            ControlFlow::Continue(resolver) => {
+                let out = out.cast::<ArchivedControlFlowVariant<C::Archived>>();
+                ptr::addr_of_mut!((*out).0).write(ArchivedControlFlowTag::Continue);
+
+                let (fp, fo) = out_field!(out.1);
+                match self {
+                    ControlFlow::Continue(value) => value.resolve(pos + fp, resolver, fo),
+                    ControlFlow::Break(_) => unreachable_unchecked(),
+                }
+            }
+            ControlFlow::Break(resolver) => {
+                let out = out.cast::<ArchivedControlFlowVariant<B::Archived>>();
+                ptr::addr_of_mut!((*out).0).write(ArchivedControlFlowTag::Break);
+
+                let (fp, fo) = out_field!(out.1);
+                match self {
+                    ControlFlow::Continue(_) => unreachable_unchecked(),
+                    ControlFlow::Break(value) => value.resolve(pos + fp, resolver, fo),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "control_flow")]
+impl<B, C, S> Serialize<S> for ControlFlow<B, C>
+where
+    B: Serialize<S>,
+    C: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(match self {
+            ControlFlow::Continue(value) => ControlFlow::Continue(value.serialize(serializer)?),
+            ControlFlow::Break(value) => ControlFlow::Break(value.serialize(serializer)?),
+        })
+    }
+}
+
+#[cfg(feature = "control_flow")]
+impl<B, C, D> Deserialize<ControlFlow<B, C>, D> for ArchivedControlFlow<B::Archived, C::Archived>
+where
+    B: Archive,
+    C: Archive,
+    D: Fallible + ?Sized,
+    B::Archived: Deserialize<B, D>,
+    C::Archived: Deserialize<C, D>,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<ControlFlow<B, C>, D::Error> {
+        Ok(match self {
+            ArchivedControlFlow::Continue(value) => {
+                ControlFlow::Continue(value.deserialize(deserializer)?)
+            }
+            ArchivedControlFlow::Break(value) => {
+                ControlFlow::Break(value.deserialize(deserializer)?)
+            }
+        })
+    }
+}
+
+#[cfg(feature = "control_flow")]
+impl<B, C, AB, AC> PartialEq<ControlFlow<B, C>> for ArchivedControlFlow<AB, AC>
+where
+    AB: PartialEq<B>,
+    AC: PartialEq<C>,
+{
+    #[inline]
+    fn eq(&self, other: &ControlFlow<B, C>) -> bool {
+        match (self, other) {
+            (ArchivedControlFlow::Continue(a), ControlFlow::Continue(b)) => a.eq(b),
+            (ArchivedControlFlow::Break(a), ControlFlow::Break(b)) => a.eq(b),
+            _ => false,
+        }
+    }
+}
@@ -85,13 +85,17 @@ macro_rules! impl_atomic {
         }
     };
     ($type:ty, $prim:ty) => {
+        /// Archives a snapshot of the atomic, loaded with [`SeqCst`](Ordering::SeqCst).
+        ///
+        /// Atomicity is not preserved across the archive boundary. Use
+        /// [`Atomic`](crate::with::Atomic) to archive an atomic as an atomic.
         impl Archive for $type {
             type Archived = $prim;
             type Resolver = ();
 
             #[inline]
             unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
-                out.write(self.load(Ordering::Relaxed));
+                out.write(self.load(Ordering::SeqCst));
             }
         }
 
@@ -105,6 +109,10 @@ macro_rules! impl_atomic {
         }
     };
     (@multibyte $type:ty, $prim:ty) => {
+        /// Archives a snapshot of the atomic, loaded with [`SeqCst`](Ordering::SeqCst).
+        ///
+        /// Atomicity is not preserved across the archive boundary. Use
+        /// [`Atomic`](crate::with::Atomic) to archive an atomic as an atomic.
         impl Archive for $type {
             #[cfg(not(any(feature = "archive_le", feature = "archive_be")))]
             type Archived = $prim;
@@ -117,7 +125,7 @@ macro_rules! impl_atomic {
 
             #[inline]
             unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
-                out.write(to_archived!(self.load(Ordering::Relaxed)));
+                out.write(to_archived!(self.load(Ordering::SeqCst)));
             }
         }
 
@@ -342,6 +350,10 @@ impl<D: Fallible + ?Sized> Deserialize<NonZeroIsize, D> for Archived<NonZeroIsiz
 
 // AtomicUsize
 
+/// Archives a snapshot of the atomic, loaded with [`SeqCst`](Ordering::SeqCst).
+///
+/// Atomicity is not preserved across the archive boundary. Use [`Atomic`](crate::with::Atomic)
+/// to archive an atomic as an atomic.
 #[cfg(has_atomics)]
 impl Archive for AtomicUsize {
     type Archived = Archived<FixedUsize>;
@@ -349,7 +361,7 @@ impl Archive for AtomicUsize {
 
     #[inline]
     unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
-        out.write(to_archived!(self.load(Ordering::Relaxed) as FixedUsize));
+        out.write(to_archived!(self.load(Ordering::SeqCst) as FixedUsize));
     }
 }
 
@@ -371,6 +383,10 @@ impl<D: Fallible + ?Sized> Deserialize<AtomicUsize, D> for Archived<AtomicUsize>
 
 // AtomicIsize
 
+/// Archives a snapshot of the atomic, loaded with [`SeqCst`](Ordering::SeqCst).
+///
+/// Atomicity is not preserved across the archive boundary. Use [`Atomic`](crate::with::Atomic)
+/// to archive an atomic as an atomic.
 #[cfg(has_atomics)]
 impl Archive for AtomicIsize {
     type Archived = Archived<FixedIsize>;
@@ -378,7 +394,7 @@ impl Archive for AtomicIsize {
 
     #[inline]
     unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
-        out.write(to_archived!(self.load(Ordering::Relaxed) as FixedIsize));
+        out.write(to_archived!(self.load(Ordering::SeqCst) as FixedIsize));
     }
 }
 
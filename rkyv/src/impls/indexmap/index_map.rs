@@ -1,5 +1,8 @@
 use crate::{
-    collections::index_map::{ArchivedIndexMap, IndexMapResolver},
+    collections::{
+        hash_map::ArchivedHashMap,
+        index_map::{ArchivedIndexMap, IndexMapResolver},
+    },
     ser::{ScratchSpace, Serializer},
     Archive, Deserialize, Fallible, Serialize,
 };
@@ -51,6 +54,33 @@ where
     }
 }
 
+/// Deserializes an archived hash map into an `IndexMap`.
+///
+/// With the `hash_map_insertion_order` feature, entries are inserted in the order they were
+/// serialized in. Otherwise, they are inserted in the order they are laid out in the archive.
+impl<K, V, D, S> Deserialize<IndexMap<K, V, S>, D> for ArchivedHashMap<K::Archived, V::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<IndexMap<K, V, S>, D::Error> {
+        #[cfg(feature = "hash_map_insertion_order")]
+        let iter = self.iter_insertion_order();
+        #[cfg(not(feature = "hash_map_insertion_order"))]
+        let iter = self.iter();
+
+        let mut result = IndexMap::with_capacity_and_hasher(self.len(), S::default());
+        for (k, v) in iter {
+            result.insert(k.deserialize(deserializer)?, v.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 impl<UK, K, UV, V, S> PartialEq<IndexMap<UK, UV, S>> for ArchivedIndexMap<K, V>
 where
     K: PartialEq<UK>,
@@ -98,6 +128,40 @@ mod tests {
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    fn hash_map_into_index_map() {
+        #[cfg(not(feature = "std"))]
+        use hashbrown::HashMap;
+        #[cfg(feature = "std")]
+        use std::collections::HashMap;
+
+        let mut value = HashMap::new();
+        value.insert(String::from("foo"), 10);
+        value.insert(String::from("bar"), 20);
+        value.insert(String::from("baz"), 40);
+        value.insert(String::from("bat"), 80);
+
+        let mut serializer = AllocSerializer::<4096>::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<HashMap<String, i32>>(result.as_ref()) };
+
+        let deserialized: IndexMap<String, i32> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.len(), value.len());
+        for (k, v) in value.iter() {
+            assert_eq!(deserialized.get(k), Some(v));
+        }
+
+        #[cfg(feature = "hash_map_insertion_order")]
+        let archived_order = archived.iter_insertion_order();
+        #[cfg(not(feature = "hash_map_insertion_order"))]
+        let archived_order = archived.iter();
+        assert!(deserialized
+            .keys()
+            .map(|k| k.as_str())
+            .eq(archived_order.map(|(k, _)| k.as_str())));
+    }
+
     #[cfg(feature = "validation")]
     #[test]
     fn validate_index_map() {
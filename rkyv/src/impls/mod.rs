@@ -15,6 +15,10 @@ mod std;
 
 #[cfg(feature = "bitvec")]
 mod bitvec;
+#[cfg(feature = "bumpalo")]
+mod bumpalo;
+#[cfg(feature = "bytes")]
+mod bytes;
 #[cfg(feature = "hashbrown")]
 mod hashbrown;
 #[cfg(feature = "indexmap")]
@@ -221,6 +221,51 @@ mod tests {
         assert_eq!(&buf[0..4], &[0x78, 0x56, 0x34, 0x12]);
     }
 
+    #[test]
+    #[cfg(any(feature = "archive_le", feature = "archive_be"))]
+    fn archive_primitives_fixed_endianness() {
+        use crate::{AlignedBytes, Archived};
+
+        #[cfg(feature = "archive_be")]
+        fn to_bytes(value: u32) -> [u8; 4] {
+            value.to_be_bytes()
+        }
+        #[cfg(feature = "archive_le")]
+        fn to_bytes(value: u32) -> [u8; 4] {
+            value.to_le_bytes()
+        }
+
+        // Native primitives are archived in the chosen byte order regardless of the host
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&0x12345678u32).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(&buf[0..4], &to_bytes(0x12345678));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&1.5f64).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let bits = to_bytes((1.5f64.to_bits() >> 32) as u32);
+        #[cfg(feature = "archive_be")]
+        assert_eq!(&buf[0..4], &bits);
+        #[cfg(feature = "archive_le")]
+        assert_eq!(&buf[4..8], &bits);
+
+        // Bytes written in the chosen byte order read back correctly, even when the host would
+        // interpret them byte-swapped
+        let bytes = AlignedBytes(to_bytes(0xdeadbeef));
+        let native = u32::from_ne_bytes(bytes.0);
+        let archived = unsafe { archived_root::<u32>(bytes.as_ref()) };
+        let _: &Archived<u32> = archived;
+        assert_eq!(archived.value(), 0xdeadbeef);
+        if cfg!(target_endian = "little") == cfg!(feature = "archive_le") {
+            assert_eq!(native, 0xdeadbeef);
+        } else {
+            assert_eq!(native, 0xdeadbeefu32.swap_bytes());
+        }
+        let deserialized: u32 = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, 0xdeadbeef);
+    }
+
     #[test]
     fn archive_rend_nonzero() {
         use crate::rend::*;
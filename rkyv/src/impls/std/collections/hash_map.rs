@@ -1,5 +1,5 @@
 use crate::{
-    collections::hash_map::{ArchivedHashMap, HashMapResolver},
+    collections::hash_map::{ArchivedHashMap, HashMapLengthError, HashMapResolver},
     ser::{ScratchSpace, Serializer},
     Archive, Deserialize, Fallible, Serialize,
 };
@@ -35,6 +35,113 @@ where
     }
 }
 
+impl<AK: Hash + Eq, AV> ArchivedHashMap<AK, AV> {
+    /// Deserializes the entries of the archived hash map into the given `HashMap`.
+    ///
+    /// Space for all of the entries is reserved up front so that the map grows at most once, and
+    /// entries are inserted in iteration order. Existing entries with the same keys are replaced.
+    ///
+    /// Archives from untrusted sources should be validated before they are deserialized. Validation
+    /// rejects hash maps whose length doesn't match their entries. Use
+    /// [`deserialize_checked`](Self::deserialize_checked) to check the length of hash maps that
+    /// aren't validated.
+    #[inline]
+    pub fn deserialize_into<K, V, S, D>(
+        &self,
+        map: &mut HashMap<K, V, S>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        K: Hash + Eq,
+        AK: Deserialize<K, D>,
+        AV: Deserialize<V, D>,
+        S: BuildHasher,
+        D: Fallible + ?Sized,
+    {
+        map.reserve(self.len());
+        for (k, v) in self.iter() {
+            map.insert(k.deserialize(deserializer)?, v.deserialize(deserializer)?);
+        }
+        Ok(())
+    }
+
+    /// Deserializes the archived hash map into a `HashMap` after checking that its length matches
+    /// its entries.
+    ///
+    /// This returns a [`HashMapLengthError`] if [`check_len`](Self::check_len) fails. Deserializers
+    /// must have an error type satisfying `<D as Fallible>::Error: From<HashMapLengthError>` to use
+    /// it.
+    #[inline]
+    pub fn deserialize_checked<K, V, S, D>(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error>
+    where
+        K: Hash + Eq,
+        AK: Deserialize<K, D>,
+        AV: Deserialize<V, D>,
+        S: Default + BuildHasher,
+        D: Fallible + ?Sized,
+        D::Error: From<HashMapLengthError>,
+    {
+        self.check_len()?;
+        let mut result = HashMap::with_hasher(S::default());
+        self.deserialize_into(&mut result, deserializer)?;
+        Ok(result)
+    }
+
+    /// Deserializes the archived hash map into a `HashMap` with room for `extra` more entries.
+    ///
+    /// The map is created with a capacity of at least `len + extra`, so inserting up to `extra`
+    /// entries after deserializing will not cause it to reallocate.
+    #[inline]
+    pub fn deserialize_with_capacity<K, V, S, D>(
+        &self,
+        extra: usize,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error>
+    where
+        K: Hash + Eq,
+        AK: Deserialize<K, D>,
+        AV: Deserialize<V, D>,
+        S: Default + BuildHasher,
+        D: Fallible + ?Sized,
+    {
+        let mut result =
+            HashMap::with_capacity_and_hasher(self.len().saturating_add(extra), S::default());
+        self.deserialize_into(&mut result, deserializer)?;
+        Ok(result)
+    }
+
+    /// Deserializes the archived hash map into a `HashMap`, checking each entry with `f` first.
+    ///
+    /// `f` is called with each archived entry before it is deserialized. If it returns an error,
+    /// deserialization stops and the error is converted into the deserializer's error type.
+    /// Deserializers must have an error type satisfying `<D as Fallible>::Error: From<E>` to use
+    /// it.
+    pub fn deserialize_with<K, V, S, D, E, F>(
+        &self,
+        deserializer: &mut D,
+        mut f: F,
+    ) -> Result<HashMap<K, V, S>, D::Error>
+    where
+        K: Hash + Eq,
+        AK: Deserialize<K, D>,
+        AV: Deserialize<V, D>,
+        S: Default + BuildHasher,
+        D: Fallible + ?Sized,
+        D::Error: From<E>,
+        F: FnMut(&AK, &AV) -> Result<(), E>,
+    {
+        let mut result = HashMap::with_capacity_and_hasher(self.len(), S::default());
+        for (k, v) in self.iter() {
+            f(k, v)?;
+            result.insert(k.deserialize(deserializer)?, v.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 impl<K: Archive + Hash + Eq, V: Archive, D: Fallible + ?Sized, S: Default + BuildHasher>
     Deserialize<HashMap<K, V, S>, D> for ArchivedHashMap<K::Archived, V::Archived>
 where
@@ -43,10 +150,8 @@ where
 {
     #[inline]
     fn deserialize(&self, deserializer: &mut D) -> Result<HashMap<K, V, S>, D::Error> {
-        let mut result = HashMap::with_capacity_and_hasher(self.len(), S::default());
-        for (k, v) in self.iter() {
-            result.insert(k.deserialize(deserializer)?, v.deserialize(deserializer)?);
-        }
+        let mut result = HashMap::with_hasher(S::default());
+        self.deserialize_into(&mut result, deserializer)?;
         Ok(result)
     }
 }
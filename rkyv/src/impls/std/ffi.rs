@@ -7,7 +7,10 @@ use crate::{
 use core::{alloc::Layout, ptr};
 use ptr_meta::Pointee;
 use std::alloc;
-use std::ffi::{CStr, CString};
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+};
 
 // CStr
 
@@ -119,3 +122,46 @@ where
         }
     }
 }
+
+// Cow<CStr>
+
+impl<'a> Archive for Cow<'a, CStr> {
+    type Archived = ArchivedCString;
+    type Resolver = CStringResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedCString::resolve_from_c_str(self, pos, resolver, out);
+    }
+}
+
+impl<'a, S: Serializer + ?Sized> Serialize<S> for Cow<'a, CStr> {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedCString::serialize_from_c_str(self, serializer)
+    }
+}
+
+impl<'a, D: Fallible + ?Sized> Deserialize<Cow<'a, CStr>, D> for Archived<CString>
+where
+    CStr: DeserializeUnsized<CStr, D>,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<Cow<'a, CStr>, D::Error> {
+        Ok(Cow::Owned(self.deserialize(deserializer)?))
+    }
+}
+
+impl<'a> PartialEq<Cow<'a, CStr>> for ArchivedCString {
+    #[inline]
+    fn eq(&self, other: &Cow<'a, CStr>) -> bool {
+        PartialEq::eq(self.as_c_str(), &**other)
+    }
+}
+
+impl<'a> PartialEq<ArchivedCString> for Cow<'a, CStr> {
+    #[inline]
+    fn eq(&self, other: &ArchivedCString) -> bool {
+        PartialEq::eq(other.as_c_str(), &**self)
+    }
+}
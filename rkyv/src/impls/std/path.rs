@@ -0,0 +1,35 @@
+use crate::{
+    path::{os, ArchivedPathBuf, PathBufResolver},
+    ser::Serializer,
+    vec::ArchivedVec,
+    Archive, Deserialize, Fallible, Serialize,
+};
+use std::path::PathBuf;
+
+impl Archive for PathBuf {
+    type Archived = ArchivedPathBuf;
+    type Resolver = PathBufResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::<u8>::resolve_from_len(resolver.len, pos, resolver.bytes, out.cast());
+    }
+}
+
+impl<S: Serializer + ?Sized> Serialize<S> for PathBuf {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let bytes = os::encode(self);
+        Ok(PathBufResolver {
+            len: bytes.len(),
+            bytes: unsafe { ArchivedVec::<u8>::serialize_copy_from_slice(&bytes, serializer)? },
+        })
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<PathBuf, D> for ArchivedPathBuf {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<PathBuf, D::Error> {
+        Ok(self.to_path_buf())
+    }
+}
@@ -0,0 +1,148 @@
+//! Archived fields that are only validated when they are accessed.
+//!
+//! A [`LazyField`] stores its value out-of-line behind a relative pointer. Validating a
+//! `LazyField` only checks the relative pointer itself, so validating an archive that contains
+//! one doesn't touch the bytes of its value. The value is validated on demand with
+//! [`get`](LazyField::get).
+//!
+//! Fields can be archived as `LazyField`s with the [`Lazy`](crate::with::Lazy) wrapper or the
+//! `#[archive(lazy)]` field attribute.
+
+use crate::{ser::Serializer, RelPtr, Serialize};
+use core::fmt;
+
+/// An archived value that is validated separately from the archive that contains it.
+///
+/// This is a thin wrapper around a [`RelPtr`] to the archived value.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct LazyField<T> {
+    ptr: RelPtr<T>,
+}
+
+impl<T> LazyField<T> {
+    /// Returns a reference to the value without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The value must have been validated, or the archive must be trusted.
+    #[inline]
+    pub unsafe fn get_unchecked(&self) -> &T {
+        &*self.ptr.as_ptr()
+    }
+
+    /// Resolves a lazy field from the given parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the value of the field
+    #[inline]
+    pub unsafe fn resolve(pos: usize, resolver: LazyResolver, out: *mut Self) {
+        let (fp, fo) = out_field!(out.ptr);
+        RelPtr::emplace(pos + fp, resolver.pos, fo);
+    }
+
+    /// Serializes the value of a lazy field.
+    #[inline]
+    pub fn serialize_from_ref<U, S>(value: &U, serializer: &mut S) -> Result<LazyResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Serializer + ?Sized,
+    {
+        Ok(LazyResolver {
+            pos: serializer.serialize_value(value)?,
+        })
+    }
+}
+
+impl<T> fmt::Debug for LazyField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyField")
+            .field("ptr", &self.ptr.as_ptr())
+            .finish()
+    }
+}
+
+/// The resolver for a [`LazyField`].
+pub struct LazyResolver {
+    pos: usize,
+}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::{
+        validation::{
+            validators::{ArchiveError, DefaultValidator, DefaultValidatorError},
+            ArchiveContext, CheckArchiveError, CheckTypeError,
+        },
+        Fallible,
+    };
+    use bytecheck::CheckBytes;
+    use core::{convert::Infallible, mem::size_of, ptr};
+
+    impl<T> LazyField<T> {
+        /// Validates the value of the lazy field and returns a reference to it.
+        ///
+        /// `bytes` must be the archive that contains the lazy field. The value is validated as if
+        /// it were the root of its own archive, so each call validates the value again.
+        pub fn get<'a>(
+            &'a self,
+            bytes: &'a [u8],
+        ) -> Result<&'a T, CheckTypeError<T, DefaultValidator<'a>>>
+        where
+            T: CheckBytes<DefaultValidator<'a>>,
+        {
+            // The relative pointer must be inside of the archive for the validator to check it
+            let range = bytes.as_ptr_range();
+            let base = (self as *const Self).cast::<u8>();
+            let overrun = match (range.end as usize).checked_sub(base as usize) {
+                Some(remaining) => remaining < size_of::<Self>(),
+                None => true,
+            };
+            if base < range.start || overrun {
+                return Err(CheckArchiveError::ContextError(
+                    DefaultValidatorError::ArchiveError(ArchiveError::Overrun {
+                        ptr: base,
+                        size: size_of::<Self>(),
+                        range,
+                    }),
+                ));
+            }
+
+            let mut validator = DefaultValidator::new(bytes);
+            unsafe {
+                let ptr = validator
+                    .check_subtree_rel_ptr(&self.ptr)
+                    .map_err(CheckArchiveError::ContextError)?;
+
+                let range = validator
+                    .push_prefix_subtree(ptr)
+                    .map_err(CheckArchiveError::ContextError)?;
+                let result = T::check_bytes(ptr, &mut validator)
+                    .map_err(CheckArchiveError::CheckBytesError)?;
+                validator
+                    .pop_prefix_range(range)
+                    .map_err(CheckArchiveError::ContextError)?;
+
+                validator
+                    .finish()
+                    .map_err(CheckArchiveError::ContextError)?;
+                Ok(result)
+            }
+        }
+    }
+
+    impl<T, C: Fallible + ?Sized> CheckBytes<C> for LazyField<T> {
+        type Error = Infallible;
+
+        /// Only checks the relative pointer of the lazy field, and not the value it points to.
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            RelPtr::manual_check_bytes(ptr::addr_of!((*value).ptr), context)?;
+            Ok(&*value)
+        }
+    }
+};
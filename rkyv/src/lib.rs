@@ -59,10 +59,26 @@
 //!   compatibility optimized for big-endian architectures.
 //! - `archive_le`: Forces archives into a little-endian format. This guarantees cross-endian
 //!   compatibility optimized for little-endian architectures.
+//! - `control_flow`: Enables archiving `core::ops::ControlFlow`. Requires Rust 1.55 or later.
 //! - `copy`: Enables copy optimizations for packed copyable data types. Requires nightly.
 //! - `copy_unsafe`: Automatically opts all potentially copyable types into copy optimization. This
 //!   broadly improves performance but may cause uninitialized bytes to be copied to the output.
 //!   Requires nightly.
+//! - `hash_map_buckets`: Records the number of buckets in archived hash indexes so that it can be
+//!   tuned when serializing hash maps with `ArchivedHashMap::serialize_from_iter_with`. This adds a
+//!   `usize` to each hash index.
+//! - `hash_map_insertion_order`: Records the order that entries were serialized in for archived
+//!   hash maps so they can be iterated in that order. This adds a `u32` per entry to each hash map.
+//! - `hash_map_tombstones`: Adds a presence bit per entry to archived hash maps so entries can be
+//!   tombstoned in mutable archives with `ArchivedHashMap::tombstone_if`.
+//! - `hash_set_keys_only`: Archives hash sets as a hash index over an array of keys instead of as a
+//!   hash map with `()` values. This changes the archived format of hash sets, which no longer
+//!   carry the per-entry data that the `hash_map_*` features add.
+//! - `rayon`: Enables parallel construction of archived hash maps with
+//!   [`rayon`](https://docs.rs/rayon).
+//! - `relocate`: Enables recording relocation tables with `RelocationSerializer` so that relative
+//!   pointers can be rewritten as absolute addresses. Implies `std`.
+//! - `saturating`: Enables archiving `core::num::Saturating`. Requires Rust 1.74 or later.
 //! - `size_16`: Archives integral `*size` types as 16-bit integers. This is intended to be used
 //!   only for small archives and may not handle large, more general data.
 //! - `size_32`: Archives integral `*size` types as 32-bit integers. Enabled by default.
@@ -87,6 +103,9 @@
 //!
 //! Crates supported by rkyv:
 //!
+//! - [`bumpalo`](https://docs.rs/bumpalo) *Deserializing into an arena requires a
+//!   `BumpDeserializer`.*
+//! - [`bytes`](https://docs.rs/bytes)
 //! - [`indexmap`](https://docs.rs/indexmap)
 //! - [`rend`](https://docs.rs/rend) *Enabled automatically when using endian-specific archive
 //!   features.*
@@ -153,16 +172,22 @@ pub mod de;
 #[cfg(feature = "std")]
 pub mod ffi;
 mod impls;
+pub mod lazy;
 pub mod net;
 pub mod niche;
 pub mod ops;
 pub mod option;
+#[cfg(feature = "std")]
+pub mod path;
 pub mod rc;
 pub mod rel_ptr;
+#[cfg(feature = "relocate")]
+pub mod relocate;
 pub mod result;
 pub mod ser;
 pub mod string;
 pub mod time;
+pub mod tuple;
 pub mod util;
 #[cfg(feature = "validation")]
 pub mod validation;
@@ -180,7 +205,7 @@ pub use util::*;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "validation")))]
 pub use validation::{
     check_archived_root_with_context, check_archived_value_with_context,
-    validators::{check_archived_root, check_archived_value, from_bytes},
+    validators::{access, check_archived_root, check_archived_value, from_bytes},
 };
 
 /// A type that can produce an error.
@@ -12,7 +12,7 @@ use core::{
 pub struct ArchivedRange<T> {
     /// The lower bound of the range (inclusive).
     pub start: T,
-    /// The upper bound of the range (inclusive).
+    /// The upper bound of the range (exclusive).
     pub end: T,
 }
 
@@ -61,6 +61,10 @@ impl<T> RangeBounds<T> for ArchivedRange<T> {
 // RangeInclusive
 
 /// An archived [`RangeInclusive`](::core::ops::RangeInclusive).
+///
+/// Only the bounds of the range are archived. A `RangeInclusive` that has been exhausted by
+/// iteration is archived with the same bounds it had when it was exhausted, and it is no longer
+/// exhausted after being deserialized.
 #[derive(Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
 #[cfg_attr(feature = "strict", repr(C))]
@@ -236,3 +240,102 @@ impl<T> RangeBounds<T> for ArchivedRangeToInclusive<T> {
         Bound::Included(&self.end)
     }
 }
+
+/// An archived [`Bound`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+pub enum ArchivedBound<T> {
+    /// An inclusive bound
+    Included(T),
+    /// An exclusive bound
+    Excluded(T),
+    /// An infinite endpoint
+    Unbounded,
+}
+
+impl<T> ArchivedBound<T> {
+    /// Converts from `&ArchivedBound<T>` to `Bound<&T>`.
+    #[inline]
+    pub fn as_ref(&self) -> Bound<&T> {
+        match self {
+            ArchivedBound::Included(value) => Bound::Included(value),
+            ArchivedBound::Excluded(value) => Bound::Excluded(value),
+            ArchivedBound::Unbounded => Bound::Unbounded,
+        }
+    }
+
+    /// Returns `true` if the bound is [`Included`](ArchivedBound::Included).
+    #[inline]
+    pub const fn is_included(&self) -> bool {
+        matches!(self, ArchivedBound::Included(_))
+    }
+
+    /// Returns `true` if the bound is [`Excluded`](ArchivedBound::Excluded).
+    #[inline]
+    pub const fn is_excluded(&self) -> bool {
+        matches!(self, ArchivedBound::Excluded(_))
+    }
+
+    /// Returns `true` if the bound is [`Unbounded`](ArchivedBound::Unbounded).
+    #[inline]
+    pub const fn is_unbounded(&self) -> bool {
+        matches!(self, ArchivedBound::Unbounded)
+    }
+
+    /// Returns the endpoint of the bound, or `None` if it is unbounded.
+    #[inline]
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            ArchivedBound::Included(value) | ArchivedBound::Excluded(value) => Some(value),
+            ArchivedBound::Unbounded => None,
+        }
+    }
+}
+
+/// An archived [`ControlFlow`](::core::ops::ControlFlow).
+///
+/// `ControlFlow` can only be archived with the `control_flow` feature enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+pub enum ArchivedControlFlow<B, C> {
+    /// Move on to the next phase of the operation as normal
+    Continue(C),
+    /// Exit the operation without running subsequent phases
+    Break(B),
+}
+
+impl<B, C> ArchivedControlFlow<B, C> {
+    /// Returns `true` if this is a [`Break`](ArchivedControlFlow::Break) variant.
+    #[inline]
+    pub const fn is_break(&self) -> bool {
+        matches!(self, ArchivedControlFlow::Break(_))
+    }
+
+    /// Returns `true` if this is a [`Continue`](ArchivedControlFlow::Continue) variant.
+    #[inline]
+    pub const fn is_continue(&self) -> bool {
+        matches!(self, ArchivedControlFlow::Continue(_))
+    }
+
+    /// Returns the break value if this is a [`Break`](ArchivedControlFlow::Break) variant,
+    /// otherwise `None`.
+    #[inline]
+    pub fn break_value(&self) -> Option<&B> {
+        match self {
+            ArchivedControlFlow::Continue(_) => None,
+            ArchivedControlFlow::Break(value) => Some(value),
+        }
+    }
+
+    /// Returns the continue value if this is a [`Continue`](ArchivedControlFlow::Continue)
+    /// variant, otherwise `None`.
+    #[inline]
+    pub fn continue_value(&self) -> Option<&C> {
+        match self {
+            ArchivedControlFlow::Continue(value) => Some(value),
+            ArchivedControlFlow::Break(_) => None,
+        }
+    }
+}
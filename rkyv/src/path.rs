@@ -0,0 +1,210 @@
+//! Archived versions of path types.
+
+use crate::vec::{ArchivedVec, VecResolver};
+use core::{borrow::Borrow, fmt};
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+/// An archived [`PathBuf`](std::path::PathBuf).
+///
+/// The path is archived as the bytes of its platform string:
+///
+/// - On Unix, these are the raw bytes of the path.
+/// - On Windows, the wide characters of the path are encoded as
+///   [WTF-8](https://simonsapin.github.io/wtf-8/), which is identical to UTF-8 for paths that are
+///   valid Unicode.
+/// - On other platforms, paths are always valid UTF-8 and are archived as such.
+///
+/// Round trips are lossless on the platform that serialized the path. Paths that are valid
+/// Unicode are archived the same way on every platform.
+///
+/// There is no archived counterpart for `Path` because it can't be used as an unsized archived
+/// type. Use `PathBuf` fields instead.
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedPathBuf {
+    bytes: ArchivedVec<u8>,
+}
+
+impl ArchivedPathBuf {
+    /// Returns the archived bytes of the path.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Returns the archived path as a [`Path`].
+    ///
+    /// This is only available on Unix, where paths are arbitrary bytes.
+    #[cfg(unix)]
+    #[inline]
+    pub fn as_path(&self) -> &Path {
+        Path::new(OsStr::from_bytes(self.as_bytes()))
+    }
+
+    /// Decodes the archived path into a [`PathBuf`].
+    #[inline]
+    pub fn to_path_buf(&self) -> PathBuf {
+        os::decode(self.as_bytes())
+    }
+}
+
+/// The resolver for [`ArchivedPathBuf`].
+pub struct PathBufResolver {
+    pub(crate) len: usize,
+    pub(crate) bytes: VecResolver,
+}
+
+impl fmt::Debug for ArchivedPathBuf {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_path_buf().fmt(f)
+    }
+}
+
+impl Eq for ArchivedPathBuf {}
+
+impl PartialEq for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl PartialEq<Path> for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &Path) -> bool {
+        self.as_bytes() == &*os::encode(other)
+    }
+}
+
+impl PartialEq<PathBuf> for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &PathBuf) -> bool {
+        self.eq(other.as_path())
+    }
+}
+
+impl PartialEq<ArchivedPathBuf> for PathBuf {
+    #[inline]
+    fn eq(&self, other: &ArchivedPathBuf) -> bool {
+        other.eq(self)
+    }
+}
+
+#[cfg(unix)]
+impl AsRef<Path> for ArchivedPathBuf {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+#[cfg(unix)]
+impl Borrow<Path> for ArchivedPathBuf {
+    #[inline]
+    fn borrow(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+pub(crate) mod os {
+    use std::{
+        borrow::Cow,
+        path::{Path, PathBuf},
+    };
+
+    #[cfg(unix)]
+    #[inline]
+    pub fn encode(path: &Path) -> Cow<'_, [u8]> {
+        use std::os::unix::ffi::OsStrExt;
+
+        Cow::Borrowed(path.as_os_str().as_bytes())
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    pub fn decode(bytes: &[u8]) -> PathBuf {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        PathBuf::from(OsStr::from_bytes(bytes))
+    }
+
+    #[cfg(windows)]
+    pub fn encode(path: &Path) -> Cow<'_, [u8]> {
+        use std::os::windows::ffi::OsStrExt;
+
+        let mut bytes = Vec::new();
+        for c in core::char::decode_utf16(path.as_os_str().encode_wide()) {
+            match c {
+                Ok(c) => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                Err(e) => {
+                    // Unpaired surrogates are encoded like any other code point
+                    let s = e.unpaired_surrogate();
+                    bytes.extend_from_slice(&[
+                        0xe0 | (s >> 12) as u8,
+                        0x80 | ((s >> 6) & 0x3f) as u8,
+                        0x80 | (s & 0x3f) as u8,
+                    ]);
+                }
+            }
+        }
+        Cow::Owned(bytes)
+    }
+
+    #[cfg(windows)]
+    pub fn decode(bytes: &[u8]) -> PathBuf {
+        use std::{ffi::OsString, os::windows::ffi::OsStringExt};
+
+        const REPLACEMENT: u16 = 0xfffd;
+
+        let mut wide = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let lead = bytes[i];
+            let (len, init) = match lead {
+                0x00..=0x7f => (1, lead as u32),
+                0xc0..=0xdf => (2, (lead & 0x1f) as u32),
+                0xe0..=0xef => (3, (lead & 0x0f) as u32),
+                0xf0..=0xf7 => (4, (lead & 0x07) as u32),
+                _ => (0, 0),
+            };
+            let cont = bytes.get(i + 1..i + len.max(1)).unwrap_or(&[]);
+            if len == 0 || cont.len() != len - 1 || cont.iter().any(|&b| b & 0xc0 != 0x80) {
+                // Bytes that aren't WTF-8 can only come from another platform
+                wide.push(REPLACEMENT);
+                i += 1;
+                continue;
+            }
+
+            let c = cont.iter().fold(init, |c, &b| (c << 6) | (b & 0x3f) as u32);
+            if c > 0x10_ffff {
+                wide.push(REPLACEMENT);
+            } else if c >= 0x1_0000 {
+                let c = c - 0x1_0000;
+                wide.push(0xd800 | (c >> 10) as u16);
+                wide.push(0xdc00 | (c & 0x3ff) as u16);
+            } else {
+                wide.push(c as u16);
+            }
+            i += len;
+        }
+        PathBuf::from(OsString::from_wide(&wide))
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    #[inline]
+    pub fn encode(path: &Path) -> Cow<'_, [u8]> {
+        match path.to_string_lossy() {
+            Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+            Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    #[inline]
+    pub fn decode(bytes: &[u8]) -> PathBuf {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
@@ -4,7 +4,7 @@
 pub mod validation;
 
 use crate::{
-    ser::{Serializer, SharedSerializeRegistry},
+    ser::{Serializer, SharedSerializeRegistry, ValueSharedSerializer},
     ArchivePointee, ArchiveUnsized, MetadataResolver, RelPtr, SerializeUnsized,
 };
 use core::{borrow::Borrow, cmp, fmt, hash, marker::PhantomData, ops::Deref, pin::Pin, ptr};
@@ -14,6 +14,12 @@ use core::{borrow::Borrow, cmp, fmt, hash, marker::PhantomData, ops::Deref, pin:
 /// This is a thin wrapper around a [`RelPtr`] to the archived type paired with a "flavor" type.
 /// Because there may be many varieties of shared pointers and they may not be used together, the
 /// flavor helps check that memory is not being shared incorrectly during validation.
+///
+/// The original strong and weak counts are not archived. When deserialized, the counts of the
+/// resulting shared pointer reflect the number of archived `Rc`s and `Weak`s that were
+/// deserialized to it. The original counts include handles that were never serialized, and the
+/// only way to restore them would be to leak that many references, which would keep the value
+/// (or its allocation) alive forever.
 #[repr(transparent)]
 pub struct ArchivedRc<T: ArchivePointee + ?Sized, F>(RelPtr<T>, PhantomData<F>);
 
@@ -24,6 +30,39 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
         unsafe { &*self.0.as_ptr() }
     }
 
+    /// Gets the value of the `ArchivedRc`, or `None` if its relative pointer is null.
+    ///
+    /// This is intended for best-effort readers of archives that haven't been validated. It only
+    /// checks whether the relative pointer has a zero offset, so it doesn't guarantee that the
+    /// value is within the archive. Archives should still be validated before they're trusted.
+    ///
+    /// Shared pointers to zero-sized values may have a zero offset, so this can return `None` for
+    /// them even when the archive is valid.
+    #[inline]
+    pub fn try_get(&self) -> Option<&T> {
+        if self.0.is_null() {
+            None
+        } else {
+            Some(self.get())
+        }
+    }
+
+    /// Gets the archived metadata of the shared value.
+    #[inline]
+    pub fn metadata(&self) -> &T::ArchivedMetadata {
+        self.0.metadata()
+    }
+
+    /// Returns whether two `ArchivedRc`s point to the same shared value.
+    ///
+    /// Like [`Rc::ptr_eq`](std::rc::Rc::ptr_eq), this compares the addresses of the shared values
+    /// and ignores their metadata. Archived `Rc`s that were serialized from the same `Rc` always
+    /// point to the same shared value.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::eq(this.0.as_ptr().cast::<()>(), other.0.as_ptr().cast::<()>())
+    }
+
     /// Gets the pinned mutable value of this `ArchivedRc`.
     ///
     /// # Safety
@@ -66,6 +105,44 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
             metadata_resolver: value.serialize_metadata(serializer)?,
         })
     }
+
+    /// Serializes an archived `Rc` from a given shared pointer, sharing the archived value with
+    /// any equal value that was previously serialized this way.
+    #[inline]
+    pub fn serialize_from_shared_by_value<P, S>(
+        value: &P,
+        serializer: &mut S,
+    ) -> Result<RcResolver<MetadataResolver<P::Target>>, S::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: SerializeUnsized<S> + hash::Hash + Eq,
+        S: Serializer + ValueSharedSerializer + ?Sized,
+    {
+        Ok(RcResolver {
+            pos: serializer.serialize_shared_by_value(value)?,
+            metadata_resolver: (**value).serialize_metadata(serializer)?,
+        })
+    }
+}
+
+impl<U, F> ArchivedRc<[U], F> {
+    /// Returns the number of elements in the shared slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.get().len()
+    }
+
+    /// Returns `true` if the shared slice contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.get().is_empty()
+    }
+
+    /// Gets the shared slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[U] {
+        self.get()
+    }
 }
 
 impl<T: ArchivePointee + ?Sized, F> AsRef<T> for ArchivedRc<T, F> {
@@ -122,6 +122,9 @@ where
             .check_rel_ptr(rel_ptr)
             .map_err(SharedPointerError::ContextError)?;
 
+        // Shared pointers are registered before their values are checked, so a shared value is
+        // only ever descended into once. Its value must also lie in the subtree before it, which
+        // rejects shared pointers that form cycles.
         let type_id = TypeId::of::<Self>();
         if context
             .register_shared_ptr(ptr.cast(), type_id)
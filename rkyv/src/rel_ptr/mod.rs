@@ -73,6 +73,10 @@ pub trait Offset: Copy {
 
     /// Gets the offset as an `isize`.
     fn to_isize(&self) -> isize;
+
+    /// Whether this is the offset type of the default [`RawRelPtr`](crate::RawRelPtr).
+    #[doc(hidden)]
+    const IS_DEFAULT: bool = false;
 }
 
 macro_rules! impl_offset {
@@ -94,8 +98,10 @@ macro_rules! impl_offset {
             }
         }
     };
-    (@endian $ty:ty) => {
+    (@endian $ty:ty, $is_default:expr) => {
         impl Offset for Archived<$ty> {
+            const IS_DEFAULT: bool = $is_default;
+
             #[inline]
             fn between(from: usize, to: usize) -> Result<Self, OffsetError> {
                 // pointer::add and pointer::offset require that the computed offsets cannot
@@ -116,17 +122,17 @@ macro_rules! impl_offset {
 }
 
 impl_offset!(i8);
-impl_offset!(@endian i16);
+impl_offset!(@endian i16, cfg!(feature = "size_16"));
 #[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
-impl_offset!(@endian i32);
+impl_offset!(@endian i32, cfg!(feature = "size_32"));
 #[cfg(target_pointer_width = "64")]
-impl_offset!(@endian i64);
+impl_offset!(@endian i64, cfg!(feature = "size_64"));
 impl_offset!(u8);
-impl_offset!(@endian u16);
+impl_offset!(@endian u16, false);
 #[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
-impl_offset!(@endian u32);
+impl_offset!(@endian u32, false);
 #[cfg(target_pointer_width = "64")]
-impl_offset!(@endian u64);
+impl_offset!(@endian u64, false);
 
 /// Errors that can occur while creating raw relative pointers.
 #[derive(Debug)]
@@ -167,6 +173,8 @@ impl<O: Offset> RawRelPtr<O> {
     pub unsafe fn try_emplace(from: usize, to: usize, out: *mut Self) -> Result<(), OffsetError> {
         let offset = O::between(from, to)?;
         ptr::addr_of_mut!((*out).offset).write(offset);
+        #[cfg(feature = "relocate")]
+        crate::relocate::record_emplace::<O>(from, to);
         Ok(())
     }
 
@@ -263,6 +271,13 @@ pub type RawRelPtrU64 = RawRelPtr<Archived<u64>>;
 ///
 /// This is a strongly-typed version of [`RawRelPtr`].
 ///
+/// The offset type `O` sets how far the pointer can reach. rkyv's own types use
+/// [`crate::RelPtr`], whose offset width is chosen by the `size_16`, `size_32`, and `size_64`
+/// features. Custom [`Archive`](crate::Archive) implementations can use a narrower offset like
+/// `Archived<i16>` for pointers that are known to be close to their targets. The `try_*` functions
+/// return [`OffsetError::ExceedsStorageRange`] if an offset doesn't fit, so serializers can report
+/// the error instead of panicking.
+///
 /// See [`Archive`](crate::Archive) for an example of creating one.
 pub struct RelPtr<T: ArchivePointee + ?Sized, O> {
     raw_ptr: RawRelPtr<O>,
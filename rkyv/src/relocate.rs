@@ -0,0 +1,252 @@
+//! Relocation tables for loading archives with absolute pointers.
+//!
+//! Archives use relative pointers so that they can be accessed from any address without any
+//! fixups. Some consumers would rather pay a one-time cost to convert those relative pointers into
+//! absolute addresses when the archive is loaded, for example to hand the archive to code that
+//! doesn't understand relative pointers.
+//!
+//! A [`RelocationTable`] lists the position of every relative pointer in an archive along with the
+//! position it points to. It is recorded while serializing with a
+//! [`RelocationSerializer`](crate::ser::serializers::RelocationSerializer). The table can then be
+//! used to rewrite the pointers in a copy of the archive with [`relocate_in_place`], and to restore
+//! them with [`unrelocate_in_place`].
+//!
+//! Only non-null [`RelPtr`](crate::RelPtr)s with the default offset type are relocated. Other
+//! relative offsets, like the packed offsets of out-of-line
+//! [`ArchivedString`](crate::string::ArchivedString)s, are left as-is. Absolute addresses are
+//! stored in the same number of bytes as the offsets they replace, so archives must usually be
+//! serialized with the `size_64` feature to be relocated to addresses on 64-bit targets.
+
+use crate::{rel_ptr::Offset, Archived, FixedIsize, FixedUsize};
+use core::{cell::Cell, convert::TryFrom, fmt, mem, ptr};
+
+/// The location of a relative pointer in an archive and the location it points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Relocation {
+    /// The position of the relative pointer in the archive
+    pub pos: usize,
+    /// The position that the relative pointer points to
+    pub target: usize,
+}
+
+/// A list of the relative pointers in an archive, sorted by position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RelocationTable {
+    relocations: Vec<Relocation>,
+}
+
+impl RelocationTable {
+    /// Creates a relocation table from a list of relocations.
+    #[inline]
+    pub fn new(mut relocations: Vec<Relocation>) -> Self {
+        relocations.sort_unstable();
+        relocations.dedup();
+        Self { relocations }
+    }
+
+    /// Returns the number of relative pointers in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.relocations.len()
+    }
+
+    /// Returns whether the table has no relative pointers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.relocations.is_empty()
+    }
+
+    /// Returns the relocations in the table, sorted by position.
+    #[inline]
+    pub fn as_slice(&self) -> &[Relocation] {
+        &self.relocations
+    }
+
+    /// Returns an iterator over the relocations in the table, sorted by position.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, Relocation> {
+        self.relocations.iter()
+    }
+}
+
+std::thread_local! {
+    // A const initializer would require Rust 1.59
+    #[allow(clippy::missing_const_for_thread_local)]
+    static RECORDER: Cell<*mut Vec<Relocation>> = Cell::new(ptr::null_mut());
+}
+
+/// Records the relative pointers emplaced on this thread while `f` runs into `relocations`.
+///
+/// If relative pointers are already being recorded, they continue to be recorded by the outer
+/// call instead.
+pub(crate) fn record_relocations<R>(relocations: &mut Vec<Relocation>, f: impl FnOnce() -> R) -> R {
+    struct Restore;
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RECORDER.with(|recorder| recorder.set(ptr::null_mut()));
+        }
+    }
+
+    if RECORDER.with(|recorder| !recorder.get().is_null()) {
+        f()
+    } else {
+        RECORDER.with(|recorder| recorder.set(relocations));
+        let _restore = Restore;
+        f()
+    }
+}
+
+/// Records a relative pointer with an offset of type `O` if relative pointers are being recorded.
+#[inline]
+pub(crate) fn record_emplace<O: Offset>(from: usize, to: usize) {
+    // Null pointers must stay null, and other offset types can't hold an address
+    if from != to && O::IS_DEFAULT {
+        let _ = RECORDER.try_with(|recorder| {
+            let relocations = recorder.get();
+            if !relocations.is_null() {
+                // SAFETY: The recorder is only set while the relocations are borrowed by
+                // `record_relocations`.
+                unsafe {
+                    (*relocations).push(Relocation {
+                        pos: from,
+                        target: to,
+                    })
+                };
+            }
+        });
+    }
+}
+
+/// An error that can occur while relocating an archive.
+#[derive(Debug)]
+pub enum RelocateError {
+    /// A relocation was outside of the buffer
+    OutOfBounds {
+        /// The position of the relative pointer
+        pos: usize,
+        /// The length of the buffer
+        len: usize,
+    },
+    /// The value stored at a relocation did not match the table
+    Mismatch {
+        /// The position of the relative pointer
+        pos: usize,
+    },
+    /// A relocated value did not fit in the storage of the relative pointer
+    ExceedsStorageRange {
+        /// The position of the relative pointer
+        pos: usize,
+    },
+}
+
+impl fmt::Display for RelocateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelocateError::OutOfBounds { pos, len } => write!(
+                f,
+                "relocation at position {} is out of bounds for a buffer of length {}",
+                pos, len
+            ),
+            RelocateError::Mismatch { pos } => write!(
+                f,
+                "the value at position {} does not match the relocation table",
+                pos
+            ),
+            RelocateError::ExceedsStorageRange { pos } => write!(
+                f,
+                "the relocated value at position {} exceeds the storage range of the pointer",
+                pos
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RelocateError {}
+
+const SLOT_SIZE: usize = mem::size_of::<Archived<isize>>();
+
+#[inline]
+fn slot(buf: &[u8], pos: usize) -> Result<usize, RelocateError> {
+    match pos.checked_add(SLOT_SIZE) {
+        Some(end) if end <= buf.len() => Ok(pos),
+        _ => Err(RelocateError::OutOfBounds {
+            pos,
+            len: buf.len(),
+        }),
+    }
+}
+
+#[inline]
+fn read_offset(buf: &[u8], pos: usize) -> FixedIsize {
+    // SAFETY: `slot` checked that there are enough bytes for an offset, and any bit pattern is a
+    // valid offset.
+    from_archived!(unsafe { ptr::read_unaligned(buf[pos..].as_ptr().cast::<Archived<isize>>()) })
+}
+
+#[inline]
+fn read_address(buf: &[u8], pos: usize) -> FixedUsize {
+    // SAFETY: `slot` checked that there are enough bytes for an address, and any bit pattern is a
+    // valid address.
+    from_archived!(unsafe { ptr::read_unaligned(buf[pos..].as_ptr().cast::<Archived<usize>>()) })
+}
+
+/// Rewrites every relative pointer in the table as an absolute address.
+///
+/// Each relative pointer is replaced with `base + target`, stored in the same number of bytes as
+/// the offset it replaces. `base` is typically the address that the archive is loaded at. Once an
+/// archive has been relocated, it can no longer be accessed with the relative pointer API until it
+/// is restored with [`unrelocate_in_place`].
+///
+/// Every relocation is checked before any are applied, so the buffer is left unchanged if an error
+/// is returned.
+pub fn relocate_in_place(
+    buf: &mut [u8],
+    table: &RelocationTable,
+    base: usize,
+) -> Result<(), RelocateError> {
+    for &Relocation { pos, target } in table.iter() {
+        let pos = slot(buf, pos)?;
+        if pos as isize + read_offset(buf, pos) as isize != target as isize {
+            return Err(RelocateError::Mismatch { pos });
+        }
+        base.checked_add(target)
+            .and_then(|address| FixedUsize::try_from(address).ok())
+            .ok_or(RelocateError::ExceedsStorageRange { pos })?;
+    }
+
+    for &Relocation { pos, target } in table.iter() {
+        let value: Archived<usize> = to_archived!((base + target) as FixedUsize);
+        // SAFETY: The slot was checked to have enough bytes for an address.
+        unsafe { ptr::write_unaligned(buf[pos..].as_mut_ptr().cast::<Archived<usize>>(), value) };
+    }
+    Ok(())
+}
+
+/// Restores the relative pointers of an archive relocated with [`relocate_in_place`].
+///
+/// `base` must be the same base address that the archive was relocated with.
+///
+/// Every relocation is checked before any are restored, so the buffer is left unchanged if an
+/// error is returned.
+pub fn unrelocate_in_place(
+    buf: &mut [u8],
+    table: &RelocationTable,
+    base: usize,
+) -> Result<(), RelocateError> {
+    for &Relocation { pos, target } in table.iter() {
+        let pos = slot(buf, pos)?;
+        if base.checked_add(target) != Some(read_address(buf, pos) as usize) {
+            return Err(RelocateError::Mismatch { pos });
+        }
+        FixedIsize::try_from(target as isize - pos as isize)
+            .map_err(|_| RelocateError::ExceedsStorageRange { pos })?;
+    }
+
+    for &Relocation { pos, target } in table.iter() {
+        let value: Archived<isize> = to_archived!((target as isize - pos as isize) as FixedIsize);
+        // SAFETY: The slot was checked to have enough bytes for an offset.
+        unsafe { ptr::write_unaligned(buf[pos..].as_mut_ptr().cast::<Archived<isize>>(), value) };
+    }
+    Ok(())
+}
@@ -3,7 +3,29 @@
 pub mod serializers;
 
 use crate::{Archive, ArchiveUnsized, Fallible, RelPtr, Serialize, SerializeUnsized};
-use core::{alloc::Layout, mem, ptr::NonNull, slice};
+use core::{alloc::Layout, any, borrow::Borrow, hash::Hash, mem, ops::Deref, ptr::NonNull, slice};
+
+/// Returns the number of padding bytes needed to align `pos` to `align`.
+#[inline]
+pub(crate) fn align_padding(pos: usize, align: usize) -> usize {
+    let mask = align - 1;
+    debug_assert_eq!(align & mask, 0);
+
+    (align - (pos & mask)) & mask
+}
+
+/// Pads the serializer to the given alignment and returns its new position.
+///
+/// This is the default behavior of [`Serializer::align`], shared with serializers that override
+/// `align` to track the alignments they have been asked for.
+#[inline]
+pub(crate) fn pad_to_align<S: Serializer + ?Sized>(
+    serializer: &mut S,
+    align: usize,
+) -> Result<usize, S::Error> {
+    serializer.pad(align_padding(serializer.pos(), align))?;
+    Ok(serializer.pos())
+}
 
 /// A byte sink that knows where it is.
 ///
@@ -33,11 +55,25 @@ pub trait Serializer: Fallible {
     /// Aligns the position of the serializer to the given alignment.
     #[inline]
     fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
-        let mask = align - 1;
-        debug_assert_eq!(align & mask, 0);
+        pad_to_align(self, align)
+    }
 
-        self.pad((align - (self.pos() & mask)) & mask)?;
-        Ok(self.pos())
+    /// Returns the largest alignment that the serializer has been aligned to so far.
+    ///
+    /// An archive written by the serializer must be placed at an address with at least this
+    /// alignment to be accessed. Serializers that do not track alignment always return 1.
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        1
+    }
+
+    /// Returns the recorder that objects written by [`resolve_aligned`](Serializer::resolve_aligned)
+    /// are reported to, if any.
+    ///
+    /// Serializers do not record offsets by default.
+    #[inline]
+    fn offset_recorder(&mut self) -> Option<&mut dyn OffsetRecorder> {
+        None
     }
 
     /// Aligns the position of the serializer to be suitable to write the given type.
@@ -69,6 +105,9 @@ pub trait Serializer: Fallible {
         let data = resolved.as_ptr().cast::<u8>();
         let len = mem::size_of::<T::Archived>();
         self.write(slice::from_raw_parts(data, len))?;
+        if let Some(recorder) = self.offset_recorder() {
+            recorder.record_offset(any::type_name::<T::Archived>(), pos, len);
+        }
         Ok(pos)
     }
 
@@ -121,6 +160,62 @@ pub trait Serializer: Fallible {
     }
 }
 
+/// A sink for the positions of objects as a serializer writes them.
+///
+/// A serializer that returns a recorder from [`offset_recorder`](Serializer::offset_recorder)
+/// reports every object it writes with [`resolve_aligned`](Serializer::resolve_aligned), including
+/// the root object. This can be used to build a table of where each object is in an archive.
+pub trait OffsetRecorder {
+    /// Records that an archived object of the named type was written at `pos` and occupies `size`
+    /// bytes.
+    fn record_offset(&mut self, type_name: &'static str, pos: usize, size: usize);
+}
+
+/// A serializer that can record which field was being serialized when an error occurred.
+///
+/// Types that derive `Serialize` with `#[archive(trace)]` pass every error returned while
+/// serializing one of their fields through [`trace_field`](TraceSerializer::trace_field). Because
+/// errors propagate outward, the innermost field is traced first and the outermost field last.
+pub trait TraceSerializer: Fallible {
+    /// Attaches the names of the type and field being serialized to an error.
+    ///
+    /// Fields of tuple structs and tuple variants are named by their index, and the type name of an
+    /// enum variant is `Enum::Variant`.
+    fn trace_field(
+        error: Self::Error,
+        type_name: &'static str,
+        field_name: &'static str,
+    ) -> Self::Error;
+}
+
+/// A value that is produced on demand when it is serialized.
+///
+/// This is used by [`ArchivedHashMap::serialize_from_iter_lazy`] to avoid materializing values
+/// until the map containing them is serialized. References to values that already exist implement
+/// `LazySerialize` by returning themselves.
+///
+/// [`ArchivedHashMap::serialize_from_iter_lazy`]:
+/// crate::collections::ArchivedHashMap::serialize_from_iter_lazy
+pub trait LazySerialize<S: Fallible + ?Sized> {
+    /// The type of the produced value.
+    type Value: Serialize<S>;
+    /// The produced value, which may own or borrow it.
+    type Output: Borrow<Self::Value>;
+
+    /// Produces the value to serialize.
+    fn materialize(self) -> Self::Output;
+}
+
+impl<'a, T: Serialize<S>, S: Fallible + ?Sized> LazySerialize<S> for &'a T {
+    type Value = T;
+    type Output = &'a T;
+
+    #[inline]
+    fn materialize(self) -> Self::Output {
+        self
+    }
+}
+
 // Someday this can probably be replaced with alloc::Allocator
 
 /// A serializer that can allocate scratch space.
@@ -167,8 +262,35 @@ pub trait SharedSerializeRegistry: Fallible {
         self.add_shared_ptr(value as *const T as *const u8, pos)
     }
 
+    /// Marks a shared pointer as being serialized until its position is added to the registry.
+    ///
+    /// Registries that track this should return an error if the pointer is already being
+    /// serialized, since the shared value must then refer back to itself. By default, this does
+    /// nothing.
+    #[inline]
+    fn begin_shared_ptr(&mut self, value: *const u8) -> Result<(), Self::Error> {
+        let _ = value;
+        Ok(())
+    }
+
+    /// Unmarks a shared pointer that was marked with
+    /// [`begin_shared_ptr`](SharedSerializeRegistry::begin_shared_ptr) but failed to serialize.
+    ///
+    /// By default, this does nothing.
+    #[inline]
+    fn cancel_shared_ptr(&mut self, value: *const u8) {
+        let _ = value;
+    }
+
     /// Archives the given shared value and returns its position. If the value has already been
     /// added then it returns the position of the previously added value.
+    ///
+    /// A value is only added to the registry after it has been serialized, because its
+    /// dependencies are written before it and its position is not known until then. Shared values
+    /// that refer back to themselves while being serialized (for example, through a `Weak` created
+    /// with `Arc::new_cyclic`) can't be archived. Registries that implement
+    /// [`begin_shared_ptr`](SharedSerializeRegistry::begin_shared_ptr) return an error for them
+    /// instead of recursing without end.
     #[inline]
     fn serialize_shared<T: SerializeUnsized<Self> + ?Sized>(
         &mut self,
@@ -180,9 +302,72 @@ pub trait SharedSerializeRegistry: Fallible {
         if let Some(pos) = self.get_shared(value) {
             Ok(pos)
         } else {
-            let pos = value.serialize_unsized(self)?;
+            let ptr = value as *const T as *const u8;
+            self.begin_shared_ptr(ptr)?;
+            let pos = match value.serialize_unsized(self) {
+                Ok(pos) => pos,
+                Err(e) => {
+                    self.cancel_shared_ptr(ptr);
+                    return Err(e);
+                }
+            };
             self.add_shared(value, pos)?;
             Ok(pos)
         }
     }
 }
+
+/// A registry that tracks serialized shared memory by value as well as by address.
+///
+/// This allows equal shared values in different allocations to be serialized only once. Shared
+/// pointers are added to the registry by cloning them, which keeps their values alive so they can
+/// be compared against later values.
+pub trait ValueSharedSerializer: SharedSerializeRegistry {
+    /// Gets the position of a previously-added shared value that is equal to the value of the given
+    /// shared pointer.
+    ///
+    /// Only values added with the same type of shared pointer are compared. Returns `None` if no
+    /// equal value has been added.
+    fn get_shared_value<P>(&self, value: &P) -> Option<usize>
+    where
+        P: Deref + 'static,
+        P::Target: Hash + Eq;
+
+    /// Adds the position of a shared value to the registry so that equal values can share it.
+    ///
+    /// The registry keeps a clone of the shared pointer until it is dropped.
+    fn add_shared_value<P>(&mut self, value: &P, pos: usize) -> Result<(), Self::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: Hash + Eq;
+
+    /// Archives the value of the given shared pointer and returns its position. If the value or
+    /// any value equal to it has already been added then it returns the position of the previously
+    /// added value.
+    #[inline]
+    fn serialize_shared_by_value<P>(&mut self, value: &P) -> Result<usize, Self::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: SerializeUnsized<Self> + Hash + Eq,
+        Self: Serializer,
+    {
+        if let Some(pos) = self.get_shared(&**value) {
+            Ok(pos)
+        } else if let Some(pos) = self.get_shared_value(value) {
+            Ok(pos)
+        } else {
+            let ptr = &**value as *const P::Target as *const u8;
+            self.begin_shared_ptr(ptr)?;
+            let pos = match (**value).serialize_unsized(self) {
+                Ok(pos) => pos,
+                Err(e) => {
+                    self.cancel_shared_ptr(ptr);
+                    return Err(e);
+                }
+            };
+            self.add_shared(&**value, pos)?;
+            self.add_shared_value(value, pos)?;
+            Ok(pos)
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use crate::{
-    ser::{serializers::BufferScratch, ScratchSpace, Serializer, SharedSerializeRegistry},
+    collections::hash_index::{ArchiveHasher, HashBuilder},
+    ser::{
+        pad_to_align, serializers::BufferScratch, ScratchSpace, Serializer,
+        SharedSerializeRegistry, ValueSharedSerializer,
+    },
     AlignedBytes, AlignedVec, Archive, ArchiveUnsized, Fallible, RelPtr,
 };
 #[cfg(not(feature = "std"))]
@@ -8,15 +12,19 @@ use ::alloc::{alloc, boxed::Box, vec::Vec};
 use ::std::alloc;
 use core::{
     alloc::Layout,
+    any::Any,
     borrow::{Borrow, BorrowMut},
     convert::Infallible,
-    fmt, mem,
+    fmt,
+    hash::{Hash, Hasher},
+    mem,
+    ops::Deref,
     ptr::NonNull,
 };
 #[cfg(not(feature = "std"))]
-use hashbrown::hash_map;
+use hashbrown::{hash_map, hash_set};
 #[cfg(feature = "std")]
-use std::collections::hash_map;
+use std::collections::{hash_map, hash_set};
 
 /// A serializer made specifically to work with [`AlignedVec`](crate::util::AlignedVec).
 ///
@@ -25,13 +33,17 @@ use std::collections::hash_map;
 #[derive(Debug)]
 pub struct AlignedSerializer<A> {
     inner: A,
+    max_align: usize,
 }
 
 impl<A: Borrow<AlignedVec>> AlignedSerializer<A> {
     /// Creates a new `AlignedSerializer` by wrapping a `Borrow<AlignedVec>`.
     #[inline]
     pub fn new(inner: A) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            max_align: 1,
+        }
     }
 
     /// Consumes the serializer and returns the underlying type.
@@ -41,11 +53,68 @@ impl<A: Borrow<AlignedVec>> AlignedSerializer<A> {
     }
 }
 
+impl<A: BorrowMut<AlignedVec>> AlignedSerializer<A> {
+    /// Creates a new `AlignedSerializer` that resumes writing to an existing archive buffer at the
+    /// given position.
+    ///
+    /// The buffer is truncated to `pos`, so any bytes past `pos` are discarded. If `pos` is past
+    /// the end of the buffer, the buffer is padded with zeroes up to it instead. Everything before
+    /// `pos` is left untouched, so the relative pointers in previously serialized objects stay
+    /// valid and those objects can still be accessed at the positions they were serialized at.
+    ///
+    /// The root of an archive is always the last object written to it, so after appending, the
+    /// objects from earlier batches are no longer reachable through
+    /// [`archived_root`](crate::archived_root). Keep the positions returned by
+    /// [`serialize_value`](Serializer::serialize_value) and either access each object with
+    /// [`archived_value`](crate::archived_value) or write an index of those positions as the last
+    /// object of each batch. Because positions are absolute, an index written
+    /// by a later batch can refer to entries from every earlier batch.
+    ///
+    /// The alignment reported by [`current_alignment`](Serializer::current_alignment) only accounts
+    /// for objects written after resuming.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::{
+    ///     archived_root, archived_value, from_archived,
+    ///     ser::{serializers::AlignedSerializer, Serializer},
+    ///     AlignedVec,
+    /// };
+    ///
+    /// let mut serializer = AlignedSerializer::new(AlignedVec::new());
+    /// let first = serializer.serialize_value(&"first".to_string()).unwrap();
+    /// let buf = serializer.into_inner();
+    ///
+    /// let len = buf.len();
+    /// let mut serializer = AlignedSerializer::from_existing(buf, len);
+    /// let second = serializer.serialize_value(&"second".to_string()).unwrap();
+    /// serializer.serialize_value(&[first as u32, second as u32]).unwrap();
+    /// let buf = serializer.into_inner();
+    ///
+    /// let index = unsafe { archived_root::<[u32; 2]>(buf.as_ref()) };
+    /// let first_pos = from_archived!(index[0]) as usize;
+    /// let second_pos = from_archived!(index[1]) as usize;
+    /// let first = unsafe { archived_value::<String>(buf.as_ref(), first_pos) };
+    /// let second = unsafe { archived_value::<String>(buf.as_ref(), second_pos) };
+    /// assert_eq!(first, "first");
+    /// assert_eq!(second, "second");
+    /// ```
+    #[inline]
+    pub fn from_existing(mut inner: A, pos: usize) -> Self {
+        inner.borrow_mut().resize(pos, 0);
+        Self {
+            inner,
+            max_align: 1,
+        }
+    }
+}
+
 impl<A: Default> Default for AlignedSerializer<A> {
     #[inline]
     fn default() -> Self {
         Self {
             inner: A::default(),
+            max_align: 1,
         }
     }
 }
@@ -66,6 +135,17 @@ impl<A: Borrow<AlignedVec> + BorrowMut<AlignedVec>> Serializer for AlignedSerial
         Ok(())
     }
 
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.max_align = self.max_align.max(align);
+        pad_to_align(self, align)
+    }
+
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.max_align
+    }
+
     #[inline]
     unsafe fn resolve_aligned<T: Archive + ?Sized>(
         &mut self,
@@ -323,11 +403,131 @@ impl ScratchSpace for AllocScratch {
     }
 }
 
+/// Scratch space that uses the global allocator and keeps freed allocations for reuse.
+///
+/// Allocations are rounded up to a power-of-two size class. When scratch space is popped, its
+/// memory is returned to a free list for its size class instead of to the global allocator, so
+/// later requests of a similar size can reuse it. This makes it well-suited for serializing many
+/// values with the same scratch space.
+#[derive(Debug)]
+pub struct PooledScratch {
+    allocations: Vec<(*mut u8, Layout, Layout)>,
+    free_lists: hash_map::HashMap<Layout, Vec<*mut u8>>,
+    allocation_count: usize,
+}
+
+// SAFETY: PooledScratch is safe to send to another thread
+// This trait is not automatically implemented because the struct contains a pointer
+unsafe impl Send for PooledScratch {}
+
+// SAFETY: PooledScratch is safe to share between threads
+// This trait is not automatically implemented because the struct contains a pointer
+unsafe impl Sync for PooledScratch {}
+
+impl PooledScratch {
+    /// Creates a new pooled scratch allocator.
+    pub fn new() -> Self {
+        Self {
+            allocations: Vec::new(),
+            free_lists: hash_map::HashMap::new(),
+            allocation_count: 0,
+        }
+    }
+
+    /// Returns the number of allocations that have been made with the global allocator.
+    ///
+    /// Scratch space that is reused from the pool does not count as a new allocation.
+    pub fn allocation_count(&self) -> usize {
+        self.allocation_count
+    }
+
+    /// Returns the size class of the given layout. Layouts too large to round up to a power of two
+    /// are their own size class.
+    #[inline]
+    fn size_class(layout: Layout) -> Layout {
+        layout
+            .size()
+            .max(layout.align())
+            .checked_next_power_of_two()
+            .and_then(|size| Layout::from_size_align(size, layout.align()).ok())
+            .unwrap_or(layout)
+    }
+}
+
+impl Drop for PooledScratch {
+    fn drop(&mut self) {
+        for (ptr, class, _) in self.allocations.drain(..).rev() {
+            unsafe {
+                alloc::dealloc(ptr, class);
+            }
+        }
+        for (class, free_list) in self.free_lists.drain() {
+            for ptr in free_list {
+                unsafe {
+                    alloc::dealloc(ptr, class);
+                }
+            }
+        }
+    }
+}
+
+impl Default for PooledScratch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fallible for PooledScratch {
+    type Error = AllocScratchError;
+}
+
+impl ScratchSpace for PooledScratch {
+    #[inline]
+    unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
+        let class = Self::size_class(layout);
+        let result_ptr = match self.free_lists.get_mut(&class).and_then(|list| list.pop()) {
+            Some(ptr) => ptr,
+            None => {
+                let ptr = alloc::alloc(class);
+                assert!(!ptr.is_null());
+                self.allocation_count += 1;
+                ptr
+            }
+        };
+        self.allocations.push((result_ptr, class, layout));
+        let result_slice = ptr_meta::from_raw_parts_mut(result_ptr.cast(), layout.size());
+        let result = NonNull::new_unchecked(result_slice);
+        Ok(result)
+    }
+
+    #[inline]
+    unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), Self::Error> {
+        if let Some(&(last_ptr, class, last_layout)) = self.allocations.last() {
+            if ptr.as_ptr() == last_ptr && layout == last_layout {
+                self.free_lists.entry(class).or_default().push(last_ptr);
+                self.allocations.pop();
+                Ok(())
+            } else {
+                Err(AllocScratchError::NotPoppedInReverseOrder {
+                    expected: last_ptr,
+                    expected_layout: last_layout,
+                    actual: ptr.as_ptr(),
+                    actual_layout: layout,
+                })
+            }
+        } else {
+            Err(AllocScratchError::NoAllocationsToPop)
+        }
+    }
+}
+
 /// An error that can occur while serializing shared pointers.
 #[derive(Debug)]
 pub enum SharedSerializeMapError {
     /// A shared pointer was added multiple times
     DuplicateSharedPointer(*const u8),
+    /// A shared pointer was reached again while its value was being serialized
+    CyclicSharedPointer(*const u8),
 }
 
 // SAFETY: SharedSerializeMapError is safe to send to another thread
@@ -342,6 +542,7 @@ impl fmt::Display for SharedSerializeMapError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::DuplicateSharedPointer(p) => write!(f, "duplicate shared pointer: {:p}", p),
+            Self::CyclicSharedPointer(p) => write!(f, "cyclic shared pointer: {:p}", p),
         }
     }
 }
@@ -357,6 +558,7 @@ const _: () = {
 #[derive(Debug)]
 pub struct SharedSerializeMap {
     shared_resolvers: hash_map::HashMap<*const u8, usize>,
+    pending: hash_set::HashSet<*const u8>,
 }
 
 // SAFETY: SharedSerializeMap is safe to send to another thread
@@ -373,8 +575,16 @@ impl SharedSerializeMap {
     pub fn new() -> Self {
         Self {
             shared_resolvers: hash_map::HashMap::new(),
+            pending: hash_set::HashSet::new(),
         }
     }
+
+    /// Resets the shared registry map to its initial state, forgetting every shared pointer.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.shared_resolvers.clear();
+        self.pending.clear();
+    }
 }
 
 impl Default for SharedSerializeMap {
@@ -400,8 +610,117 @@ impl SharedSerializeRegistry for SharedSerializeMap {
             }
             hash_map::Entry::Vacant(e) => {
                 e.insert(pos);
+                self.pending.remove(&value);
                 Ok(())
             }
         }
     }
+
+    fn begin_shared_ptr(&mut self, value: *const u8) -> Result<(), Self::Error> {
+        if self.pending.insert(value) {
+            Ok(())
+        } else {
+            Err(SharedSerializeMapError::CyclicSharedPointer(value))
+        }
+    }
+
+    fn cancel_shared_ptr(&mut self, value: *const u8) {
+        self.pending.remove(&value);
+    }
+}
+
+/// An adapter that adds shared serialization support to a serializer and also shares equal
+/// values between different shared pointers.
+///
+/// This is required by the [`ShareByValue`](crate::with::ShareByValue) wrapper. Every shared
+/// pointer serialized by value is cloned and kept until the map is dropped so that later values
+/// can be compared against it. Because those clones may be `Rc`s, this map is neither `Send` nor
+/// `Sync`.
+#[derive(Debug, Default)]
+pub struct SharedValueSerializeMap {
+    shared: SharedSerializeMap,
+    shared_values: hash_map::HashMap<u64, Vec<SharedValue>>,
+}
+
+/// A clone of a shared pointer and the position its value was serialized at.
+type SharedValue = (Box<dyn Any>, usize);
+
+impl SharedValueSerializeMap {
+    /// Creates a new shared value registry map.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            shared: SharedSerializeMap::new(),
+            shared_values: hash_map::HashMap::new(),
+        }
+    }
+
+    /// Resets the shared value registry map to its initial state, forgetting every shared pointer
+    /// and dropping the clones of the shared values.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.shared.reset();
+        self.shared_values.clear();
+    }
+
+    #[inline]
+    fn hash_value<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = HashBuilder::make_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Fallible for SharedValueSerializeMap {
+    type Error = SharedSerializeMapError;
+}
+
+impl SharedSerializeRegistry for SharedValueSerializeMap {
+    #[inline]
+    fn get_shared_ptr(&self, value: *const u8) -> Option<usize> {
+        self.shared.get_shared_ptr(value)
+    }
+
+    #[inline]
+    fn add_shared_ptr(&mut self, value: *const u8, pos: usize) -> Result<(), Self::Error> {
+        self.shared.add_shared_ptr(value, pos)
+    }
+
+    #[inline]
+    fn begin_shared_ptr(&mut self, value: *const u8) -> Result<(), Self::Error> {
+        self.shared.begin_shared_ptr(value)
+    }
+
+    #[inline]
+    fn cancel_shared_ptr(&mut self, value: *const u8) {
+        self.shared.cancel_shared_ptr(value)
+    }
+}
+
+impl ValueSharedSerializer for SharedValueSerializeMap {
+    fn get_shared_value<P>(&self, value: &P) -> Option<usize>
+    where
+        P: Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.shared_values
+            .get(&Self::hash_value(&**value))?
+            .iter()
+            .find_map(|(shared, pos)| match shared.downcast_ref::<P>() {
+                Some(shared) if **shared == **value => Some(*pos),
+                _ => None,
+            })
+    }
+
+    fn add_shared_value<P>(&mut self, value: &P, pos: usize) -> Result<(), Self::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.shared_values
+            .entry(Self::hash_value(&**value))
+            .or_default()
+            .push((Box::new(value.clone()), pos));
+        Ok(())
+    }
 }
@@ -1,10 +1,11 @@
 use crate::{
-    ser::{ScratchSpace, Serializer},
-    Fallible,
+    ser::{align_padding, pad_to_align, ScratchSpace, Serializer},
+    Archive, ArchiveUnsized, Fallible, RelPtr,
 };
 use core::{
     alloc::Layout,
-    fmt,
+    convert::Infallible,
+    fmt, mem,
     ops::DerefMut,
     ptr::{copy_nonoverlapping, NonNull},
 };
@@ -85,6 +86,7 @@ const _: () = {
 pub struct BufferSerializer<T> {
     inner: T,
     pos: usize,
+    max_align: usize,
 }
 
 impl<T> BufferSerializer<T> {
@@ -99,7 +101,11 @@ impl<T> BufferSerializer<T> {
     /// be correct).
     #[inline]
     pub fn with_pos(inner: T, pos: usize) -> Self {
-        Self { inner, pos }
+        Self {
+            inner,
+            pos,
+            max_align: 1,
+        }
     }
 
     /// Consumes the serializer and returns the underlying type.
@@ -147,6 +153,115 @@ impl<T: AsMut<[u8]>> Serializer for BufferSerializer<T> {
             Ok(())
         }
     }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.max_align = self.max_align.max(align);
+        pad_to_align(self, align)
+    }
+
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.max_align
+    }
+}
+
+/// A serializer that writes nothing and only counts the number of bytes an archive would take up.
+///
+/// Alignment padding is counted the same way as a real serializer, so serializing a value with a
+/// `SizeSerializer` results in the same position as serializing it into a buffer. Pair it with
+/// scratch space and a shared registry (see
+/// [`AllocSizeSerializer`](crate::ser::serializers::AllocSizeSerializer)) to count values that need
+/// them.
+///
+/// # Examples
+/// ```
+/// use rkyv::ser::{serializers::SizeSerializer, Serializer};
+///
+/// let mut serializer = SizeSerializer::new();
+/// serializer.serialize_value(&(1u8, 2u32)).unwrap();
+/// assert_eq!(serializer.pos(), 8);
+/// ```
+#[derive(Debug)]
+pub struct SizeSerializer {
+    pos: usize,
+    max_align: usize,
+}
+
+impl SizeSerializer {
+    /// Creates a new size serializer starting at position 0.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            pos: 0,
+            max_align: 1,
+        }
+    }
+}
+
+impl Default for SizeSerializer {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fallible for SizeSerializer {
+    type Error = Infallible;
+}
+
+impl Serializer for SizeSerializer {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.pos += bytes.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn pad(&mut self, padding: usize) -> Result<(), Self::Error> {
+        self.pos += padding;
+        Ok(())
+    }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.max_align = self.max_align.max(align);
+        self.pos += align_padding(self.pos, align);
+        Ok(self.pos)
+    }
+
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.max_align
+    }
+
+    #[inline]
+    unsafe fn resolve_aligned<T: Archive + ?Sized>(
+        &mut self,
+        _: &T,
+        _: T::Resolver,
+    ) -> Result<usize, Self::Error> {
+        let pos = self.pos;
+        self.pos += mem::size_of::<T::Archived>();
+        Ok(pos)
+    }
+
+    #[inline]
+    unsafe fn resolve_unsized_aligned<T: ArchiveUnsized + ?Sized>(
+        &mut self,
+        _: &T,
+        _: usize,
+        _: T::MetadataResolver,
+    ) -> Result<usize, Self::Error> {
+        let from = self.pos;
+        self.pos += mem::size_of::<RelPtr<T::Archived>>();
+        Ok(from)
+    }
 }
 
 /// Errors that can occur when using a fixed-size allocator.
@@ -3,16 +3,21 @@
 #[cfg(feature = "alloc")]
 mod alloc;
 mod core;
+#[cfg(feature = "relocate")]
+mod relocation;
 #[cfg(feature = "std")]
 mod std;
 
 #[cfg(feature = "alloc")]
 use crate::AlignedVec;
 use crate::{
-    ser::{ScratchSpace, Serializer, SharedSerializeRegistry},
+    ser::{
+        align_padding, OffsetRecorder, ScratchSpace, Serializer, SharedSerializeRegistry,
+        ValueSharedSerializer,
+    },
     AlignedBytes, Archive, ArchiveUnsized, Fallible, Infallible,
 };
-use ::core::{alloc::Layout, fmt, ptr::NonNull};
+use ::core::{alloc::Layout, fmt, hash::Hash, ops::Deref, ptr::NonNull};
 
 #[doc(inline)]
 #[cfg(feature = "alloc")]
@@ -20,6 +25,9 @@ pub use self::alloc::*;
 #[doc(inline)]
 pub use self::core::*;
 #[doc(inline)]
+#[cfg(feature = "relocate")]
+pub use self::relocation::*;
+#[doc(inline)]
 #[cfg(feature = "std")]
 pub use self::std::*;
 
@@ -145,6 +153,16 @@ impl<S: Serializer, C: Fallible, H: Fallible> Serializer for CompositeSerializer
             .map_err(CompositeSerializerError::SerializerError)
     }
 
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.serializer.current_alignment()
+    }
+
+    #[inline]
+    fn offset_recorder(&mut self) -> Option<&mut dyn OffsetRecorder> {
+        self.serializer.offset_recorder()
+    }
+
     #[inline]
     fn align_for<T>(&mut self) -> Result<usize, Self::Error> {
         self.serializer
@@ -206,6 +224,42 @@ impl<S: Fallible, C: Fallible, H: SharedSerializeRegistry> SharedSerializeRegist
             .add_shared_ptr(value, pos)
             .map_err(CompositeSerializerError::SharedError)
     }
+
+    #[inline]
+    fn begin_shared_ptr(&mut self, value: *const u8) -> Result<(), Self::Error> {
+        self.shared
+            .begin_shared_ptr(value)
+            .map_err(CompositeSerializerError::SharedError)
+    }
+
+    #[inline]
+    fn cancel_shared_ptr(&mut self, value: *const u8) {
+        self.shared.cancel_shared_ptr(value)
+    }
+}
+
+impl<S: Fallible, C: Fallible, H: ValueSharedSerializer> ValueSharedSerializer
+    for CompositeSerializer<S, C, H>
+{
+    #[inline]
+    fn get_shared_value<P>(&self, value: &P) -> Option<usize>
+    where
+        P: Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.shared.get_shared_value(value)
+    }
+
+    #[inline]
+    fn add_shared_value<P>(&mut self, value: &P, pos: usize) -> Result<(), Self::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.shared
+            .add_shared_value(value, pos)
+            .map_err(CompositeSerializerError::SharedError)
+    }
 }
 
 /// A serializer suitable for environments where allocations cannot be made.
@@ -232,3 +286,208 @@ pub type AllocSerializer<const N: usize> = CompositeSerializer<
     FallbackScratch<HeapScratch<N>, AllocScratch>,
     SharedSerializeMap,
 >;
+
+/// A serializer that counts the size of an archive without writing it.
+///
+/// `AllocSizeSerializer` takes the same argument as [`AllocSerializer`] and uses the same scratch
+/// space and shared pointer registry, so shared pointers are only counted once.
+#[cfg(feature = "alloc")]
+pub type AllocSizeSerializer<const N: usize> = CompositeSerializer<
+    SizeSerializer,
+    FallbackScratch<HeapScratch<N>, AllocScratch>,
+    SharedSerializeMap,
+>;
+
+/// The error type returned by a [`LimitSerializer`].
+#[derive(Debug)]
+pub enum LimitSerializerError<E> {
+    /// Writing would have exceeded the size limit.
+    SizeLimitExceeded {
+        /// The position of the serializer
+        pos: usize,
+        /// The number of bytes needed
+        bytes_needed: usize,
+        /// The size limit of the serializer
+        limit: usize,
+    },
+    /// An error occurred in the inner serializer
+    Inner(E),
+}
+
+impl<E: fmt::Display> fmt::Display for LimitSerializerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeLimitExceeded {
+                pos,
+                bytes_needed,
+                limit,
+            } => write!(
+                f,
+                "writing has exceeded the serializer size limit: pos {}, needed {}, limit {}",
+                pos, bytes_needed, limit
+            ),
+            Self::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E> From<E> for LimitSerializerError<E> {
+    #[inline]
+    fn from(e: E) -> Self {
+        Self::Inner(e)
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use ::std::error::Error;
+
+    impl<E: Error + 'static> Error for LimitSerializerError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::SizeLimitExceeded { .. } => None,
+                Self::Inner(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// Wraps a serializer and limits the size of the archive it can write.
+///
+/// Writing past the limit returns a [`SizeLimitExceeded`](LimitSerializerError::SizeLimitExceeded)
+/// error instead of writing to the inner serializer. Scratch space and shared memory are forwarded
+/// to the inner serializer and do not count toward the limit.
+#[derive(Debug)]
+pub struct LimitSerializer<S> {
+    inner: S,
+    limit: usize,
+}
+
+impl<S> LimitSerializer<S> {
+    /// Creates a new serializer that limits the archive written by `inner` to `limit` bytes.
+    #[inline]
+    pub fn new(inner: S, limit: usize) -> Self {
+        Self { inner, limit }
+    }
+
+    /// Returns the size limit of the serializer.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Consumes the serializer and returns the inner serializer.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Serializer> LimitSerializer<S> {
+    #[inline]
+    fn check(&self, bytes_needed: usize) -> Result<(), LimitSerializerError<S::Error>> {
+        let pos = self.inner.pos();
+        if self.limit.saturating_sub(pos) < bytes_needed {
+            Err(LimitSerializerError::SizeLimitExceeded {
+                pos,
+                bytes_needed,
+                limit: self.limit,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<S: Fallible> Fallible for LimitSerializer<S> {
+    type Error = LimitSerializerError<S::Error>;
+}
+
+impl<S: Serializer> Serializer for LimitSerializer<S> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.check(bytes.len())?;
+        Ok(self.inner.write(bytes)?)
+    }
+
+    #[inline]
+    fn pad(&mut self, padding: usize) -> Result<(), Self::Error> {
+        self.check(padding)?;
+        Ok(self.inner.pad(padding)?)
+    }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.check(align_padding(self.pos(), align))?;
+        Ok(self.inner.align(align)?)
+    }
+
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.inner.current_alignment()
+    }
+
+    #[inline]
+    fn offset_recorder(&mut self) -> Option<&mut dyn OffsetRecorder> {
+        self.inner.offset_recorder()
+    }
+}
+
+impl<S: ScratchSpace> ScratchSpace for LimitSerializer<S> {
+    #[inline]
+    unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
+        Ok(self.inner.push_scratch(layout)?)
+    }
+
+    #[inline]
+    unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), Self::Error> {
+        Ok(self.inner.pop_scratch(ptr, layout)?)
+    }
+}
+
+impl<S: SharedSerializeRegistry> SharedSerializeRegistry for LimitSerializer<S> {
+    #[inline]
+    fn get_shared_ptr(&self, value: *const u8) -> Option<usize> {
+        self.inner.get_shared_ptr(value)
+    }
+
+    #[inline]
+    fn add_shared_ptr(&mut self, value: *const u8, pos: usize) -> Result<(), Self::Error> {
+        Ok(self.inner.add_shared_ptr(value, pos)?)
+    }
+
+    #[inline]
+    fn begin_shared_ptr(&mut self, value: *const u8) -> Result<(), Self::Error> {
+        Ok(self.inner.begin_shared_ptr(value)?)
+    }
+
+    #[inline]
+    fn cancel_shared_ptr(&mut self, value: *const u8) {
+        self.inner.cancel_shared_ptr(value)
+    }
+}
+
+impl<S: ValueSharedSerializer> ValueSharedSerializer for LimitSerializer<S> {
+    #[inline]
+    fn get_shared_value<P>(&self, value: &P) -> Option<usize>
+    where
+        P: Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.inner.get_shared_value(value)
+    }
+
+    #[inline]
+    fn add_shared_value<P>(&mut self, value: &P, pos: usize) -> Result<(), Self::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        Ok(self.inner.add_shared_value(value, pos)?)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    relocate::{record_relocations, Relocation, RelocationTable},
+    ser::{
+        OffsetRecorder, ScratchSpace, Serializer, SharedSerializeRegistry, ValueSharedSerializer,
+    },
+    Archive, ArchiveUnsized, Fallible, RelPtr, Serialize, SerializeUnsized,
+};
+use core::{alloc::Layout, hash::Hash, mem, ops::Deref, ptr::NonNull};
+
+/// Wraps a serializer and records a [`RelocationTable`] of the relative pointers it writes.
+///
+/// Relative pointers are recorded while values are serialized with
+/// [`serialize_value`](Serializer::serialize_value) and
+/// [`serialize_unsized_value`](Serializer::serialize_unsized_value), and while they are resolved
+/// with [`resolve_aligned`](Serializer::resolve_aligned) and
+/// [`resolve_unsized_aligned`](Serializer::resolve_unsized_aligned). The positions in the table
+/// are positions of the inner serializer. See [`relocate`](crate::relocate) for how to use the
+/// table.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     relocate::relocate_in_place,
+///     ser::{serializers::{AllocSerializer, RelocationSerializer}, Serializer},
+/// };
+///
+/// let mut serializer = RelocationSerializer::new(AllocSerializer::<256>::default());
+/// serializer.serialize_value(&vec![1u32, 2, 3]).unwrap();
+/// let (serializer, table) = serializer.into_parts();
+/// let mut bytes = serializer.into_serializer().into_inner();
+/// assert_eq!(table.len(), 1);
+///
+/// relocate_in_place(&mut bytes, &table, 0x1000).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RelocationSerializer<S> {
+    inner: S,
+    relocations: Vec<Relocation>,
+}
+
+impl<S> RelocationSerializer<S> {
+    /// Creates a new serializer that records the relative pointers written by `inner`.
+    #[inline]
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            relocations: Vec::new(),
+        }
+    }
+
+    /// Consumes the serializer and returns the inner serializer and the recorded relocations.
+    #[inline]
+    pub fn into_parts(self) -> (S, RelocationTable) {
+        (self.inner, RelocationTable::new(self.relocations))
+    }
+
+    /// Runs `f` while recording the relative pointers it emplaces.
+    #[inline]
+    fn record<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        // Take the relocations so that `f` can borrow the serializer mutably. Nested calls record
+        // into the outermost list and leave theirs empty.
+        let mut relocations = mem::take(&mut self.relocations);
+        let result = record_relocations(&mut relocations, || f(self));
+        self.relocations.append(&mut relocations);
+        result
+    }
+}
+
+impl<S: Fallible> Fallible for RelocationSerializer<S> {
+    type Error = S::Error;
+}
+
+impl<S: Serializer> Serializer for RelocationSerializer<S> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.inner.write(bytes)
+    }
+
+    #[inline]
+    fn pad(&mut self, padding: usize) -> Result<(), Self::Error> {
+        self.inner.pad(padding)
+    }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.inner.align(align)
+    }
+
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.inner.current_alignment()
+    }
+
+    #[inline]
+    fn offset_recorder(&mut self) -> Option<&mut dyn OffsetRecorder> {
+        self.inner.offset_recorder()
+    }
+
+    #[inline]
+    unsafe fn resolve_aligned<T: Archive + ?Sized>(
+        &mut self,
+        value: &T,
+        resolver: T::Resolver,
+    ) -> Result<usize, Self::Error> {
+        self.record(|s| s.inner.resolve_aligned(value, resolver))
+    }
+
+    #[inline]
+    fn serialize_value<T: Serialize<Self>>(&mut self, value: &T) -> Result<usize, Self::Error> {
+        self.record(|s| {
+            let resolver = value.serialize(s)?;
+            s.align_for::<T::Archived>()?;
+            unsafe { s.resolve_aligned(value, resolver) }
+        })
+    }
+
+    #[inline]
+    unsafe fn resolve_unsized_aligned<T: ArchiveUnsized + ?Sized>(
+        &mut self,
+        value: &T,
+        to: usize,
+        metadata_resolver: T::MetadataResolver,
+    ) -> Result<usize, Self::Error> {
+        self.record(|s| {
+            s.inner
+                .resolve_unsized_aligned(value, to, metadata_resolver)
+        })
+    }
+
+    #[inline]
+    fn serialize_unsized_value<T: SerializeUnsized<Self> + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<usize, Self::Error> {
+        self.record(|s| {
+            let to = value.serialize_unsized(s)?;
+            let metadata_resolver = value.serialize_metadata(s)?;
+            s.align_for::<RelPtr<T::Archived>>()?;
+            unsafe { s.resolve_unsized_aligned(value, to, metadata_resolver) }
+        })
+    }
+}
+
+impl<S: ScratchSpace> ScratchSpace for RelocationSerializer<S> {
+    #[inline]
+    unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
+        self.inner.push_scratch(layout)
+    }
+
+    #[inline]
+    unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), Self::Error> {
+        self.inner.pop_scratch(ptr, layout)
+    }
+}
+
+impl<S: SharedSerializeRegistry> SharedSerializeRegistry for RelocationSerializer<S> {
+    #[inline]
+    fn get_shared_ptr(&self, value: *const u8) -> Option<usize> {
+        self.inner.get_shared_ptr(value)
+    }
+
+    #[inline]
+    fn add_shared_ptr(&mut self, value: *const u8, pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_shared_ptr(value, pos)
+    }
+
+    #[inline]
+    fn begin_shared_ptr(&mut self, value: *const u8) -> Result<(), Self::Error> {
+        self.inner.begin_shared_ptr(value)
+    }
+
+    #[inline]
+    fn cancel_shared_ptr(&mut self, value: *const u8) {
+        self.inner.cancel_shared_ptr(value)
+    }
+}
+
+impl<S: ValueSharedSerializer> ValueSharedSerializer for RelocationSerializer<S> {
+    #[inline]
+    fn get_shared_value<P>(&self, value: &P) -> Option<usize>
+    where
+        P: Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.inner.get_shared_value(value)
+    }
+
+    #[inline]
+    fn add_shared_value<P>(&mut self, value: &P, pos: usize) -> Result<(), Self::Error>
+    where
+        P: Clone + Deref + 'static,
+        P::Target: Hash + Eq,
+    {
+        self.inner.add_shared_value(value, pos)
+    }
+}
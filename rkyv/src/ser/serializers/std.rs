@@ -1,4 +1,7 @@
-use crate::{ser::Serializer, Fallible};
+use crate::{
+    ser::{pad_to_align, Serializer},
+    Fallible,
+};
 use std::io;
 
 /// Wraps a type that implements [`io::Write`](std::io::Write) and equips it with [`Serializer`].
@@ -19,6 +22,7 @@ use std::io;
 pub struct WriteSerializer<W: io::Write> {
     inner: W,
     pos: usize,
+    max_align: usize,
 }
 
 impl<W: io::Write> WriteSerializer<W> {
@@ -32,7 +36,11 @@ impl<W: io::Write> WriteSerializer<W> {
     /// at the given position.
     #[inline]
     pub fn with_pos(inner: W, pos: usize) -> Self {
-        Self { inner, pos }
+        Self {
+            inner,
+            pos,
+            max_align: 1,
+        }
     }
 
     /// Consumes the serializer and returns the internal writer used to create it.
@@ -58,4 +66,15 @@ impl<W: io::Write> Serializer for WriteSerializer<W> {
         self.pos += bytes.len();
         Ok(())
     }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.max_align = self.max_align.max(align);
+        pad_to_align(self, align)
+    }
+
+    #[inline]
+    fn current_alignment(&self) -> usize {
+        self.max_align
+    }
 }
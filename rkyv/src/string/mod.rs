@@ -16,7 +16,9 @@ use repr::{ArchivedStringRepr, INLINE_CAPACITY};
 ///
 /// This has inline and out-of-line representations. Short strings will use the available space
 /// inside the structure to store the string, and long strings will store a
-/// [`RelPtr`](crate::RelPtr) to a `str` instead.
+/// [`RelPtr`](crate::RelPtr) to a `str` instead. Strings of up to
+/// [`INLINE_CAPACITY`](repr::INLINE_CAPACITY) bytes are stored inline, which is 3 bytes with
+/// `size_16`, 7 bytes with `size_32`, and 15 bytes with `size_64`.
 #[repr(transparent)]
 pub struct ArchivedString(repr::ArchivedStringRepr);
 
@@ -33,6 +35,26 @@ impl ArchivedString {
         unsafe { self.map_unchecked_mut(|s| s.0.as_mut_str()) }
     }
 
+    /// Extracts a mutable string slice containing the entire `ArchivedString`.
+    ///
+    /// The string can be edited in place, but its length can't be changed through a `&mut str`.
+    /// Edits that change the encoded length of a character (like most non-ASCII case conversions)
+    /// are not possible and must be done by serializing a new string instead.
+    #[inline]
+    pub fn as_mut_str(self: Pin<&mut Self>) -> &mut str {
+        self.pin_mut_str().get_mut()
+    }
+
+    /// Extracts a mutable byte slice containing the entire `ArchivedString`.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must be valid UTF-8 when the returned slice is dropped.
+    #[inline]
+    pub unsafe fn as_mut_bytes(self: Pin<&mut Self>) -> &mut [u8] {
+        self.as_mut_str().as_bytes_mut()
+    }
+
     /// Resolves an archived string from a given `str`.
     ///
     /// # Safety
@@ -180,6 +202,41 @@ impl PartialEq<ArchivedString> for &str {
     }
 }
 
+impl PartialEq<ArchivedString> for str {
+    #[inline]
+    fn eq(&self, other: &ArchivedString) -> bool {
+        PartialEq::eq(other.as_str(), self)
+    }
+}
+
+impl PartialOrd<&str> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &&str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(*other)
+    }
+}
+
+impl PartialOrd<str> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+impl PartialOrd<ArchivedString> for &str {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        (*self).partial_cmp(other.as_str())
+    }
+}
+
+impl PartialOrd<ArchivedString> for str {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        self.partial_cmp(other.as_str())
+    }
+}
+
 /// The resolver for `String`.
 pub struct StringResolver {
     pos: usize,
@@ -141,7 +141,8 @@ const _: () = {
             // The fields of `ArchivedDuration` are always valid
             let duration = &*value;
 
-            if duration.secs.checked_add((duration.nanos / 1_000_000_000) as u64).is_none() {
+            let secs = from_archived!(duration.secs);
+            if secs.checked_add((from_archived!(duration.nanos) / NANOS_PER_SEC) as u64).is_none() {
                 Err(DurationError)
             } else {
                 Ok(duration)
@@ -0,0 +1,91 @@
+//! Archived versions of tuples with more than 12 elements.
+//!
+//! Tuples with up to 12 elements are archived as tuples of their archived elements. Longer tuples
+//! are archived as these tuple structs instead, since `bytecheck` only implements `CheckBytes` for
+//! tuples with up to 12 elements.
+
+/// An archived tuple with 13 elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedTuple13<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12>(
+    pub T0,
+    pub T1,
+    pub T2,
+    pub T3,
+    pub T4,
+    pub T5,
+    pub T6,
+    pub T7,
+    pub T8,
+    pub T9,
+    pub T10,
+    pub T11,
+    pub T12,
+);
+
+/// An archived tuple with 14 elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedTuple14<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13>(
+    pub T0,
+    pub T1,
+    pub T2,
+    pub T3,
+    pub T4,
+    pub T5,
+    pub T6,
+    pub T7,
+    pub T8,
+    pub T9,
+    pub T10,
+    pub T11,
+    pub T12,
+    pub T13,
+);
+
+/// An archived tuple with 15 elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedTuple15<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14>(
+    pub T0,
+    pub T1,
+    pub T2,
+    pub T3,
+    pub T4,
+    pub T5,
+    pub T6,
+    pub T7,
+    pub T8,
+    pub T9,
+    pub T10,
+    pub T11,
+    pub T12,
+    pub T13,
+    pub T14,
+);
+
+/// An archived tuple with 16 elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedTuple16<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15>(
+    pub T0,
+    pub T1,
+    pub T2,
+    pub T3,
+    pub T4,
+    pub T5,
+    pub T6,
+    pub T7,
+    pub T8,
+    pub T9,
+    pub T10,
+    pub T11,
+    pub T12,
+    pub T13,
+    pub T14,
+    pub T15,
+);
@@ -0,0 +1,239 @@
+//! Archive headers for accessing archives from memory that rkyv did not allocate.
+//!
+//! Archives written with [`to_bytes_with_header`] begin with an [`ArchiveHeader`] that records
+//! the total length of the archive and the alignment it must be placed at. This makes it possible
+//! to check memory-mapped files and other externally-provided buffers with [`check_alignment`]
+//! before accessing the archive inside.
+
+use crate::Archived;
+#[cfg(feature = "alloc")]
+use crate::{
+    ser::{serializers::AllocSerializer, Serializer},
+    AlignedVec, Fallible, Serialize,
+};
+use core::{convert::Infallible, fmt, mem, ptr};
+
+/// A fixed-size header that precedes an archive and describes how it must be placed in memory.
+///
+/// The header is part of the archive, so the positions of archived values include it and the
+/// root is still located at the end of the bytes. The archive can be accessed with the usual
+/// functions like [`archived_root`](crate::archived_root) once it has been checked.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct ArchiveHeader {
+    align: Archived<u64>,
+    len: Archived<u64>,
+}
+
+impl ArchiveHeader {
+    /// The size of the header in bytes.
+    pub const SIZE: usize = mem::size_of::<Self>();
+
+    /// Creates a new header for an archive with the given alignment and total length.
+    #[inline]
+    pub fn new(align: usize, len: usize) -> Self {
+        Self {
+            align: to_archived!(align as u64),
+            len: to_archived!(len as u64),
+        }
+    }
+
+    /// Reads a header from the beginning of the given bytes.
+    ///
+    /// Returns `None` if there are not enough bytes to contain a header.
+    #[inline]
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::SIZE {
+            None
+        } else {
+            // SAFETY: There are enough bytes for a header and any bit pattern is a valid header.
+            Some(unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<Self>()) })
+        }
+    }
+
+    /// Returns the alignment that the archive must be placed at.
+    #[inline]
+    pub fn align(&self) -> usize {
+        from_archived!(self.align) as usize
+    }
+
+    /// Returns the total length of the archive in bytes, including the header.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns whether the archive is empty. Archives always contain at least their header.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes of the header as they are written at the beginning of an archive.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: The header is plain old data with no padding.
+        unsafe { core::slice::from_raw_parts((self as *const Self).cast::<u8>(), Self::SIZE) }
+    }
+}
+
+impl fmt::Debug for ArchiveHeader {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveHeader")
+            .field("align", &self.align())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// An error resulting from checking an archive before accessing it.
+///
+/// This is returned by [`check_alignment`] and, with the `validation` feature, by
+/// [`access`](crate::access), which reports validation errors as `Invalid`.
+#[derive(Debug)]
+pub enum CheckError<E = Infallible> {
+    /// There were not enough bytes to contain a header
+    MissingHeader {
+        /// The number of bytes available
+        len: usize,
+    },
+    /// The header recorded an alignment that is not a power of two
+    InvalidAlignment {
+        /// The recorded alignment
+        align: usize,
+    },
+    /// The length recorded in the header did not match the number of bytes
+    LengthMismatch {
+        /// The length recorded in the header
+        expected: usize,
+        /// The number of bytes available
+        actual: usize,
+    },
+    /// The bytes were not placed at the alignment recorded in the header
+    Unaligned {
+        /// The address of the bytes
+        address: usize,
+        /// The alignment recorded in the header
+        align: usize,
+    },
+    /// The buffer is too short to contain the archived root
+    BufferTooShort {
+        /// The length of the buffer
+        len: usize,
+        /// The size of the archived root
+        required: usize,
+    },
+    /// The archived root failed validation
+    Invalid(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CheckError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::MissingHeader { len } => {
+                write!(f, "missing archive header: only {} bytes available", len)
+            }
+            CheckError::InvalidAlignment { align } => {
+                write!(f, "invalid alignment in archive header: {}", align)
+            }
+            CheckError::LengthMismatch { expected, actual } => write!(
+                f,
+                "archive length mismatch: header expected {} bytes but found {} bytes",
+                expected, actual
+            ),
+            CheckError::Unaligned { address, align } => write!(
+                f,
+                "unaligned archive: address {:#x} is not aligned to {} bytes",
+                address, align
+            ),
+            CheckError::BufferTooShort { len, required } => write!(
+                f,
+                "buffer too short: length is {} but the archived root requires {} bytes",
+                len, required
+            ),
+            CheckError::Invalid(e) => write!(f, "invalid archive: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for CheckError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                CheckError::Invalid(e) => Some(e as &dyn Error),
+                _ => None,
+            }
+        }
+    }
+};
+
+/// Checks that the given bytes match their archive header and are placed at the recorded
+/// alignment.
+///
+/// Returns the header if the bytes are suitable for accessing the archive.
+///
+/// # Examples
+/// ```
+/// use rkyv::{archived_root, header::check_alignment};
+///
+/// let value = vec![1, 2, 3, 4];
+///
+/// let bytes = rkyv::header::to_bytes_with_header::<_, 256>(&value)
+///     .expect("failed to serialize vec");
+/// let header = check_alignment(&bytes).expect("archive is not accessible");
+/// assert_eq!(header.len(), bytes.len());
+///
+/// // SAFETY: The bytes were just serialized from a `Vec<i32>`.
+/// let archived = unsafe { archived_root::<Vec<i32>>(&bytes) };
+/// assert_eq!(archived.as_slice(), &[1, 2, 3, 4]);
+/// ```
+pub fn check_alignment(bytes: &[u8]) -> Result<ArchiveHeader, CheckError> {
+    let header =
+        ArchiveHeader::read(bytes).ok_or(CheckError::MissingHeader { len: bytes.len() })?;
+
+    let align = header.align();
+    if !align.is_power_of_two() {
+        return Err(CheckError::InvalidAlignment { align });
+    }
+    if header.len() != bytes.len() {
+        return Err(CheckError::LengthMismatch {
+            expected: header.len(),
+            actual: bytes.len(),
+        });
+    }
+    let address = bytes.as_ptr() as usize;
+    if address & (align - 1) != 0 {
+        return Err(CheckError::Unaligned { address, align });
+    }
+
+    Ok(header)
+}
+
+/// Serializes the given value and returns the resulting bytes prefixed with an
+/// [`ArchiveHeader`].
+///
+/// The header records the largest alignment that the serializer was aligned to while writing the
+/// archive, and the total length of the returned bytes.
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn to_bytes_with_header<T, const N: usize>(
+    value: &T,
+) -> Result<AlignedVec, <AllocSerializer<N> as Fallible>::Error>
+where
+    T: Serialize<AllocSerializer<N>>,
+{
+    let mut serializer = AllocSerializer::<N>::default();
+    serializer.align_for::<ArchiveHeader>()?;
+    serializer.write(ArchiveHeader::new(1, 0).as_bytes())?;
+    serializer.serialize_value(value)?;
+    let align = serializer.current_alignment();
+
+    let mut bytes = serializer.into_serializer().into_inner();
+    let header = ArchiveHeader::new(align, bytes.len());
+    bytes[..ArchiveHeader::SIZE].copy_from_slice(header.as_bytes());
+    Ok(bytes)
+}
@@ -4,6 +4,10 @@
 //!
 //! Helper functions to get the root object of an archive under certain conditions.
 //!
+//! ## Versioning
+//!
+//! The [`version`] module writes archives that record the schema version of their root type.
+//!
 //! ## Alignment
 //!
 //! Alignment helpers ensure that byte buffers are properly aligned when accessing and deserializing
@@ -11,12 +15,17 @@
 
 #[cfg(feature = "alloc")]
 mod aligned_vec;
+pub mod header;
 mod scratch_vec;
+pub mod version;
 
 #[cfg(feature = "alloc")]
 use crate::{
     de::deserializers::SharedDeserializeMap,
-    ser::{serializers::AllocSerializer, Serializer},
+    ser::{
+        serializers::{AllocSerializer, AllocSizeSerializer},
+        Serializer,
+    },
     Fallible,
 };
 use crate::{Archive, ArchiveUnsized, RelPtr, Deserialize, Serialize};
@@ -297,6 +306,34 @@ where
     Ok(serializer.into_serializer().into_inner())
 }
 
+/// Returns the number of bytes that serializing the given value with [`to_bytes`] would produce.
+///
+/// Nothing is written while computing the size, but scratch space is still used and shared pointers
+/// are only counted once. The const generic parameter `N` specifies the number of bytes to
+/// pre-allocate as scratch space.
+///
+/// # Examples
+/// ```
+/// let value = vec![1, 2, 3, 4];
+///
+/// let size = rkyv::serialized_size::<_, 1024>(&value).expect("failed to size vec");
+/// let bytes = rkyv::to_bytes::<_, 1024>(&value).expect("failed to serialize vec");
+///
+/// assert_eq!(size, bytes.len());
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn serialized_size<T, const N: usize>(
+    value: &T,
+) -> Result<usize, <AllocSizeSerializer<N> as Fallible>::Error>
+where
+    T: Serialize<AllocSizeSerializer<N>>,
+{
+    let mut serializer = AllocSizeSerializer::<N>::default();
+    serializer.serialize_value(value)?;
+    Ok(serializer.pos())
+}
+
 /// Deserializes a value from the given bytes.
 ///
 /// This function is only available with the `alloc` feature because it uses a general-purpose
@@ -0,0 +1,170 @@
+//! Versioned archives whose schema version can be read before accessing the root.
+//!
+//! Types that derive `Archive` with `#[archive(version = ...)]` implement [`ArchiveVersion`]. When
+//! they are serialized with [`serialize_versioned`], the archive ends with a [`VersionedRoot`]
+//! that records the version and points to the archived value. Because the versioned root has the
+//! same size regardless of the archived type, [`archive_version`] can read the version of an
+//! archive without knowing what type it contains. This makes it possible to dispatch to the right
+//! type for an archive written by an older version of a program.
+
+#[cfg(feature = "alloc")]
+use crate::{ser::serializers::AllocSerializer, AlignedVec, Fallible};
+use crate::{ser::Serializer, Archive, Archived, RelPtr, Serialize};
+use core::{mem, mem::MaybeUninit, ptr, slice};
+
+/// A type with a schema version that is recorded in its archives.
+///
+/// This is implemented by the [`Archive`](macro@crate::Archive) derive macro when the
+/// `#[archive(version = ...)]` attribute is used.
+pub trait ArchiveVersion: Archive {
+    /// The schema version of the type.
+    const VERSION: u32;
+}
+
+/// The root of a versioned archive.
+///
+/// The versioned root records the schema version of the archived type and a relative pointer to
+/// the archived value.
+#[repr(C)]
+pub struct VersionedRoot<T> {
+    version: Archived<u32>,
+    value: RelPtr<T>,
+}
+
+impl<T> VersionedRoot<T> {
+    /// The size of a versioned root in bytes.
+    pub const SIZE: usize = mem::size_of::<Self>();
+
+    /// Returns the schema version recorded in the root.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        from_archived!(self.version)
+    }
+
+    /// Returns the archived value that the root points to.
+    #[inline]
+    pub fn get(&self) -> &T {
+        unsafe { &*self.value.as_ptr() }
+    }
+}
+
+/// Returns the schema version of a versioned archive.
+///
+/// This only reads the version from the root of the archive, so it can be called before the
+/// archive has been validated and without knowing which type was archived. Returns `None` if the
+/// bytes are too short to contain a versioned root.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     from_archived,
+///     ser::{serializers::AllocSerializer, Serializer},
+///     version::{archive_version, archived_versioned_root, serialize_versioned},
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(version = 1)]
+/// struct ConfigV1 {
+///     name: String,
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(version = 2)]
+/// struct ConfigV2 {
+///     name: String,
+///     retries: u32,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serialize_versioned(&mut serializer, &ConfigV1 { name: "example".to_string() }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// let retries = match archive_version(&bytes) {
+///     // SAFETY: The bytes contain a versioned archive of the checked type.
+///     Some(1) => unsafe { archived_versioned_root::<ConfigV1>(&bytes) }.map(|_| 3),
+///     Some(2) => {
+///         unsafe { archived_versioned_root::<ConfigV2>(&bytes) }.map(|c| from_archived!(c.retries))
+///     }
+///     _ => None,
+/// };
+/// assert_eq!(retries, Some(3));
+/// ```
+#[inline]
+pub fn archive_version(bytes: &[u8]) -> Option<u32> {
+    let size = VersionedRoot::<()>::SIZE;
+    if bytes.len() < size {
+        None
+    } else {
+        // SAFETY: There are enough bytes for a versioned root, which begins with the version, and
+        // any bit pattern is a valid version.
+        let version = unsafe {
+            ptr::read_unaligned(bytes[bytes.len() - size..].as_ptr().cast::<Archived<u32>>())
+        };
+        Some(from_archived!(version))
+    }
+}
+
+/// Casts the archived value of a versioned archive from the given byte slice.
+///
+/// Returns `None` if the archive was not written with the version of `T`.
+///
+/// # Safety
+///
+/// - The byte slice must represent a versioned archive written by [`serialize_versioned`]
+/// - If the version of the archive matches the version of `T`, then the archived value must be a
+///   `T::Archived`
+#[inline]
+pub unsafe fn archived_versioned_root<T: ArchiveVersion>(bytes: &[u8]) -> Option<&T::Archived> {
+    if archive_version(bytes) == Some(T::VERSION) {
+        let pos = bytes.len() - VersionedRoot::<T::Archived>::SIZE;
+        let root = &*bytes.as_ptr().add(pos).cast::<VersionedRoot<T::Archived>>();
+        Some(root.get())
+    } else {
+        None
+    }
+}
+
+/// Serializes a value into a versioned archive and returns the position of the versioned root.
+///
+/// The archived value is written first, followed by a [`VersionedRoot`] recording the version of
+/// `T`. The archive can be accessed with [`archived_versioned_root`].
+#[inline]
+pub fn serialize_versioned<S, T>(serializer: &mut S, value: &T) -> Result<usize, S::Error>
+where
+    S: Serializer + ?Sized,
+    T: ArchiveVersion + Serialize<S>,
+{
+    let value_pos = serializer.serialize_value(value)?;
+    let pos = serializer.align_for::<VersionedRoot<T::Archived>>()?;
+
+    let mut root = MaybeUninit::<VersionedRoot<T::Archived>>::zeroed();
+    let out = root.as_mut_ptr();
+    unsafe {
+        let (_, fo) = out_field!(out.version);
+        fo.write(to_archived!(T::VERSION));
+        let (fp, fo) = out_field!(out.value);
+        RelPtr::emplace(pos + fp, value_pos, fo);
+        serializer.write(slice::from_raw_parts(
+            out.cast::<u8>(),
+            VersionedRoot::<T::Archived>::SIZE,
+        ))?;
+    }
+    Ok(pos)
+}
+
+/// Serializes the given value into a versioned archive and returns the resulting bytes.
+///
+/// See [`serialize_versioned`] for more information.
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn to_bytes_versioned<T, const N: usize>(
+    value: &T,
+) -> Result<AlignedVec, <AllocSerializer<N> as Fallible>::Error>
+where
+    T: ArchiveVersion + Serialize<AllocSerializer<N>>,
+{
+    let mut serializer = AllocSerializer::<N>::default();
+    serialize_versioned(&mut serializer, value)?;
+    Ok(serializer.into_serializer().into_inner())
+}
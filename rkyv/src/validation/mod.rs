@@ -1,4 +1,13 @@
 //! Validation implementations and helper types.
+//!
+//! # Large archives
+//!
+//! Large archives can be checked from a reader with
+//! [`check_bytes_streaming`](validators::check_bytes_streaming), which only reads the parts of the
+//! archive that validation reaches. It keeps every block it reads in memory, so archives that are
+//! larger than memory should be memory-mapped and checked in place instead. Only the pages that
+//! validation touches will be read from disk, and the operating system evicts them as needed to
+//! bound memory usage.
 
 pub mod owned;
 pub mod validators;
@@ -312,23 +321,40 @@ where
     T::Archived: CheckBytes<C> + Pointee<Metadata = ()>,
     C: ArchiveContext + ?Sized,
 {
-    unsafe {
-        let ptr = context
-            .check_subtree_ptr(buf.as_ptr(), pos, ())
-            .map_err(CheckArchiveError::ContextError)?;
-
-        let range = context
-            .push_prefix_subtree(ptr)
-            .map_err(CheckArchiveError::ContextError)?;
-        let result =
-            CheckBytes::check_bytes(ptr, context).map_err(CheckArchiveError::CheckBytesError)?;
-        context
-            .pop_prefix_range(range)
-            .map_err(CheckArchiveError::ContextError)?;
-
-        context.finish().map_err(CheckArchiveError::ContextError)?;
-        Ok(result)
-    }
+    unsafe { check_archived_value_at::<T, C>(buf.as_ptr(), pos, context) }
+}
+
+/// Checks the archived value at `pos` relative to `base`.
+///
+/// # Safety
+///
+/// `base` must be the start of the archive that `context` was created for.
+#[inline]
+unsafe fn check_archived_value_at<'a, T, C>(
+    base: *const u8,
+    pos: isize,
+    context: &mut C,
+) -> Result<&'a T::Archived, CheckTypeError<T::Archived, C>>
+where
+    T: Archive,
+    T::Archived: CheckBytes<C> + Pointee<Metadata = ()>,
+    C: ArchiveContext + ?Sized,
+{
+    let ptr = context
+        .check_subtree_ptr(base, pos, ())
+        .map_err(CheckArchiveError::ContextError)?;
+
+    let range = context
+        .push_prefix_subtree(ptr)
+        .map_err(CheckArchiveError::ContextError)?;
+    let result =
+        CheckBytes::check_bytes(ptr, context).map_err(CheckArchiveError::CheckBytesError)?;
+    context
+        .pop_prefix_range(range)
+        .map_err(CheckArchiveError::ContextError)?;
+
+    context.finish().map_err(CheckArchiveError::ContextError)?;
+    Ok(result)
 }
 
 /// Checks the given archive with an additional context.
@@ -1,7 +1,7 @@
 //! The provided implementation for `ArchiveContext`.
 
 use crate::{validation::ArchiveContext, Fallible};
-use core::{alloc::Layout, fmt, ops::Range};
+use core::{alloc::Layout, fmt, marker::PhantomData, ops::Range};
 
 /// Errors that can occur when checking archive memory.
 #[derive(Debug)]
@@ -195,7 +195,8 @@ unsafe impl Sync for SuffixRange {}
 /// A validator that can verify archives with nonlocal memory.
 #[derive(Debug)]
 pub struct ArchiveValidator<'a> {
-    bytes: &'a [u8],
+    bytes: Range<*const u8>,
+    _phantom: PhantomData<&'a [u8]>,
     subtree_range: Range<*const u8>,
     subtree_depth: usize,
     max_subtree_depth: usize,
@@ -216,12 +217,23 @@ impl<'a> ArchiveValidator<'a> {
         Self::with_max_depth(bytes, usize::MAX)
     }
 
-    /// Crates a new bounds validator for the given bytes with a maximum validation depth.
+    /// Creates a new bounds validator for the given bytes with a maximum validation depth.
     #[inline]
     pub fn with_max_depth(bytes: &'a [u8], max_subtree_depth: usize) -> Self {
+        unsafe { Self::from_ptr_range(bytes.as_ptr_range(), max_subtree_depth) }
+    }
+
+    /// Creates a new bounds validator for the bytes in the given pointer range.
+    ///
+    /// # Safety
+    ///
+    /// The range must be valid for reads for `'a` wherever validation dereferences it.
+    #[inline]
+    pub(crate) unsafe fn from_ptr_range(bytes: Range<*const u8>, max_subtree_depth: usize) -> Self {
         Self {
+            subtree_range: bytes.clone(),
             bytes,
-            subtree_range: bytes.as_ptr_range(),
+            _phantom: PhantomData,
             subtree_depth: 0,
             max_subtree_depth,
         }
@@ -233,7 +245,7 @@ impl<'a> ArchiveValidator<'a> {
     /// and so on.
     #[inline]
     pub fn log_alignment(&self) -> usize {
-        (self.bytes.as_ptr() as usize).trailing_zeros() as usize
+        (self.bytes.start as usize).trailing_zeros() as usize
     }
 
     /// Returns the alignment of the archive.
@@ -241,6 +253,19 @@ impl<'a> ArchiveValidator<'a> {
     pub fn alignment(&self) -> usize {
         1 << self.log_alignment()
     }
+
+    /// Returns the position of the innermost object that is being validated, if any.
+    ///
+    /// Validation descends into the root object and into each object reached through a relative
+    /// pointer. If validation fails, this is the position of the object that the error occurred in.
+    #[inline]
+    pub fn subtree_root_pos(&self) -> Option<usize> {
+        if self.subtree_depth == 0 {
+            None
+        } else {
+            Some(self.subtree_range.end as usize - self.bytes.start as usize)
+        }
+    }
 }
 
 impl<'a> Fallible for ArchiveValidator<'a> {
@@ -257,15 +282,15 @@ impl<'a> ArchiveContext for ArchiveValidator<'a> {
         base: *const u8,
         offset: isize,
     ) -> Result<*const u8, Self::Error> {
-        let base_pos = base.offset_from(self.bytes.as_ptr());
+        let base_pos = base.offset_from(self.bytes.start);
         let target_pos = base_pos
             .checked_add(offset)
             .ok_or(ArchiveError::Overflow { base, offset })?;
-        if target_pos < 0 || target_pos as usize > self.bytes.len() {
+        if target_pos < 0 || target_pos > self.bytes.end.offset_from(self.bytes.start) {
             Err(ArchiveError::OutOfBounds {
                 base,
                 offset,
-                range: self.bytes.as_ptr_range(),
+                range: self.bytes.clone(),
             })
         } else {
             Ok(base.offset(offset))
@@ -289,12 +314,12 @@ impl<'a> ArchiveContext for ArchiveValidator<'a> {
                 align: layout.align(),
             })
         } else {
-            let available_space = self.bytes.as_ptr_range().end.offset_from(data_address) as usize;
+            let available_space = self.bytes.end.offset_from(data_address) as usize;
             if available_space < layout.size() {
                 Err(ArchiveError::Overrun {
                     ptr: data_address,
                     size: layout.size(),
-                    range: self.bytes.as_ptr_range(),
+                    range: self.bytes.clone(),
                 })
             } else {
                 Ok(())
@@ -380,14 +405,20 @@ impl<'a> ArchiveContext for ArchiveValidator<'a> {
         start: *const u8,
         root: *const u8,
     ) -> Result<SuffixRange, Self::Error> {
-        let result = SuffixRange {
-            start: self.subtree_range.start,
-            depth: self.subtree_depth,
-        };
-        self.subtree_depth += 1;
-        self.subtree_range.start = start;
-        self.subtree_range.end = root;
-        Ok(result)
+        if self.subtree_depth >= self.max_subtree_depth {
+            Err(ArchiveError::ExceededMaximumSubtreeDepth {
+                max_subtree_depth: self.max_subtree_depth,
+            })
+        } else {
+            let result = SuffixRange {
+                start: self.subtree_range.start,
+                depth: self.subtree_depth,
+            };
+            self.subtree_depth += 1;
+            self.subtree_range.start = start;
+            self.subtree_range.end = root;
+            Ok(result)
+        }
     }
 
     #[inline]
@@ -2,8 +2,11 @@
 
 mod archive;
 mod shared;
+#[cfg(feature = "std")]
+mod streaming;
 mod util;
 
+pub use crate::util::header::CheckError;
 use crate::{
     validation::{
         check_archived_root_with_context, check_archived_value_with_context, ArchiveContext,
@@ -15,6 +18,8 @@ pub use archive::*;
 use bytecheck::CheckBytes;
 use core::{alloc::Layout, any::TypeId, fmt};
 pub use shared::*;
+#[cfg(feature = "std")]
+pub use streaming::*;
 pub use util::*;
 
 /// The default validator error.
@@ -65,6 +70,29 @@ impl<'a> DefaultValidator<'a> {
             shared: SharedValidator::new(),
         }
     }
+
+    /// Creates a new validator from a byte range with a maximum validation depth.
+    ///
+    /// Each relative pointer that the validator descends through (for example, into a `Box` or a
+    /// shared pointer) counts toward the depth. Validation fails with
+    /// [`ExceededMaximumSubtreeDepth`](ArchiveError::ExceededMaximumSubtreeDepth) if an archive is
+    /// nested more deeply than the maximum depth, which bounds the stack usage of validating
+    /// untrusted archives.
+    #[inline]
+    pub fn with_max_depth(bytes: &'a [u8], max_depth: usize) -> Self {
+        Self {
+            archive: ArchiveValidator::with_max_depth(bytes, max_depth),
+            shared: SharedValidator::new(),
+        }
+    }
+
+    /// Returns the position of the innermost object that is being validated, if any.
+    ///
+    /// See [`ArchiveValidator::subtree_root_pos`] for more details.
+    #[inline]
+    pub fn subtree_root_pos(&self) -> Option<usize> {
+        self.archive.subtree_root_pos()
+    }
 }
 
 impl<'a> Fallible for DefaultValidator<'a> {
@@ -227,3 +255,131 @@ where
     let mut validator = DefaultValidator::new(bytes);
     check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator)
 }
+
+/// A validation error along with the location in the archive where it occurred.
+///
+/// This is returned by [`check_archived_value_located`] and [`check_archived_root_located`].
+#[derive(Debug)]
+pub struct LocatedError<E> {
+    /// The validation error
+    ///
+    /// Errors from derived `CheckBytes` implementations name each field that the error occurred in,
+    /// from the outermost to the innermost.
+    pub error: E,
+    /// The position of the innermost object that the error occurred in
+    ///
+    /// This is either the root object or the last object reached through a relative pointer before
+    /// the error occurred. It is `None` if the error occurred while locating the root object.
+    pub pos: Option<usize>,
+}
+
+impl<E: fmt::Display> fmt::Display for LocatedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pos {
+            Some(pos) => write!(f, "{} (in object at position {})", self.error, pos),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for LocatedError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.error as &dyn Error)
+        }
+    }
+};
+
+/// Checks the given archive at the given position for an archived version of the given type,
+/// reporting where in the archive validation failed.
+///
+/// This is the same as [`check_archived_value`], but returns a [`LocatedError`] on failure.
+#[inline]
+pub fn check_archived_value_located<'a, T: Archive>(
+    bytes: &'a [u8],
+    pos: usize,
+) -> Result<&'a T::Archived, LocatedError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    let mut validator = DefaultValidator::new(bytes);
+    check_archived_value_with_context::<T, DefaultValidator>(bytes, pos, &mut validator).map_err(
+        |error| LocatedError {
+            error,
+            pos: validator.subtree_root_pos(),
+        },
+    )
+}
+
+/// Checks the given archive for an archived version of the given type, reporting where in the
+/// archive validation failed.
+///
+/// This is the same as [`check_archived_root`], but returns a [`LocatedError`] on failure.
+#[inline]
+pub fn check_archived_root_located<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<&'a T::Archived, LocatedError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    let mut validator = DefaultValidator::new(bytes);
+    check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator).map_err(
+        |error| LocatedError {
+            error,
+            pos: validator.subtree_root_pos(),
+        },
+    )
+}
+
+/// Safely accesses the archived root of the given bytes.
+///
+/// The buffer is first checked to be large enough to hold the archived root, then the whole
+/// archive is validated with `CheckBytes` before the typed reference is returned. This is a safe
+/// alternative to [`archived_root`](crate::archived_root).
+///
+/// # Examples
+/// ```
+/// use rkyv::{access, ser::{Serializer, serializers::AlignedSerializer}, AlignedVec, Archive, Serialize};
+/// use bytecheck::CheckBytes;
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive_attr(derive(CheckBytes))]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let mut serializer = AlignedSerializer::new(AlignedVec::new());
+/// serializer.serialize_value(&value).unwrap();
+/// let buf = serializer.into_inner();
+///
+/// let archived = access::<Example>(buf.as_ref()).unwrap();
+/// assert_eq!(archived.name, "pi");
+/// assert_eq!(archived.value, 31415926);
+///
+/// assert!(access::<Example>(&buf[..4]).is_err());
+/// ```
+#[inline]
+pub fn access<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<&'a T::Archived, CheckError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    let required = core::mem::size_of::<T::Archived>();
+    if bytes.len() < required {
+        return Err(CheckError::BufferTooShort {
+            len: bytes.len(),
+            required,
+        });
+    }
+    check_archived_root::<T>(bytes).map_err(CheckError::Invalid)
+}
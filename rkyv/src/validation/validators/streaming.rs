@@ -0,0 +1,354 @@
+//! Validation of archives read from a reader.
+
+use crate::{
+    validation::{
+        check_archived_value_at,
+        validators::{
+            ArchiveError, ArchiveValidator, PrefixRange, SharedError, SharedValidator, SuffixRange,
+        },
+        ArchiveContext, CheckArchiveError, CheckTypeError, SharedContext,
+    },
+    AlignedVec, Archive, Fallible,
+};
+use bytecheck::CheckBytes;
+use core::{alloc::Layout, any::TypeId, convert::TryFrom, fmt, ptr::NonNull};
+use std::{
+    alloc,
+    error::Error,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// Errors that can occur while validating an archive from a reader.
+#[derive(Debug)]
+pub enum StreamingValidatorError {
+    /// An archive validator error occurred.
+    ArchiveError(ArchiveError),
+    /// A shared validator error occurred.
+    SharedError(SharedError),
+    /// Reading part of the archive failed.
+    IoError(io::Error),
+}
+
+impl fmt::Display for StreamingValidatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ArchiveError(e) => write!(f, "{}", e),
+            Self::SharedError(e) => write!(f, "{}", e),
+            Self::IoError(e) => write!(f, "failed to read archive: {}", e),
+        }
+    }
+}
+
+impl Error for StreamingValidatorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ArchiveError(e) => Some(e as &dyn Error),
+            Self::SharedError(e) => Some(e as &dyn Error),
+            Self::IoError(e) => Some(e as &dyn Error),
+        }
+    }
+}
+
+/// A validator that reads the parts of an archive it checks from a reader.
+///
+/// The archive is read in blocks of a fixed size, and a block is only read the first time
+/// validation reaches an object inside of it. Each block is read at most once, so the number of
+/// bytes read is bounded by the parts of the archive that validation reaches.
+///
+/// Blocks are read into a zeroed buffer the size of the whole archive so that relative pointers
+/// can be followed as usual. Blocks are never evicted, because objects may still be borrowed by
+/// the objects that point to them. Memory usage is therefore not bounded: on platforms that lazily
+/// commit zeroed allocations, the blocks that were read take up memory, and on other platforms the
+/// whole archive does. To check archives that are larger than memory, memory-map them instead.
+pub struct StreamingValidator<'a, R> {
+    reader: &'a mut R,
+    buffer: NonNull<u8>,
+    len: usize,
+    block_size: usize,
+    loaded: Vec<u64>,
+    bytes_read: u64,
+    archive: ArchiveValidator<'a>,
+    shared: SharedValidator,
+}
+
+impl<'a, R: Read + Seek> StreamingValidator<'a, R> {
+    /// The default number of bytes read at a time.
+    pub const DEFAULT_BLOCK_SIZE: usize = 4096;
+
+    /// Creates a new validator for the archive in the given reader which reads `block_size` bytes
+    /// at a time.
+    ///
+    /// The length of the archive is the length of the reader.
+    pub fn new(reader: &'a mut R, block_size: usize) -> io::Result<Self> {
+        assert!(block_size != 0, "block size must be nonzero");
+
+        let len = reader.seek(SeekFrom::End(0))?;
+        let len = usize::try_from(len).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "archive is too large for the address space",
+            )
+        })?;
+        let buffer = if len == 0 {
+            NonNull::dangling()
+        } else {
+            let layout = Self::layout(len);
+            match NonNull::new(unsafe { alloc::alloc_zeroed(layout) }) {
+                Some(buffer) => buffer,
+                None => alloc::handle_alloc_error(layout),
+            }
+        };
+        let start = buffer.as_ptr() as *const u8;
+        let range = start..start.wrapping_add(len);
+
+        Ok(Self {
+            reader,
+            buffer,
+            len,
+            block_size,
+            loaded: vec![0; len / block_size / 64 + 1],
+            bytes_read: 0,
+            archive: unsafe { ArchiveValidator::from_ptr_range(range, usize::MAX) },
+            shared: SharedValidator::new(),
+        })
+    }
+
+    /// Returns the length of the archive.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the archive is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the total number of bytes read from the reader so far.
+    #[inline]
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    #[inline]
+    fn layout(len: usize) -> Layout {
+        Layout::from_size_align(len, AlignedVec::ALIGNMENT).unwrap()
+    }
+
+    #[inline]
+    fn is_loaded(&self, block: usize) -> bool {
+        self.loaded[block / 64] & (1 << (block % 64)) != 0
+    }
+
+    /// Reads every block that overlaps the given range of the archive and hasn't been read yet.
+    fn load(&mut self, start: usize, end: usize) -> io::Result<()> {
+        if start == end {
+            return Ok(());
+        }
+
+        let mut block = start / self.block_size;
+        let last_block = (end - 1) / self.block_size;
+        while block <= last_block {
+            if self.is_loaded(block) {
+                block += 1;
+                continue;
+            }
+
+            // Read runs of unloaded blocks together
+            let first = block;
+            while block <= last_block && !self.is_loaded(block) {
+                self.loaded[block / 64] |= 1 << (block % 64);
+                block += 1;
+            }
+
+            let read_start = first * self.block_size;
+            let read_end = (block * self.block_size).min(self.len);
+            // SAFETY: No references to unloaded blocks exist, because objects are only checked
+            // after the blocks they occupy have been loaded.
+            let bytes = unsafe {
+                core::slice::from_raw_parts_mut(
+                    self.buffer.as_ptr().add(read_start),
+                    read_end - read_start,
+                )
+            };
+            self.reader.seek(SeekFrom::Start(read_start as u64))?;
+            self.reader.read_exact(bytes)?;
+            self.bytes_read += bytes.len() as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, R> Drop for StreamingValidator<'a, R> {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            unsafe {
+                alloc::dealloc(
+                    self.buffer.as_ptr(),
+                    Layout::from_size_align_unchecked(self.len, AlignedVec::ALIGNMENT),
+                );
+            }
+        }
+    }
+}
+
+impl<'a, R> fmt::Debug for StreamingValidator<'a, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingValidator")
+            .field("len", &self.len)
+            .field("block_size", &self.block_size)
+            .field("bytes_read", &self.bytes_read)
+            .finish()
+    }
+}
+
+impl<'a, R> Fallible for StreamingValidator<'a, R> {
+    type Error = StreamingValidatorError;
+}
+
+impl<'a, R: Read + Seek> ArchiveContext for StreamingValidator<'a, R> {
+    type PrefixRange = PrefixRange;
+    type SuffixRange = SuffixRange;
+
+    #[inline]
+    unsafe fn bounds_check_ptr(
+        &mut self,
+        base: *const u8,
+        offset: isize,
+    ) -> Result<*const u8, Self::Error> {
+        self.archive
+            .bounds_check_ptr(base, offset)
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.archive
+            .bounds_check_layout(data_address, layout)
+            .map_err(StreamingValidatorError::ArchiveError)?;
+
+        let start = data_address as usize - self.buffer.as_ptr() as usize;
+        self.load(start, start + layout.size())
+            .map_err(StreamingValidatorError::IoError)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_subtree_ptr_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.archive
+            .bounds_check_subtree_ptr_layout(data_address, layout)
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<PrefixRange, Self::Error> {
+        self.archive
+            .push_prefix_subtree_range(root, end)
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+
+    #[inline]
+    fn pop_prefix_range(&mut self, range: PrefixRange) -> Result<(), Self::Error> {
+        self.archive
+            .pop_prefix_range(range)
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<SuffixRange, Self::Error> {
+        self.archive
+            .push_suffix_subtree_range(start, root)
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+
+    #[inline]
+    fn pop_suffix_range(&mut self, range: SuffixRange) -> Result<(), Self::Error> {
+        self.archive
+            .pop_suffix_range(range)
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+
+    #[inline]
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        self.archive
+            .finish()
+            .map_err(StreamingValidatorError::ArchiveError)
+    }
+}
+
+impl<'a, R> SharedContext for StreamingValidator<'a, R> {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        ptr: *const u8,
+        type_id: TypeId,
+    ) -> Result<bool, Self::Error> {
+        self.shared
+            .register_shared_ptr(ptr, type_id)
+            .map_err(StreamingValidatorError::SharedError)
+    }
+}
+
+/// Checks the archive in the given reader for an archived version of the given type, reading only
+/// the parts of the archive that validation reaches.
+///
+/// The root object is located at the end of the reader, like with
+/// [`check_archived_root`](crate::validation::validators::check_archived_root). The archive is
+/// read in blocks of [`DEFAULT_BLOCK_SIZE`](StreamingValidator::DEFAULT_BLOCK_SIZE) bytes. Use
+/// [`check_bytes_streaming_with_context`] to choose a different block size or to inspect the
+/// validator afterward.
+///
+/// Because the archive is never fully in memory, this only checks it and does not return a
+/// reference to the archived value.
+#[inline]
+pub fn check_bytes_streaming<'a, T, R>(
+    reader: &'a mut R,
+) -> Result<(), CheckTypeError<T::Archived, StreamingValidator<'a, R>>>
+where
+    T: Archive,
+    T::Archived: CheckBytes<StreamingValidator<'a, R>>,
+    R: Read + Seek,
+{
+    let block_size = StreamingValidator::<R>::DEFAULT_BLOCK_SIZE;
+    let mut validator = StreamingValidator::new(reader, block_size)
+        .map_err(|e| CheckArchiveError::ContextError(StreamingValidatorError::IoError(e)))?;
+    check_bytes_streaming_with_context::<T, R>(&mut validator)
+}
+
+/// Checks the archive read by the given streaming validator for an archived version of the given
+/// type.
+///
+/// See [`check_bytes_streaming`] for more details.
+#[inline]
+pub fn check_bytes_streaming_with_context<'a, T, R>(
+    validator: &mut StreamingValidator<'a, R>,
+) -> Result<(), CheckTypeError<T::Archived, StreamingValidator<'a, R>>>
+where
+    T: Archive,
+    T::Archived: CheckBytes<StreamingValidator<'a, R>>,
+    R: Read + Seek,
+{
+    let base = validator.buffer.as_ptr() as *const u8;
+    let pos = validator.len as isize - core::mem::size_of::<T::Archived>() as isize;
+    unsafe {
+        check_archived_value_at::<T, StreamingValidator<'a, R>>(base, pos, validator)?;
+    }
+    Ok(())
+}
@@ -4,13 +4,15 @@ mod raw;
 
 use crate::{
     ser::{ScratchSpace, Serializer},
-    Archive, Archived, RelPtr, Serialize, SerializeUnsized,
+    Archive, Archived, Deserialize, Fallible, RelPtr, Serialize, SerializeUnsized,
 };
 use core::{
     borrow::Borrow,
     cmp, fmt, hash,
+    mem::{self, MaybeUninit},
     ops::{Deref, Index, IndexMut},
     pin::Pin,
+    ptr,
     slice::SliceIndex,
 };
 
@@ -51,6 +53,81 @@ impl<T> ArchivedVec<T> {
         unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
     }
 
+    /// Deserializes the element at the given index without deserializing the rest of the vec.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    #[inline]
+    pub fn deserialize_element<U, D>(
+        &self,
+        index: usize,
+        deserializer: &mut D,
+    ) -> Result<Option<U>, D::Error>
+    where
+        T: Deserialize<U, D>,
+        D: Fallible + ?Sized,
+    {
+        self.as_slice()
+            .get(index)
+            .map(|element| element.deserialize(deserializer))
+            .transpose()
+    }
+
+    /// Deserializes the elements of the archived vec into the given slice without allocating.
+    ///
+    /// On success, returns the number of elements written to the front of `out`. The caller is
+    /// responsible for dropping those elements. If `out` is too small to hold every element, then
+    /// no elements are deserialized and an error is returned. If deserializing an element fails,
+    /// the elements that were already written are dropped before the error is returned.
+    #[inline]
+    pub fn deserialize_into_slice<U, D>(
+        &self,
+        out: &mut [MaybeUninit<U>],
+        deserializer: &mut D,
+    ) -> Result<usize, DeserializeIntoSliceError<D::Error>>
+    where
+        T: Deserialize<U, D>,
+        D: Fallible + ?Sized,
+    {
+        // Drops the initialized elements if deserialization fails or panics
+        struct Guard<'a, U> {
+            out: &'a mut [MaybeUninit<U>],
+            initialized: usize,
+        }
+
+        impl<U> Drop for Guard<'_, U> {
+            fn drop(&mut self) {
+                for element in &mut self.out[..self.initialized] {
+                    unsafe {
+                        ptr::drop_in_place(element.as_mut_ptr());
+                    }
+                }
+            }
+        }
+
+        let len = self.len();
+        if out.len() < len {
+            return Err(DeserializeIntoSliceError::SliceTooSmall {
+                len,
+                capacity: out.len(),
+            });
+        }
+
+        let mut guard = Guard {
+            out,
+            initialized: 0,
+        };
+        for element in self.as_slice() {
+            let value = element
+                .deserialize(deserializer)
+                .map_err(DeserializeIntoSliceError::Deserialize)?;
+            guard.out[guard.initialized] = MaybeUninit::new(value);
+            guard.initialized += 1;
+        }
+        mem::forget(guard);
+
+        Ok(len)
+    }
+
     /// Gets the elements of the archived vec as a pinned mutable slice.
     #[inline]
     pub fn pin_mut_slice(self: Pin<&mut Self>) -> Pin<&mut [T]> {
@@ -71,6 +148,47 @@ impl<T> ArchivedVec<T> {
         unsafe { self.pin_mut_slice().map_unchecked_mut(|s| &mut s[index]) }
     }
 
+    /// Divides the archived vec into two pinned mutable slices at an index.
+    ///
+    /// The first slice contains the elements in `[0, mid)` and the second contains the elements in
+    /// `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[inline]
+    pub fn split_at_pin_mut(self: Pin<&mut Self>, mid: usize) -> (Pin<&mut [T]>, Pin<&mut [T]>) {
+        unsafe {
+            let (left, right) = self.pin_mut_slice().get_unchecked_mut().split_at_mut(mid);
+            (Pin::new_unchecked(left), Pin::new_unchecked(right))
+        }
+    }
+
+    /// Sorts the elements of the archived vec in place, without preserving the order of equal
+    /// elements.
+    ///
+    /// See [`slice::sort_unstable`](prim@slice#method.sort_unstable) for more details.
+    #[inline]
+    pub fn sort_unstable(self: Pin<&mut Self>)
+    where
+        T: Ord + Unpin,
+    {
+        self.pin_mut_slice().get_mut().sort_unstable();
+    }
+
+    /// Sorts the elements of the archived vec in place with a comparator function, without
+    /// preserving the order of equal elements.
+    ///
+    /// See [`slice::sort_unstable_by`](prim@slice#method.sort_unstable_by) for more details.
+    #[inline]
+    pub fn sort_unstable_by<F>(self: Pin<&mut Self>, compare: F)
+    where
+        T: Unpin,
+        F: FnMut(&T, &T) -> cmp::Ordering,
+    {
+        self.pin_mut_slice().get_mut().sort_unstable_by(compare);
+    }
+
     /// Resolves an archived `Vec` from a given slice.
     ///
     /// # Safety
@@ -146,6 +264,12 @@ impl<T> ArchivedVec<T> {
 
     /// Serializes an archived `Vec` from a given iterator.
     ///
+    /// Each item is serialized as soon as it is yielded, so the items do not need to be collected
+    /// into a `Vec` first. The iterator may yield owned items or references. Items are held in
+    /// scratch space along with their resolvers until all of them have been serialized, and the
+    /// iterator must report its exact length. The archived vec can then be resolved with
+    /// [`resolve_from_len`](ArchivedVec::resolve_from_len).
+    ///
     /// This method is unable to perform copy optimizations; prefer
     /// [`serialize_from_slice`](ArchivedVec::serialize_from_slice) when possible.
     #[inline]
@@ -295,6 +419,47 @@ pub struct VecResolver {
     pos: usize,
 }
 
+/// The error returned by [`ArchivedVec::deserialize_into_slice`].
+#[derive(Debug)]
+pub enum DeserializeIntoSliceError<E> {
+    /// The slice was too small to hold every element.
+    SliceTooSmall {
+        /// The number of elements in the archived vec
+        len: usize,
+        /// The length of the slice
+        capacity: usize,
+    },
+    /// An error occurred while deserializing an element
+    Deserialize(E),
+}
+
+impl<E: fmt::Display> fmt::Display for DeserializeIntoSliceError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SliceTooSmall { len, capacity } => write!(
+                f,
+                "slice too small: {} elements do not fit in a slice of length {}",
+                len, capacity
+            ),
+            Self::Deserialize(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for DeserializeIntoSliceError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::SliceTooSmall { .. } => None,
+                Self::Deserialize(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
 #[cfg(feature = "validation")]
 const _: () = {
     use crate::validation::{
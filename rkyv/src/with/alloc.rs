@@ -1,30 +1,41 @@
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
-    collections::util::Entry,
+    collections::{
+        packed_bools::{ArchivedPackedBools, PackedBoolsResolver},
+        util::Entry,
+    },
+    de::{AllocationTracker, StringInterner},
     niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
-    ser::{ScratchSpace, Serializer},
+    ser::{ScratchSpace, Serializer, ValueSharedSerializer},
     string::{ArchivedString, StringResolver},
     vec::{ArchivedVec, RawArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsOwned, AsVec, CopyOptimize, DeserializeWith, Map, Niche, Raw, RefAsBox,
-        SerializeWith, With,
+        ArchiveWith, AsOwned, AsVec, Budgeted, CopyOptimize, DeserializeWith, Intern, Map, Niche,
+        PackedBools, Raw, RefAsBox, SerializeWith, ShareByValue, With,
     },
-    Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized, Fallible,
-    MetadataResolver, Serialize, SerializeUnsized,
+    Archive, ArchiveUnsized, Archived, ArchivedMetadata, Deserialize, DeserializeUnsized, Fallible,
+    MetadataResolver, Resolver, Serialize, SerializeUnsized,
 };
-use ::core::marker::PhantomData;
+use ::core::{hash::Hash, marker::PhantomData};
+#[cfg(all(not(feature = "std"), has_atomics))]
+use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use alloc::{
     borrow::Cow,
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    string::String,
     vec::Vec,
 };
+#[cfg(all(feature = "std", has_atomics))]
+use std::sync::Arc;
 #[cfg(feature = "std")]
 use std::{
     borrow::Cow,
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    rc::Rc,
 };
 
 // Map for Vecs
@@ -165,21 +176,6 @@ impl<'a, T: Serialize<S> + Clone, S: ScratchSpace + Serializer + ?Sized>
     }
 }
 
-impl<'a, T, D> DeserializeWith<ArchivedVec<T::Archived>, Cow<'a, [T]>, D> for AsOwned
-where
-    T: Archive + Clone,
-    T::Archived: Deserialize<T, D>,
-    D: Fallible + ?Sized,
-{
-    #[inline]
-    fn deserialize_with(
-        field: &ArchivedVec<T::Archived>,
-        deserializer: &mut D,
-    ) -> Result<Cow<'a, [T]>, D::Error> {
-        Ok(Cow::Owned(field.deserialize(deserializer)?))
-    }
-}
-
 impl<'a> ArchiveWith<Cow<'a, str>> for AsOwned {
     type Archived = ArchivedString;
     type Resolver = StringResolver;
@@ -205,16 +201,6 @@ impl<'a, S: Serializer + ?Sized> SerializeWith<Cow<'a, str>, S> for AsOwned {
     }
 }
 
-impl<'a, D: Fallible + ?Sized> DeserializeWith<ArchivedString, Cow<'a, str>, D> for AsOwned {
-    #[inline]
-    fn deserialize_with(
-        field: &ArchivedString,
-        deserializer: &mut D,
-    ) -> Result<Cow<'a, str>, D::Error> {
-        Ok(Cow::Owned(field.deserialize(deserializer)?))
-    }
-}
-
 #[cfg(feature = "std")]
 const _: () = {
     use crate::ffi::{ArchivedCString, CStringResolver};
@@ -244,17 +230,223 @@ const _: () = {
             ArchivedCString::serialize_from_c_str(field, serializer)
         }
     }
+};
 
-    impl<'a, D: Fallible + ?Sized> DeserializeWith<ArchivedCString, Cow<'a, CStr>, D> for AsOwned {
-        #[inline]
-        fn deserialize_with(
-            field: &ArchivedCString,
-            deserializer: &mut D,
-        ) -> Result<Cow<'a, CStr>, D::Error> {
-            Ok(Cow::Owned(field.deserialize(deserializer)?))
-        }
+// Intern
+
+impl ArchiveWith<Rc<str>> for Intern {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Rc<str>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedString::resolve_from_str(field, pos, resolver, out);
     }
-};
+}
+
+impl<S: Serializer + ?Sized> SerializeWith<Rc<str>, S> for Intern {
+    #[inline]
+    fn serialize_with(field: &Rc<str>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(field, serializer)
+    }
+}
+
+impl<D: StringInterner + ?Sized> DeserializeWith<ArchivedString, Rc<str>, D> for Intern {
+    #[inline]
+    fn deserialize_with(field: &ArchivedString, deserializer: &mut D) -> Result<Rc<str>, D::Error> {
+        deserializer.intern(field.as_str())
+    }
+}
+
+// ShareByValue
+
+impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Rc<T>> for ShareByValue {
+    type Archived = Archived<Rc<T>>;
+    type Resolver = Resolver<Rc<T>>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Rc<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Rc<T>, S> for ShareByValue
+where
+    T: SerializeUnsized<S> + Hash + Eq + ?Sized + 'static,
+    S: Serializer + ValueSharedSerializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &Rc<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <Archived<Rc<T>>>::serialize_from_shared_by_value(field, serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<Archived<Rc<T>>, Rc<T>, D> for ShareByValue
+where
+    T: ArchiveUnsized + ?Sized,
+    Archived<Rc<T>>: Deserialize<Rc<T>, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(field: &Archived<Rc<T>>, deserializer: &mut D) -> Result<Rc<T>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+#[cfg(has_atomics)]
+impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Arc<T>> for ShareByValue {
+    type Archived = Archived<Arc<T>>;
+    type Resolver = Resolver<Arc<T>>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Arc<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+#[cfg(has_atomics)]
+impl<T, S> SerializeWith<Arc<T>, S> for ShareByValue
+where
+    T: SerializeUnsized<S> + Hash + Eq + ?Sized + 'static,
+    S: Serializer + ValueSharedSerializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &Arc<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <Archived<Arc<T>>>::serialize_from_shared_by_value(field, serializer)
+    }
+}
+
+#[cfg(has_atomics)]
+impl<T, D> DeserializeWith<Archived<Arc<T>>, Arc<T>, D> for ShareByValue
+where
+    T: ArchiveUnsized + ?Sized,
+    Archived<Arc<T>>: Deserialize<Arc<T>, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &Archived<Arc<T>>,
+        deserializer: &mut D,
+    ) -> Result<Arc<T>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// Budgeted
+
+impl<T: Archive> ArchiveWith<Vec<T>> for Budgeted {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<T: Serialize<S>, S: ScratchSpace + Serializer + ?Sized> SerializeWith<Vec<T>, S> for Budgeted {
+    #[inline]
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        field.serialize(serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedVec<T::Archived>, Vec<T>, D> for Budgeted
+where
+    T: Archive,
+    ArchivedVec<T::Archived>: Deserialize<Vec<T>, D>,
+    D: AllocationTracker + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        deserializer.track_allocation(field.len().saturating_mul(::core::mem::size_of::<T>()))?;
+        field.deserialize(deserializer)
+    }
+}
+
+impl ArchiveWith<String> for Budgeted {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &String,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedString::resolve_from_str(field, pos, resolver, out);
+    }
+}
+
+impl<S: Serializer + ?Sized> SerializeWith<String, S> for Budgeted {
+    #[inline]
+    fn serialize_with(field: &String, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(field, serializer)
+    }
+}
+
+impl<D: AllocationTracker + ?Sized> DeserializeWith<ArchivedString, String, D> for Budgeted {
+    #[inline]
+    fn deserialize_with(field: &ArchivedString, deserializer: &mut D) -> Result<String, D::Error> {
+        deserializer.track_allocation(field.as_str().len())?;
+        Ok(field.as_str().into())
+    }
+}
+
+// PackedBools
+
+impl ArchiveWith<Vec<bool>> for PackedBools {
+    type Archived = ArchivedPackedBools;
+    type Resolver = PackedBoolsResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Vec<bool>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedPackedBools::resolve_from_slice(field, pos, resolver, out);
+    }
+}
+
+impl<S: ScratchSpace + Serializer + ?Sized> SerializeWith<Vec<bool>, S> for PackedBools {
+    #[inline]
+    fn serialize_with(field: &Vec<bool>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedPackedBools::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedPackedBools, Vec<bool>, D> for PackedBools {
+    #[inline]
+    fn deserialize_with(field: &ArchivedPackedBools, _: &mut D) -> Result<Vec<bool>, D::Error> {
+        Ok(field.iter().collect())
+    }
+}
 
 // AsVec
 
@@ -1,5 +1,7 @@
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
+    collections::bit_set::ArchivedBitSet,
+    lazy::{LazyField, LazyResolver},
     niche::option_nonzero::{
         ArchivedOptionNonZeroI128, ArchivedOptionNonZeroI16, ArchivedOptionNonZeroI32,
         ArchivedOptionNonZeroI64, ArchivedOptionNonZeroI8, ArchivedOptionNonZeroU128,
@@ -7,9 +9,10 @@ use crate::{
         ArchivedOptionNonZeroU8,
     },
     option::ArchivedOption,
+    ser::Serializer,
     with::{
-        ArchiveWith, AsBox, DeserializeWith, Inline, Map, Niche, RefAsBox, SerializeWith, Skip,
-        Unsafe,
+        ArchiveWith, AsBitSet, AsBox, DeserializeWith, Inline, Lazy, Map, Niche, RefAsBox,
+        SerializeWith, Skip, Unsafe,
     },
     Archive, ArchiveUnsized, Deserialize, Fallible, Serialize, SerializeUnsized,
 };
@@ -412,6 +415,25 @@ where
     }
 }
 
+// Lazy
+
+impl<F: Archive> ArchiveWith<F> for Lazy {
+    type Archived = LazyField<F::Archived>;
+    type Resolver = LazyResolver;
+
+    #[inline]
+    unsafe fn resolve_with(_: &F, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        LazyField::resolve(pos, resolver, out);
+    }
+}
+
+impl<F: Serialize<S>, S: Serializer + ?Sized> SerializeWith<F, S> for Lazy {
+    #[inline]
+    fn serialize_with(field: &F, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        LazyField::serialize_from_ref(field, serializer)
+    }
+}
+
 // Skip
 
 impl<F> ArchiveWith<F> for Skip {
@@ -432,3 +454,56 @@ impl<F: Default, D: Fallible + ?Sized> DeserializeWith<(), F, D> for Skip {
         Ok(Default::default())
     }
 }
+
+// AsBitSet
+
+impl<const N: usize> ArchiveWith<[u64; N]> for AsBitSet {
+    type Archived = ArchivedBitSet<N>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve_with(field: &[u64; N], _: usize, _: (), out: *mut Self::Archived) {
+        ArchivedBitSet::emplace(field, out);
+    }
+}
+
+impl<S: Fallible + ?Sized, const N: usize> SerializeWith<[u64; N], S> for AsBitSet {
+    #[inline]
+    fn serialize_with(_: &[u64; N], _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized, const N: usize> DeserializeWith<ArchivedBitSet<N>, [u64; N], D>
+    for AsBitSet
+{
+    #[inline]
+    fn deserialize_with(field: &ArchivedBitSet<N>, _: &mut D) -> Result<[u64; N], D::Error> {
+        Ok(field.to_words())
+    }
+}
+
+impl ArchiveWith<u128> for AsBitSet {
+    type Archived = ArchivedBitSet<2>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve_with(field: &u128, _: usize, _: (), out: *mut Self::Archived) {
+        ArchivedBitSet::emplace(&[*field as u64, (*field >> 64) as u64], out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<u128, S> for AsBitSet {
+    #[inline]
+    fn serialize_with(_: &u128, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedBitSet<2>, u128, D> for AsBitSet {
+    #[inline]
+    fn deserialize_with(field: &ArchivedBitSet<2>, _: &mut D) -> Result<u128, D::Error> {
+        let [low, high] = field.to_words();
+        Ok(low as u128 | ((high as u128) << 64))
+    }
+}
@@ -1,6 +1,6 @@
 //! Wrapper type support and commonly used wrappers.
 //!
-//! Wrappers can be applied with the `#[with(...)]` attribute in the
+//! Wrappers can be applied with the `#[with(...)]` or `#[archive(with = "...")]` attributes in the
 //! [`Archive`](macro@crate::Archive) macro. See [`With`] for examples.
 
 #[cfg(feature = "alloc")]
@@ -298,6 +298,11 @@ pub struct Map<Archivable> {
 ///
 /// By default, atomics are archived with an underlying integer.
 ///
+/// All loads and stores performed by this wrapper use
+/// [`Relaxed`](::core::sync::atomic::Ordering::Relaxed) ordering, both when copying the value into
+/// the archive and when deserializing it. Synchronize with any writers before serializing if the
+/// archived value must observe their writes.
+///
 /// # Safety
 ///
 /// This wrapper is only safe to use when the backing memory for wrapped types is mutable.
@@ -444,6 +449,83 @@ impl fmt::Display for AsStringError {
 #[cfg(feature = "std")]
 impl ::std::error::Error for AsStringError {}
 
+/// A wrapper that archives an `Rc<str>` as a string and interns it when deserializing.
+///
+/// The string is archived as an [`ArchivedString`](crate::string::ArchivedString) rather than a
+/// shared pointer. Deserializing it requires a [`StringInterner`](crate::de::StringInterner) like
+/// [`StringInternMap`](crate::de::deserializers::StringInternMap), which returns the same `Rc<str>`
+/// for every archived string with the same contents.
+///
+/// # Example
+///
+/// ```
+/// use std::rc::Rc;
+/// use rkyv::{Archive, Deserialize, Serialize, with::{Intern, Map}};
+///
+/// #[derive(Archive, Deserialize, Serialize)]
+/// struct Example {
+///     #[with(Intern)]
+///     name: Rc<str>,
+///     #[with(Map<Intern>)]
+///     tags: Vec<Rc<str>>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Intern;
+
+/// A wrapper that shares the archived value of an `Rc` or `Arc` with every other shared pointer
+/// that has an equal value.
+///
+/// By default, shared pointers are only shared with pointers to the same allocation. With this
+/// wrapper, equal values in different allocations are also serialized only once. Serializing it
+/// requires a [`ValueSharedSerializer`](crate::ser::ValueSharedSerializer) like
+/// [`SharedValueSerializeMap`](crate::ser::serializers::SharedValueSerializeMap).
+///
+/// # Example
+///
+/// ```
+/// use std::rc::Rc;
+/// use rkyv::{Archive, Deserialize, Serialize, with::{Map, ShareByValue}};
+///
+/// #[derive(Archive, Deserialize, Serialize)]
+/// struct Example {
+///     #[with(ShareByValue)]
+///     name: Rc<str>,
+///     #[with(Map<ShareByValue>)]
+///     tags: Vec<Rc<str>>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ShareByValue;
+
+/// A wrapper that tracks the memory allocated to deserialize a `Vec` or `String`.
+///
+/// Before the vector or string is allocated, its size in bytes is reported to the deserializer,
+/// which must be an [`AllocationTracker`](crate::de::AllocationTracker) like
+/// [`AllocationLimiter`](crate::de::deserializers::AllocationLimiter). This lets archives that
+/// declare enormous vectors be rejected before any memory is allocated for them.
+///
+/// Only fields with this wrapper count toward the budget. Other fields are deserialized without
+/// being tracked, even if they allocate, and only the outer allocation of a wrapped field is
+/// tracked. Nested vectors and strings need their own wrappers: [`Map<Budgeted>`](Map) tracks
+/// each string of a `Vec<String>`, but not the vector itself.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, Deserialize, Serialize, with::Budgeted};
+///
+/// #[derive(Archive, Deserialize, Serialize)]
+/// struct Example {
+///     #[with(Budgeted)]
+///     name: String,
+///     #[with(Budgeted)]
+///     values: Vec<u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Budgeted;
+
 /// A wrapper that locks a lock and serializes the value immutably.
 ///
 /// This wrapper can panic under very specific circumstances when:
@@ -497,6 +579,9 @@ impl ::std::error::Error for LockError {}
 
 /// A wrapper that serializes a `Cow` as if it were owned.
 ///
+/// `Cow<str>`, `Cow<[T]>`, and `Cow<CStr>` archive as their owned forms without a wrapper. This
+/// wrapper is needed for `Cow`s of other types.
+///
 /// # Example
 ///
 /// ```
@@ -506,7 +591,7 @@ impl ::std::error::Error for LockError {}
 /// #[derive(Archive)]
 /// struct Example<'a> {
 ///     #[with(AsOwned)]
-///     a: Cow<'a, str>,
+///     a: Cow<'a, u32>,
 /// }
 /// ```
 #[derive(Debug)]
@@ -590,9 +675,9 @@ pub struct CopyOptimize;
 /// A wrapper that converts a [`SystemTime`](::std::time::SystemTime) to a
 /// [`Duration`](::std::time::Duration) since [`UNIX_EPOCH`](::std::time::UNIX_EPOCH).
 ///
-/// If the serialized time occurs before the UNIX epoch, serialization will panic during `resolve`.
-/// The resulting archived time will be an [`ArchivedDuration`](crate::time::ArchivedDuration)
-/// relative to the UNIX epoch.
+/// If the serialized time occurs before the UNIX epoch, serialization will fail with
+/// [`UnixTimestampError::TimeBeforeUnixEpoch`]. The resulting archived time will be an
+/// [`ArchivedDuration`](crate::time::ArchivedDuration) relative to the UNIX epoch.
 ///
 /// Regular serializers don't support the custom error handling needed for this type by default. To
 /// use this wrapper, a custom serializer with an error type satisfying
@@ -697,7 +782,9 @@ pub struct Unsafe;
 
 /// A wrapper that skips serializing a field.
 ///
-/// Skipped fields must implement `Default` to be deserialized.
+/// Skipped fields must implement `Default` to be deserialized. The field is still present in the
+/// archived type as a `()`. To remove it from the archived type entirely, use the
+/// `#[archive(skip)]` field attribute instead.
 ///
 /// # Example
 ///
@@ -712,3 +799,67 @@ pub struct Unsafe;
 /// ```
 #[derive(Debug)]
 pub struct Skip;
+
+/// A wrapper that archives a field as a [`LazyField`](crate::lazy::LazyField).
+///
+/// The value of the field is archived out-of-line and isn't validated with the rest of the
+/// archive. It is validated when it's accessed with [`get`](crate::lazy::LazyField::get) instead.
+/// This is the same as the `#[archive(lazy)]` field attribute.
+///
+/// Lazy fields can't be deserialized along with the rest of a value because they may not have
+/// been validated. Get the value of the field and deserialize it separately instead.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, Serialize, with::Lazy};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[with(Lazy)]
+///     a: Vec<u8>,
+///     #[archive(lazy)]
+///     b: Vec<u8>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Lazy;
+
+/// A wrapper that archives a `[u64; N]` or `u128` as an
+/// [`ArchivedBitSet`](crate::collections::bit_set::ArchivedBitSet).
+///
+/// A `u128` archives as an `ArchivedBitSet<2>`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::AsBitSet};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsBitSet)]
+///     flags: u128,
+///     #[with(AsBitSet)]
+///     visited: [u64; 4],
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsBitSet;
+
+/// A wrapper that archives a `Vec<bool>` as an
+/// [`ArchivedPackedBools`](crate::collections::packed_bools::ArchivedPackedBools), packing the
+/// bools eight to a byte.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::PackedBools};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(PackedBools)]
+///     features: Vec<bool>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PackedBools;
@@ -1,7 +1,7 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
     repr::{BaseRepr, IntRepr, Repr},
-    util::{add_bounds, strip_raw},
+    util::{add_bounds, check_archive_field_args, forbid_skipped, is_skipped, strip_raw},
     with::{make_with_cast, make_with_ty},
 };
 use proc_macro2::{Span, TokenStream};
@@ -38,6 +38,16 @@ fn derive_archive_impl(
     mut input: DeriveInput,
     attributes: &Attributes,
 ) -> Result<TokenStream, Error> {
+    match input.data {
+        Data::Struct(ref data) => check_archive_field_args(&data.fields)?,
+        Data::Enum(ref data) => {
+            for variant in data.variants.iter() {
+                check_archive_field_args(&variant.fields)?;
+            }
+        }
+        Data::Union(_) => (),
+    }
+
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bound {
         add_bounds(bounds, where_clause)?;
@@ -139,10 +149,10 @@ fn derive_archive_impl(
 
             match data.fields {
                 Fields::Named(ref fields) => {
+                    let archived_fields = || fields.named.iter().filter(|f| !is_skipped(f));
+
                     let mut archive_where = where_clause.clone();
-                    for field in fields
-                        .named
-                        .iter()
+                    for field in archived_fields()
                         .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
                     {
                         let ty = with_ty(field)?;
@@ -151,14 +161,14 @@ fn derive_archive_impl(
                             .push(parse_quote! { #ty: #rkyv_path::Archive });
                     }
 
-                    let resolver_fields = fields.named.iter().map(|f| {
+                    let resolver_fields = archived_fields().map(|f| {
                         let name = &f.ident;
                         let ty = with_ty(f).unwrap();
                         quote! { #name: #rkyv_path::Resolver<#ty> }
                     });
 
                     let archived_def = if attributes.archive_as.is_none() {
-                        let archived_fields = fields.named.iter().map(|f| {
+                        let archived_fields = archived_fields().map(|f| {
                             let field_name = f.ident.as_ref();
                             let ty = with_ty(f).unwrap();
                             let vis = &f.vis;
@@ -188,7 +198,7 @@ fn derive_archive_impl(
                         None
                     };
 
-                    let resolve_fields = fields.named.iter().map(|f| {
+                    let resolve_fields = archived_fields().map(|f| {
                         let name = &f.ident;
                         let field = with_cast(f, parse_quote! { (&self.#name) }).unwrap();
                         quote! {
@@ -203,7 +213,7 @@ fn derive_archive_impl(
                         for compare in compares {
                             if compare.is_ident("PartialEq") {
                                 let mut partial_eq_where = archive_where.clone();
-                                for field in fields.named.iter().filter(|f| {
+                                for field in archived_fields().filter(|f| {
                                     !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds"))
                                 }) {
                                     let ty = &field.ty;
@@ -213,7 +223,7 @@ fn derive_archive_impl(
                                     );
                                 }
 
-                                let field_names = fields.named.iter().map(|f| &f.ident);
+                                let field_names = archived_fields().map(|f| &f.ident);
 
                                 partial_eq_impl = Some(quote! {
                                     impl #impl_generics PartialEq<#archived_type> for #name #ty_generics #partial_eq_where {
@@ -232,7 +242,7 @@ fn derive_archive_impl(
                                 });
                             } else if compare.is_ident("PartialOrd") {
                                 let mut partial_ord_where = archive_where.clone();
-                                for field in fields.named.iter().filter(|f| {
+                                for field in archived_fields().filter(|f| {
                                     !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds"))
                                 }) {
                                     let ty = &field.ty;
@@ -242,7 +252,7 @@ fn derive_archive_impl(
                                     );
                                 }
 
-                                let field_names = fields.named.iter().map(|f| &f.ident);
+                                let field_names = archived_fields().map(|f| &f.ident);
 
                                 partial_ord_impl = Some(quote! {
                                     impl #impl_generics PartialOrd<#archived_type> for #name #ty_generics #partial_ord_where {
@@ -277,9 +287,7 @@ fn derive_archive_impl(
                     let copy_safe_impl = if cfg!(feature = "copy") && attributes.copy_safe.is_some()
                     {
                         let mut copy_safe_where = where_clause.clone();
-                        for field in fields
-                            .named
-                            .iter()
+                        for field in archived_fields()
                             .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
                         {
                             let ty = with_ty(field).unwrap();
@@ -325,6 +333,8 @@ fn derive_archive_impl(
                     )
                 }
                 Fields::Unnamed(ref fields) => {
+                    forbid_skipped(&fields.unnamed)?;
+
                     let mut archive_where = where_clause.clone();
                     for field in fields
                         .unnamed
@@ -601,6 +611,10 @@ fn derive_archive_impl(
             }
         }
         Data::Enum(ref data) => {
+            for variant in data.variants.iter() {
+                forbid_skipped(&variant.fields)?;
+            }
+
             let mut archive_where = where_clause.clone();
             for variant in data.variants.iter() {
                 match variant.fields {
@@ -1280,6 +1294,19 @@ fn derive_archive_impl(
         }
     };
 
+    let version_impl = attributes.version.as_ref().map(|version| {
+        let version = version.base10_parse::<u32>().unwrap();
+        let mut version_where = where_clause.clone();
+        version_where
+            .predicates
+            .push(parse_quote! { #name #ty_generics: Archive });
+        quote! {
+            impl #impl_generics #rkyv_path::version::ArchiveVersion for #name #ty_generics #version_where {
+                const VERSION: u32 = #version;
+            }
+        }
+    });
+
     Ok(quote! {
         #archive_types
 
@@ -1289,6 +1316,7 @@ fn derive_archive_impl(
             use #rkyv_path::{out_field, Archive, Archived};
 
             #archive_impls
+            #version_impl
         };
     })
 }
@@ -1,6 +1,6 @@
 use crate::repr::Repr;
 use quote::ToTokens;
-use syn::{AttrStyle, DeriveInput, Error, Ident, Lit, LitStr, Meta, NestedMeta, Path};
+use syn::{AttrStyle, DeriveInput, Error, Ident, Lit, LitInt, LitStr, Meta, NestedMeta, Path};
 
 #[derive(Default)]
 pub struct Attributes {
@@ -14,6 +14,8 @@ pub struct Attributes {
     pub serialize_bound: Option<LitStr>,
     pub deserialize_bound: Option<LitStr>,
     pub copy_safe: Option<Path>,
+    pub trace: Option<Path>,
+    pub version: Option<LitInt>,
     pub rkyv_path: Option<Path>,
 }
 
@@ -38,6 +40,8 @@ fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<
         Meta::Path(path) => {
             if path.is_ident("copy_safe") {
                 try_set_attribute(&mut attributes.copy_safe, path.clone(), "copy_safe")
+            } else if path.is_ident("trace") {
+                try_set_attribute(&mut attributes.trace, path.clone(), "trace")
             } else {
                 Err(Error::new_spanned(meta, "unrecognized archive argument"))
             }
@@ -140,6 +144,13 @@ fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<
                 } else {
                     Err(Error::new_spanned(meta, "archive as must be a string"))
                 }
+            } else if meta.path.is_ident("version") {
+                if let Lit::Int(ref lit_int) = meta.lit {
+                    lit_int.base10_parse::<u32>()?;
+                    try_set_attribute(&mut attributes.version, lit_int.clone(), "version")
+                } else {
+                    Err(Error::new_spanned(meta, "version must be an integer"))
+                }
             } else if meta.path.is_ident("crate") {
                 if let Lit::Str(ref lit_str) = meta.lit {
                     let stream = syn::parse_str(&lit_str.value())?;
@@ -1,7 +1,7 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
-    util::add_bounds,
-    with::{make_with_ty, with_inner},
+    util::{add_bounds, is_skipped},
+    with::{make_with_inner, make_with_ty},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -42,6 +42,7 @@ fn derive_deserialize_impl(
     let default_rkyv_path = parse_quote! { ::rkyv };
     let rkyv_path = attributes.rkyv_path.as_ref().unwrap_or(&default_rkyv_path);
     let with_ty = make_with_ty(rkyv_path);
+    let with_inner = make_with_inner(rkyv_path);
 
     let name = &input.ident;
     let (impl_generics, _, _) = impl_input_generics.split_for_impl();
@@ -57,6 +58,14 @@ fn derive_deserialize_impl(
                     .iter()
                     .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
                 {
+                    if is_skipped(field) {
+                        let ty = &field.ty;
+                        deserialize_where
+                            .predicates
+                            .push(parse_quote! { #ty: Default });
+                        continue;
+                    }
+
                     let ty = with_ty(field)?;
                     deserialize_where
                         .predicates
@@ -68,6 +77,10 @@ fn derive_deserialize_impl(
 
                 let deserialize_fields = fields.named.iter().map(|f| {
                     let name = &f.ident;
+                    if is_skipped(f) {
+                        return quote! { #name: Default::default() };
+                    }
+
                     let ty = with_ty(f).unwrap();
                     let value = with_inner(
                         f,
@@ -45,14 +45,31 @@ use syn::{parse_macro_input, DeriveInput};
 ///   `Deserialize` bounds.
 /// - `copy_safe`: States that the archived type is tightly packed with no padding bytes. This
 ///   qualifies it for copy optimizations. (requires nightly)
+/// - `trace`: Makes the derived `Serialize` implementation report which field was being serialized
+///   when an error occurs. The serializer must implement `TraceSerializer`, which is called with
+///   the name of the type and field for each error that passes through the implementation.
 /// - `as = "..."`: Instead of generating a separate archived type, this type will archive as the
 ///   named type. This is useful for types which are generic over their parameters.
 /// - `crate = "..."`: Chooses an alternative crate path to import rkyv from.
+/// - `version = ...`: Implements `ArchiveVersion` with the given `u32` schema version. Versioned
+///   types can be serialized into archives that record their version, which can be read back with
+///   `archive_version` before accessing the archive.
 ///
 /// `#[archive_attr(...)]` adds the attributes passed as arguments as attributes to the generated
 /// type. This is commonly used with attributes like `derive(...)` to derive trait implementations
 /// for the archived type.
 ///
+/// # Skipping fields
+///
+/// Adding `#[archive(skip)]` to a field of a struct with named fields omits it from the archived
+/// type entirely. Skipped fields are not serialized and do not need to implement `Archive`. When
+/// deserializing, they are reconstructed with `Default::default()`.
+///
+/// This is like `#[with(Skip)]`, except that `with(Skip)` keeps the field in the archived type as
+/// a `()`. Because `#[archive(skip)]` removes the field instead, it also leaves it out of
+/// `compare(...)` implementations, but it can't be used on tuple structs or enums where removing a
+/// field would shift the positions of the others. Use `#[with(Skip)]` for those.
+///
 /// # Recursive types
 ///
 /// This derive macro automatically adds a type bound `field: Archive` for each field type. This can
@@ -69,6 +86,9 @@ use syn::{parse_macro_input, DeriveInput};
 /// reference as if it were a field of the struct. Wrappers can be applied to fields using the
 /// `#[with(...)]` attribute. Multiple wrappers can be used, and they are applied in reverse order
 /// (i.e. `#[with(A, B, C)]` will archive `MyType` as `With<With<With<MyType, C>, B, A>`).
+///
+/// A single wrapper can also be applied with `#[archive(with = "...")]`. Wrappers from `with` and
+/// `archive(with = "...")` attributes are combined in the order the attributes appear on the field.
 #[proc_macro_derive(Archive, attributes(archive, archive_attr, omit_bounds, with))]
 pub fn derive_archive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut derive_input = parse_macro_input!(input as DeriveInput);
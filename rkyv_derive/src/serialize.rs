@@ -1,12 +1,12 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
-    util::{add_bounds, strip_raw},
+    util::{add_bounds, is_skipped, strip_raw},
     with::{make_with_cast, make_with_ty},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Fields,
+    parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Expr, Fields,
     Generics, Ident, Index,
 };
 
@@ -19,6 +19,9 @@ fn derive_serialize_impl(
     mut input: DeriveInput,
     attributes: &Attributes,
 ) -> Result<TokenStream, Error> {
+    let default_rkyv_path = parse_quote! { ::rkyv };
+    let rkyv_path = attributes.rkyv_path.as_ref().unwrap_or(&default_rkyv_path);
+
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bound {
         add_bounds(bounds, where_clause)?;
@@ -26,6 +29,11 @@ fn derive_serialize_impl(
     if let Some(ref bounds) = attributes.serialize_bound {
         add_bounds(bounds, where_clause)?;
     }
+    if attributes.trace.is_some() {
+        where_clause
+            .predicates
+            .push(parse_quote! { __S: #rkyv_path::ser::TraceSerializer });
+    }
 
     let mut impl_input_params = Punctuated::default();
     impl_input_params.push(parse_quote! { __S: Fallible + ?Sized });
@@ -39,16 +47,27 @@ fn derive_serialize_impl(
         where_clause: input.generics.where_clause.clone(),
     };
 
-    let default_rkyv_path = parse_quote! { ::rkyv };
-    let rkyv_path = attributes.rkyv_path.as_ref().unwrap_or(&default_rkyv_path);
     let with_ty = make_with_ty(rkyv_path);
     let with_cast = make_with_cast(rkyv_path);
+    let try_serialize = |field: Expr, type_name: String, field_name: String| {
+        if attributes.trace.is_some() {
+            quote! {
+                Serialize::<__S>::serialize(#field, serializer).map_err(|e| {
+                    <__S as #rkyv_path::ser::TraceSerializer>::trace_field(e, #type_name, #field_name)
+                })?
+            }
+        } else {
+            quote! { Serialize::<__S>::serialize(#field, serializer)? }
+        }
+    };
 
     let name = &input.ident;
     let (impl_generics, _, _) = impl_input_generics.split_for_impl();
     let (_, ty_generics, where_clause) = input.generics.split_for_impl();
     let where_clause = where_clause.unwrap();
 
+    let type_name = strip_raw(name);
+
     let resolver = attributes.resolver.as_ref().map_or_else(
         || Ident::new(&format!("{}Resolver", strip_raw(name)), name.span()),
         |value| value.clone(),
@@ -61,6 +80,7 @@ fn derive_serialize_impl(
                 for field in fields
                     .named
                     .iter()
+                    .filter(|f| !is_skipped(f))
                     .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
                 {
                     let ty = with_ty(field)?;
@@ -69,10 +89,12 @@ fn derive_serialize_impl(
                         .push(parse_quote! { #ty: Serialize<__S> });
                 }
 
-                let resolver_values = fields.named.iter().map(|f| {
+                let resolver_values = fields.named.iter().filter(|f| !is_skipped(f)).map(|f| {
                     let name = &f.ident;
                     let field = with_cast(f, parse_quote! { &self.#name }).unwrap();
-                    quote! { #name: Serialize::<__S>::serialize(#field, serializer)? }
+                    let serialize =
+                        try_serialize(field, type_name.clone(), strip_raw(name.as_ref().unwrap()));
+                    quote! { #name: #serialize }
                 });
 
                 quote! {
@@ -102,7 +124,7 @@ fn derive_serialize_impl(
                 let resolver_values = fields.unnamed.iter().enumerate().map(|(i, f)| {
                     let index = Index::from(i);
                     let field = with_cast(f, parse_quote! { &self.#index }).unwrap();
-                    quote! { Serialize::<__S>::serialize(#field, serializer)? }
+                    try_serialize(field, type_name.clone(), i.to_string())
                 });
 
                 quote! {
@@ -161,6 +183,7 @@ fn derive_serialize_impl(
 
             let serialize_arms = data.variants.iter().map(|v| {
                 let variant = &v.ident;
+                let variant_name = format!("{}::{}", type_name, strip_raw(variant));
                 match v.fields {
                     Fields::Named(ref fields) => {
                         let bindings = fields.named.iter().map(|f| {
@@ -170,9 +193,12 @@ fn derive_serialize_impl(
                         let fields = fields.named.iter().map(|f| {
                             let name = &f.ident;
                             let field = with_cast(f, parse_quote! { #name }).unwrap();
-                            quote! {
-                                #name: Serialize::<__S>::serialize(#field, serializer)?
-                            }
+                            let serialize = try_serialize(
+                                field,
+                                variant_name.clone(),
+                                strip_raw(name.as_ref().unwrap()),
+                            );
+                            quote! { #name: #serialize }
                         });
                         quote! {
                             Self::#variant { #(#bindings,)* } => #resolver::#variant {
@@ -188,9 +214,7 @@ fn derive_serialize_impl(
                         let fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
                             let binding = Ident::new(&format!("_{}", i), f.span());
                             let field = with_cast(f, parse_quote! { #binding }).unwrap();
-                            quote! {
-                                Serialize::<__S>::serialize(#field, serializer)?
-                            }
+                            try_serialize(field, variant_name.clone(), i.to_string())
                         });
                         quote! {
                             Self::#variant( #(#bindings,)* ) => #resolver::#variant(#(#fields,)*)
@@ -1,5 +1,8 @@
 use proc_macro2::Ident;
-use syn::{punctuated::Punctuated, Error, LitStr, Token, WhereClause, WherePredicate};
+use syn::{
+    punctuated::Punctuated, Attribute, Error, Field, LitStr, Meta, NestedMeta, Token, WhereClause,
+    WherePredicate,
+};
 
 pub fn add_bounds(bounds: &LitStr, where_clause: &mut WhereClause) -> Result<(), Error> {
     let clauses = bounds.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
@@ -16,3 +19,74 @@ pub fn strip_raw(ident: &Ident) -> String {
         .map(ToString::to_string)
         .unwrap_or(as_string)
 }
+
+/// Parses the arguments of an `#[archive(...)]` attribute on a field, returning an error if any
+/// of them isn't a field argument.
+pub fn archive_field_args(attr: &Attribute) -> Result<Vec<NestedMeta>, Error> {
+    let list = match attr.parse_meta()? {
+        Meta::List(list) => list,
+        meta => return Err(Error::new_spanned(meta, "expected #[archive(...)]")),
+    };
+    for nested in list.nested.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::Path(path))
+                if path.is_ident("skip") || path.is_ident("lazy") => {}
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("with") => {}
+            _ => {
+                return Err(Error::new_spanned(
+                    nested,
+                    "unrecognized archive field argument, expected one of `skip`, `lazy`, or \
+                    `with = \"...\"`",
+                ))
+            }
+        }
+    }
+    Ok(list.nested.into_iter().collect())
+}
+
+/// Checks the `#[archive(...)]` attributes on each of the given fields.
+pub fn check_archive_field_args<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> Result<(), Error> {
+    for field in fields {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("archive"))
+        {
+            archive_field_args(attr)?;
+        }
+    }
+    Ok(())
+}
+
+// `#[archive(skip)]` isn't lowered to `#[with(Skip)]` because it removes the field from the
+// archived type entirely instead of archiving it as `()`.
+fn skip_attr(field: &Field) -> Option<&Attribute> {
+    field.attrs.iter().find(|attr| {
+        attr.path.is_ident("archive")
+            && matches!(
+                attr.parse_meta(),
+                Ok(Meta::List(list)) if list.nested.iter().any(|nested| matches!(
+                    nested,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip")
+                ))
+            )
+    })
+}
+
+pub fn is_skipped(field: &Field) -> bool {
+    skip_attr(field).is_some()
+}
+
+pub fn forbid_skipped<'a>(fields: impl IntoIterator<Item = &'a Field>) -> Result<(), Error> {
+    for field in fields {
+        if let Some(attr) = skip_attr(field) {
+            return Err(Error::new_spanned(
+                attr,
+                "archive(skip) may only be used on fields of structs with named fields",
+            ));
+        }
+    }
+    Ok(())
+}
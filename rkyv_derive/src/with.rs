@@ -1,19 +1,44 @@
-use syn::{parse_quote, punctuated::Punctuated, token::Comma, Error, Expr, Field, Path, Type};
+use crate::util::archive_field_args;
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Error, Expr, Field, Lit, Meta, NestedMeta,
+    Path, Type,
+};
 
 #[inline]
-pub fn with<B, F: FnMut(B, &Type) -> B>(field: &Field, init: B, f: F) -> Result<B, Error> {
-    let fields = field
-        .attrs
-        .iter()
-        .filter_map(|attr| {
-            if attr.path.is_ident("with") {
-                Some(attr.parse_args_with(Punctuated::<Type, Comma>::parse_separated_nonempty))
-            } else {
-                None
+pub fn with<B, F: FnMut(B, &Type) -> B>(
+    field: &Field,
+    rkyv_path: &Path,
+    init: B,
+    f: F,
+) -> Result<B, Error> {
+    let mut wrappers = Vec::new();
+    for attr in field.attrs.iter() {
+        if attr.path.is_ident("with") {
+            wrappers
+                .extend(attr.parse_args_with(Punctuated::<Type, Comma>::parse_separated_nonempty)?);
+        } else if attr.path.is_ident("archive") {
+            for nested in archive_field_args(attr)? {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("with") =>
+                    {
+                        if let Lit::Str(ref lit_str) = name_value.lit {
+                            wrappers.push(lit_str.parse::<Type>()?);
+                        } else {
+                            return Err(Error::new_spanned(name_value, "with must be a string"));
+                        }
+                    }
+                    // `#[archive(lazy)]` is shorthand for `#[with(Lazy)]`
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("lazy") => {
+                        wrappers.push(parse_quote! { #rkyv_path::with::Lazy });
+                    }
+                    // `#[archive(skip)]` is handled by the derives
+                    _ => (),
+                }
             }
-        })
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(fields.iter().flatten().rev().fold(init, f))
+        }
+    }
+    Ok(wrappers.iter().rev().fold(init, f))
 }
 
 #[inline]
@@ -21,6 +46,7 @@ pub fn make_with_ty(rkyv_path: &Path) -> impl '_ + Fn(&Field) -> Result<Type, Er
     move |field| {
         with(
             field,
+            rkyv_path,
             field.ty.clone(),
             |ty, wrapper| parse_quote! { #rkyv_path::with::With<#ty, #wrapper> },
         )
@@ -32,6 +58,7 @@ pub fn make_with_cast(rkyv_path: &Path) -> impl '_ + Fn(&Field, Expr) -> Result<
     move |field, expr| {
         with(
             field,
+            rkyv_path,
             expr,
             |expr, wrapper| parse_quote! { #rkyv_path::with::With::<_, #wrapper>::cast(#expr) },
         )
@@ -39,6 +66,10 @@ pub fn make_with_cast(rkyv_path: &Path) -> impl '_ + Fn(&Field, Expr) -> Result<
 }
 
 #[inline]
-pub fn with_inner(field: &Field, expr: Expr) -> Result<Expr, Error> {
-    with(field, expr, |expr, _| parse_quote! { #expr.into_inner() })
+pub fn make_with_inner(rkyv_path: &Path) -> impl '_ + Fn(&Field, Expr) -> Result<Expr, Error> {
+    move |field, expr| {
+        with(field, rkyv_path, expr, |expr, _| {
+            parse_quote! { #expr.into_inner() }
+        })
+    }
 }
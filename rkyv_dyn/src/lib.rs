@@ -36,8 +36,9 @@ use ptr_meta::{DynMetadata, Pointee};
 use rkyv::with::{Atomic, With};
 use rkyv::{
     from_archived,
+    rc::ArchivedRc,
     ser::{ScratchSpace, Serializer},
-    to_archived, Archived, Fallible, Serialize,
+    to_archived, ArchivePointee, Archived, Fallible, Serialize,
 };
 pub use rkyv_dyn_derive::archive_dyn;
 use rkyv_typename::TypeName;
@@ -308,6 +309,28 @@ pub trait DeserializeDyn<T: Pointee + ?Sized> {
     ) -> Result<T::Metadata, DynError>;
 }
 
+/// Downcasting for archived shared pointers to trait objects.
+pub trait DowncastArchivedRc {
+    /// Gets a reference to the shared value if it has the concrete archived type `U`.
+    ///
+    /// Returns `None` if the shared value has some other type.
+    fn downcast_ref<U: TypeName>(&self) -> Option<&U>;
+}
+
+impl<T, F> DowncastArchivedRc for ArchivedRc<T, F>
+where
+    T: ArchivePointee<ArchivedMetadata = ArchivedDynMetadata<T>> + TypeName + ?Sized,
+{
+    #[inline]
+    fn downcast_ref<U: TypeName>(&self) -> Option<&U> {
+        if self.metadata().is::<U>() {
+            Some(unsafe { &*(self.get() as *const T).cast::<U>() })
+        } else {
+            None
+        }
+    }
+}
+
 /// The archived version of `DynMetadata`.
 #[cfg_attr(feature = "strict", repr(C))]
 pub struct ArchivedDynMetadata<T: ?Sized> {
@@ -337,6 +360,12 @@ impl<T: TypeName + ?Sized> ArchivedDynMetadata<T> {
         ptr::addr_of_mut!((*out).cached_vtable).write(to_archived!(0u64));
     }
 
+    /// Returns whether the archived trait object has the concrete archived type `U`.
+    #[inline]
+    pub fn is<U: TypeName>(&self) -> bool {
+        from_archived!(self.type_id) == hash_type::<U>()
+    }
+
     fn lookup_vtable(&self) -> usize {
         IMPL_REGISTRY
             .get::<T>(from_archived!(self.type_id))
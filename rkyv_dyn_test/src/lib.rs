@@ -413,4 +413,80 @@ mod tests {
         value.as_mut().get_pin_mut().set_value(64);
         assert_eq!(value.value(), 64);
     }
+
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn downcast_archived_rc() {
+        use rkyv_dyn::{archive_dyn, DowncastArchivedRc};
+        use rkyv_typename::TypeName;
+        use std::rc::Rc;
+
+        #[archive_dyn]
+        trait TestTrait {
+            fn value(&self) -> i32;
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(TypeName))]
+        struct IntStruct(i32);
+
+        #[archive_dyn]
+        impl TestTrait for IntStruct {
+            fn value(&self) -> i32 {
+                self.0
+            }
+        }
+
+        impl TestTrait for Archived<IntStruct> {
+            fn value(&self) -> i32 {
+                rkyv::from_archived!(self.0)
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(TypeName))]
+        struct StringStruct(String);
+
+        #[archive_dyn]
+        impl TestTrait for StringStruct {
+            fn value(&self) -> i32 {
+                self.0.len() as i32
+            }
+        }
+
+        impl TestTrait for Archived<StringStruct> {
+            fn value(&self) -> i32 {
+                self.0.len() as i32
+            }
+        }
+
+        let value: Vec<Rc<dyn SerializeTestTrait>> = vec![
+            Rc::new(IntStruct(42)),
+            Rc::new(StringStruct("hello world".to_string())),
+        ];
+
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value =
+            unsafe { archived_root::<Vec<Rc<dyn SerializeTestTrait>>>(buf.as_ref()) };
+        assert_eq!(archived_value[0].value(), 42);
+        assert_eq!(archived_value[1].value(), 11);
+
+        let int = archived_value[0]
+            .downcast_ref::<Archived<IntStruct>>()
+            .unwrap();
+        assert_eq!(int.0, 42);
+        assert!(archived_value[0]
+            .downcast_ref::<Archived<StringStruct>>()
+            .is_none());
+
+        let string = archived_value[1]
+            .downcast_ref::<Archived<StringStruct>>()
+            .unwrap();
+        assert_eq!(string.0, "hello world");
+        assert!(archived_value[1]
+            .downcast_ref::<Archived<IntStruct>>()
+            .is_none());
+    }
 }
@@ -40,6 +40,8 @@ mod tests {
         test_archive(&1234567890u32);
         test_archive(&12345678901234567890u64);
         test_archive(&123456789012345678901234567890123456789u128);
+        test_archive(&'x');
+        test_archive(&'\u{1f980}');
         #[cfg(not(any(feature = "strict", feature = "archive_le", feature = "archive_be")))]
         test_archive(&(24, true, 16f32));
         test_archive(&[1, 2, 3, 4, 5, 6]);
@@ -74,6 +76,20 @@ mod tests {
         test_archive_ref::<str>("");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_arrays() {
+        let mut long = [0u8; 128];
+        for (i, x) in long.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+
+        test_archive::<[i32; 0]>(&[]);
+        test_archive(&[42i32]);
+        test_archive(&[0x1234u16; 33]);
+        test_archive(&long);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_nonzero() {
@@ -116,6 +132,283 @@ mod tests {
         test_archive_ref::<[MyZST]>(&[MyZST, MyZST, MyZST, MyZST]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_markers() {
+        use core::marker::{PhantomData, PhantomPinned};
+        use rkyv::{Archive, Deserialize, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            value: i32,
+            ptr: PhantomData<*const u8>,
+            unsized_marker: PhantomData<str>,
+            pinned: PhantomPinned,
+        }
+
+        test_archive(&PhantomData::<*const u8>);
+        test_archive(&PhantomPinned);
+        test_archive(&Test {
+            value: 42,
+            ptr: PhantomData,
+            unsized_marker: PhantomData,
+            pinned: PhantomPinned,
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_bound() {
+        use core::ops::Bound;
+
+        test_archive(&Bound::Included(42i32));
+        test_archive(&Bound::Excluded(42i32));
+        test_archive(&Bound::<i32>::Unbounded);
+    }
+
+    #[test]
+    #[cfg(feature = "control_flow")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_control_flow() {
+        use core::ops::ControlFlow;
+
+        test_archive(&ControlFlow::<i32, u16>::Continue(12345u16));
+        test_archive(&ControlFlow::<i32, u16>::Break(-12345i32));
+        test_archive(&ControlFlow::<(), ()>::Continue(()));
+        test_archive(&ControlFlow::<(), ()>::Break(()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_wrapping() {
+        use core::num::Wrapping;
+        use rkyv::{
+            archived_root, from_archived, ser::Serializer, Archive, Deserialize, Serialize,
+        };
+
+        fn round_trip<T>(value: &T, check: impl FnOnce(&T::Archived)) -> T
+        where
+            T: Serialize<DefaultSerializer>,
+            T::Archived: Deserialize<T, DefaultDeserializer>,
+        {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            let len = serializer.pos();
+            let buffer = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<T>(&buffer[0..len]) };
+            check(archived);
+            archived
+                .deserialize(&mut DefaultDeserializer::default())
+                .unwrap()
+        }
+
+        let value = round_trip(&Wrapping(u32::MAX), |archived| {
+            assert_eq!(from_archived!(*archived), u32::MAX);
+        });
+        assert_eq!(value + Wrapping(1), Wrapping(0));
+        let value = round_trip(&Wrapping(i8::MIN), |archived| {
+            assert_eq!(*archived, i8::MIN);
+        });
+        assert_eq!(value - Wrapping(1), Wrapping(i8::MAX));
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Checksum {
+            sum: Wrapping<u32>,
+            count: Wrapping<u8>,
+        }
+
+        let value = round_trip(
+            &Checksum {
+                sum: Wrapping(0xdead_beef),
+                count: Wrapping(u8::MAX),
+            },
+            |archived| {
+                assert_eq!(from_archived!(archived.sum), 0xdead_beef);
+                assert_eq!(archived.count, u8::MAX);
+            },
+        );
+        assert_eq!(value.sum, Wrapping(0xdead_beef));
+        assert_eq!(value.count, Wrapping(u8::MAX));
+    }
+
+    #[test]
+    #[cfg(feature = "saturating")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_saturating() {
+        use core::num::Saturating;
+        use rkyv::{
+            archived_root, from_archived, ser::Serializer, Archive, Deserialize, Serialize,
+        };
+
+        fn round_trip<T>(value: &T, check: impl FnOnce(&T::Archived)) -> T
+        where
+            T: Serialize<DefaultSerializer>,
+            T::Archived: Deserialize<T, DefaultDeserializer>,
+        {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            let len = serializer.pos();
+            let buffer = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<T>(&buffer[0..len]) };
+            check(archived);
+            archived
+                .deserialize(&mut DefaultDeserializer::default())
+                .unwrap()
+        }
+
+        let value = round_trip(&Saturating(u16::MAX), |archived| {
+            assert_eq!(from_archived!(*archived), u16::MAX);
+        });
+        assert_eq!(value + Saturating(1), Saturating(u16::MAX));
+        let value = round_trip(&Saturating(i64::MIN), |archived| {
+            assert_eq!(from_archived!(*archived), i64::MIN);
+        });
+        assert_eq!(value - Saturating(1), Saturating(i64::MIN));
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Counter {
+            count: Saturating<u8>,
+        }
+
+        let value = round_trip(
+            &Counter {
+                count: Saturating(u8::MAX),
+            },
+            |archived| assert_eq!(archived.count, u8::MAX),
+        );
+        assert_eq!(value.count, Saturating(u8::MAX));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn rel_ptr_i16_offsets() {
+        use core::mem::{size_of, MaybeUninit};
+        use rkyv::{
+            rel_ptr::{OffsetError, RelPtr},
+            Archived,
+        };
+
+        type RelPtrI16 = RelPtr<u32, Archived<i16>>;
+
+        assert_eq!(size_of::<RelPtrI16>(), 2);
+
+        // Offsets that fit
+        let mut out = MaybeUninit::<RelPtrI16>::uninit();
+        unsafe {
+            RelPtrI16::try_emplace(0x100, 0x100 + i16::MAX as usize, out.as_mut_ptr()).unwrap();
+            assert_eq!(out.assume_init_ref().offset(), i16::MAX as isize);
+
+            RelPtrI16::try_emplace(0x8000, 0, out.as_mut_ptr()).unwrap();
+            assert_eq!(out.assume_init_ref().offset(), i16::MIN as isize);
+        }
+
+        // Offsets that overflow
+        unsafe {
+            assert_eq!(
+                RelPtrI16::try_emplace(0, 0x8000, out.as_mut_ptr()),
+                Err(OffsetError::ExceedsStorageRange),
+            );
+            assert_eq!(
+                RelPtrI16::try_emplace(0x8001, 0, out.as_mut_ptr()),
+                Err(OffsetError::ExceedsStorageRange),
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(not(feature = "strict"))]
+    fn archive_long_tuples() {
+        use rkyv::{archived_root, from_archived, ser::Serializer, Deserialize};
+
+        // Tuples this long don't implement `PartialEq` or `Debug`, so they can't use `test_archive`
+        type Tuple13 = (
+            u8,
+            i16,
+            u32,
+            i64,
+            bool,
+            char,
+            f32,
+            f64,
+            u8,
+            u16,
+            (),
+            i32,
+            u64,
+        );
+        let value: Tuple13 = (1, 2, 3, 4, true, 'x', 7.5, 8.25, 9, 10, (), 12, 13);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let len = serializer.pos();
+        let buffer = serializer.into_serializer().into_inner();
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Tuple13>(&buffer[0..len]).unwrap();
+        let archived = unsafe { archived_root::<Tuple13>(&buffer[0..len]) };
+        assert_eq!(archived.0, 1);
+        assert_eq!(from_archived!(archived.1), 2);
+        assert_eq!(from_archived!(archived.2), 3);
+        assert_eq!(from_archived!(archived.3), 4);
+        assert!(archived.4);
+        assert_eq!(from_archived!(archived.5), 'x');
+        assert_eq!(from_archived!(archived.6), 7.5);
+        assert_eq!(from_archived!(archived.7), 8.25);
+        assert_eq!(archived.8, 9);
+        assert_eq!(from_archived!(archived.9), 10);
+        assert_eq!(from_archived!(archived.11), 12);
+        assert_eq!(from_archived!(archived.12), 13);
+
+        let deserialized: Tuple13 = archived
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        let (a, b, c, d, e, f, g, h, i, j, (), l, m) = deserialized;
+        assert_eq!((a, b, c, d, e, f), (1, 2, 3, 4, true, 'x'));
+        assert_eq!((g, h, i, j, l, m), (7.5, 8.25, 9, 10, 12, 13));
+
+        type Tuple16 = (
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+            u32,
+        );
+        let value: Tuple16 = (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let len = serializer.pos();
+        let buffer = serializer.into_serializer().into_inner();
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Tuple16>(&buffer[0..len]).unwrap();
+        let archived = unsafe { archived_root::<Tuple16>(&buffer[0..len]) };
+        assert_eq!(from_archived!(archived.0), 0);
+        assert_eq!(from_archived!(archived.12), 12);
+        assert_eq!(from_archived!(archived.15), 15);
+
+        let deserialized: Tuple16 = archived
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        let (a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p) = deserialized;
+        assert_eq!(
+            [a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p],
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     #[allow(non_camel_case_types)]
@@ -139,4 +432,56 @@ mod tests {
         test_archive::<r#virtual>(&r#virtual { r#virtual: 42 });
         test_archive::<r#try>(&r#try::r#try { r#try: 42 });
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(all(
+        target_endian = "little",
+        feature = "size_32",
+        not(any(
+            feature = "archive_be",
+            feature = "archive_le",
+            feature = "hash_map_buckets",
+            feature = "hash_map_insertion_order",
+            feature = "hash_map_tombstones",
+            feature = "strict",
+        ))
+    ))]
+    fn read_static_hash_map() {
+        use core::mem::size_of;
+        use rkyv::collections::ArchivedHashMap;
+
+        #[repr(C, align(16))]
+        struct Aligned<T>(T);
+
+        // An archived `HashMap<u32, u32>` of `{1: 10, 2: 20, 3: 30, 5: 50, 8: 80}`
+        static HASH_MAP_ARCHIVE: Aligned<[u8; 72]> = Aligned([
+            0, 0, 0, 128, 2, 0, 0, 128, 255, 255, 255, 255, 2, 0, 0, 0, 255, 255, 255, 255, 2, 0,
+            0, 0, 20, 0, 0, 0, 1, 0, 0, 0, 10, 0, 0, 0, 5, 0, 0, 0, 50, 0, 0, 0, 3, 0, 0, 0, 30, 0,
+            0, 0, 8, 0, 0, 0, 80, 0, 0, 0, 5, 0, 0, 0, 192, 255, 255, 255, 208, 255, 255, 255,
+        ]);
+
+        // Without `std` there's no `HashMap` to look up the root with, so locate it directly
+        let bytes = &HASH_MAP_ARCHIVE.0;
+        let pos = bytes.len() - size_of::<ArchivedHashMap<u32, u32>>();
+        let archived = unsafe { &*bytes.as_ptr().add(pos).cast::<ArchivedHashMap<u32, u32>>() };
+
+        assert_eq!(archived.len(), 5);
+        assert!(!archived.is_empty());
+        assert_eq!(archived.get(&1), Some(&10));
+        assert_eq!(archived.get(&8), Some(&80));
+        assert_eq!(archived.get(&4), None);
+        assert!(archived.contains_key(&5));
+        assert!(!archived.contains_key(&13));
+        assert_eq!(archived[&3], 30);
+
+        let mut sum = 0;
+        for (key, value) in archived.iter() {
+            assert_eq!(*value, *key * 10);
+            sum += *key;
+        }
+        assert_eq!(sum, 19);
+        assert_eq!(archived.keys().count(), 5);
+        assert_eq!(archived.values().copied().max(), Some(80));
+    }
 }
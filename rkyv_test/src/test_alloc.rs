@@ -5,7 +5,7 @@ mod tests {
     use rkyv::{
         archived_root, archived_root_mut,
         ser::{
-            serializers::{AlignedSerializer, BufferSerializer},
+            serializers::{AlignedSerializer, AllocScratch, BufferSerializer, CompositeSerializer},
             Serializer,
         },
         AlignedBytes, AlignedVec, Archive, Archived, Deserialize, Fallible, Infallible, Serialize,
@@ -45,6 +45,52 @@ mod tests {
         test_archive_container(&vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_boxed_unsized() {
+        use rkyv::{boxed::ArchivedBox, RelPtr};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            name: Box<str>,
+            empty: Box<str>,
+            values: Box<[u32]>,
+            no_values: Box<[u32]>,
+        }
+
+        let value = Test {
+            name: "hello world".into(),
+            empty: "".into(),
+            values: vec![1, 2, 3, 4].into_boxed_slice(),
+            no_values: Vec::new().into_boxed_slice(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        // Boxed slices archive as boxes, without the capacity of a string or vec
+        let name: &ArchivedBox<str> = &archived.name;
+        let values: &ArchivedBox<[Archived<u32>]> = &archived.values;
+        assert_eq!(&**name, "hello world");
+        assert_eq!(&**values, &[1, 2, 3, 4]);
+        assert_eq!(
+            core::mem::size_of::<ArchivedBox<str>>(),
+            core::mem::size_of::<RelPtr<str>>(),
+        );
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        let name: Box<str> = deserialized.name;
+        let empty: Box<str> = deserialized.empty;
+        let values: Box<[u32]> = deserialized.values;
+        let no_values: Box<[u32]> = deserialized.no_values;
+        assert_eq!(&*name, "hello world");
+        assert_eq!(&*empty, "");
+        assert_eq!(&*values, &[1, 2, 3, 4]);
+        assert!(no_values.is_empty());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_option() {
@@ -80,6 +126,380 @@ mod tests {
         };
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_string_inline() {
+        use rkyv::string::{repr::INLINE_CAPACITY, ArchivedString};
+
+        fn is_inline(archived: &ArchivedString) -> bool {
+            let start = archived as *const ArchivedString as usize;
+            let end = start + core::mem::size_of::<ArchivedString>();
+            let ptr = archived.as_str().as_ptr() as usize;
+            start <= ptr && ptr < end
+        }
+
+        for len in [
+            0,
+            1,
+            3,
+            INLINE_CAPACITY - 1,
+            INLINE_CAPACITY,
+            INLINE_CAPACITY + 1,
+            100,
+        ] {
+            let value = "x".repeat(len);
+
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<String>(buf.as_ref()) };
+
+            assert_eq!(archived, &value);
+            assert_eq!(archived.len(), len);
+            if len <= INLINE_CAPACITY {
+                assert!(is_inline(archived), "length {} should be inline", len);
+                assert_eq!(buf.len(), core::mem::size_of::<ArchivedString>());
+            } else {
+                assert!(!is_inline(archived), "length {} should be out of line", len);
+                assert!(buf.len() >= core::mem::size_of::<ArchivedString>() + len);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_string_display_and_compare() {
+        use core::cmp::Ordering;
+
+        let value = "hello world".to_string();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<String>(buf.as_ref()) };
+
+        assert_eq!(archived.to_string(), "hello world");
+
+        assert!(*archived == *"hello world");
+        assert!(*"hello world" == *archived);
+        assert!(*archived == "hello world");
+        assert!("hello world" == *archived);
+        assert!(*archived == value);
+        assert!(value == *archived);
+        assert!(*archived != "goodbye");
+
+        assert_eq!(archived.partial_cmp("hello"), Some(Ordering::Greater));
+        assert_eq!("hello".partial_cmp(archived), Some(Ordering::Less));
+        assert!(*archived < "world");
+        assert!("world" > *archived);
+        assert_eq!(archived.partial_cmp(&value), Some(Ordering::Equal));
+        let smaller = "a".to_string();
+        assert!(smaller < *archived);
+        assert!(*archived > smaller);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_binary_search() {
+        let value = vec![10u64, 20, 30, 40, 50];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u64>>(buf.as_ref()) };
+        let a = |x: u64| -> Archived<u64> { rkyv::to_archived!(x) };
+
+        // Found
+        assert_eq!(archived.binary_search(&a(10)), Ok(0));
+        assert_eq!(archived.binary_search(&a(30)), Ok(2));
+        assert_eq!(archived.binary_search(&a(50)), Ok(4));
+        assert_eq!(archived.binary_search_by(|x| x.cmp(&a(40))), Ok(3));
+        assert_eq!(
+            archived.binary_search_by_key(&20, |&x| rkyv::from_archived!(x)),
+            Ok(1)
+        );
+
+        // Not found
+        assert_eq!(archived.binary_search(&a(5)), Err(0));
+        assert_eq!(archived.binary_search(&a(35)), Err(3));
+        assert_eq!(archived.binary_search(&a(60)), Err(5));
+        assert_eq!(archived.binary_search_by(|x| x.cmp(&a(15))), Err(1));
+        assert_eq!(
+            archived.binary_search_by_key(&45, |&x| rkyv::from_archived!(x)),
+            Err(4)
+        );
+
+        // Empty
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Vec::<u64>::new()).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u64>>(buf.as_ref()) };
+        assert_eq!(archived.binary_search(&a(10)), Err(0));
+        assert_eq!(archived.binary_search_by(|x| x.cmp(&a(10))), Err(0));
+        assert_eq!(
+            archived.binary_search_by_key(&10, |&x| rkyv::from_archived!(x)),
+            Err(0)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_chunks_windows() {
+        let value = (0..10).collect::<Vec<u32>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+
+        assert_eq!(archived.chunks(1).count(), 10);
+        assert_eq!(archived.chunks(3).count(), 4);
+        assert_eq!(archived.chunks(10).count(), 1);
+        assert_eq!(archived.chunks(20).count(), 1);
+        for (archived_chunk, chunk) in archived.chunks(3).zip(value.chunks(3)) {
+            assert_eq!(archived_chunk, chunk);
+        }
+        assert_eq!(archived.chunks(3).last().unwrap(), &[9]);
+
+        assert_eq!(archived.windows(1).count(), 10);
+        assert_eq!(archived.windows(3).count(), 8);
+        assert_eq!(archived.windows(10).count(), 1);
+        assert_eq!(archived.windows(11).count(), 0);
+        for (i, window) in archived.windows(3).enumerate() {
+            assert_eq!(window, &value[i..i + 3]);
+        }
+        for pair in archived.windows(3).collect::<Vec<_>>().windows(2) {
+            assert_eq!(pair[0][1..], pair[1][..2]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn archive_vec_chunks_zero() {
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&vec![1u32, 2, 3]).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+        let _ = archived.chunks(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn archive_vec_windows_zero() {
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&vec![1u32, 2, 3]).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+        let _ = archived.windows(0);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_deserialize_element() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            id: u32,
+            name: String,
+            values: Vec<u64>,
+        }
+
+        let value = (0..1000)
+            .map(|i| Test {
+                id: i,
+                name: i.to_string(),
+                values: (0..u64::from(i % 8)).collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Test>>(buf.as_ref()) };
+
+        for &index in &[0, 5, 999] {
+            let element: Test = archived
+                .deserialize_element(index, &mut Infallible)
+                .unwrap()
+                .unwrap();
+            assert_eq!(element, value[index]);
+        }
+
+        let out_of_range: Option<Test> =
+            archived.deserialize_element(1000, &mut Infallible).unwrap();
+        assert!(out_of_range.is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_serialize_from_iter() {
+        use rkyv::{
+            ser::ScratchSpace,
+            vec::{ArchivedVec, VecResolver},
+        };
+
+        fn row(i: usize) -> String {
+            "row ".repeat(i % 5) + &i.to_string()
+        }
+
+        // Produces rows one at a time, like a database cursor
+        struct Cursor {
+            next: usize,
+            len: usize,
+        }
+
+        impl Iterator for Cursor {
+            type Item = String;
+
+            fn next(&mut self) -> Option<String> {
+                if self.next < self.len {
+                    self.next += 1;
+                    Some(row(self.next - 1))
+                } else {
+                    None
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let remaining = self.len - self.next;
+                (remaining, Some(remaining))
+            }
+        }
+
+        impl ExactSizeIterator for Cursor {}
+
+        struct Rows(usize);
+
+        impl Archive for Rows {
+            type Archived = ArchivedVec<Archived<String>>;
+            type Resolver = VecResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedVec::resolve_from_len(self.0, pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Rows {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                ArchivedVec::serialize_from_iter::<String, _, _, _>(
+                    Cursor {
+                        next: 0,
+                        len: self.0,
+                    },
+                    serializer,
+                )
+            }
+        }
+
+        for &len in &[0, 1, 100] {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&Rows(len)).unwrap();
+            let lazy_buf = serializer.into_serializer().into_inner();
+
+            let value = (0..len).map(row).collect::<Vec<_>>();
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let vec_buf = serializer.into_serializer().into_inner();
+
+            assert_eq!(lazy_buf.as_slice(), vec_buf.as_slice());
+            let archived = unsafe { archived_root::<Rows>(lazy_buf.as_ref()) };
+            assert_eq!(archived.as_slice(), value.as_slice());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_deserialize_into_slice() {
+        use core::{cell::Cell, mem::MaybeUninit};
+        use rkyv::vec::DeserializeIntoSliceError;
+
+        let value = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<String>>(buf.as_ref()) };
+
+        // Exact fit
+        let mut out: [MaybeUninit<String>; 3] = [
+            MaybeUninit::uninit(),
+            MaybeUninit::uninit(),
+            MaybeUninit::uninit(),
+        ];
+        let count = archived
+            .deserialize_into_slice(&mut out, &mut Infallible)
+            .unwrap();
+        assert_eq!(count, 3);
+        for (i, element) in out.iter_mut().enumerate() {
+            let element = unsafe { element.as_ptr().read() };
+            assert_eq!(element, value[i]);
+        }
+
+        // Too small
+        let mut out: [MaybeUninit<String>; 2] = [MaybeUninit::uninit(), MaybeUninit::uninit()];
+        let result = archived.deserialize_into_slice(&mut out, &mut Infallible);
+        assert!(matches!(
+            result,
+            Err(DeserializeIntoSliceError::SliceTooSmall {
+                len: 3,
+                capacity: 2
+            })
+        ));
+
+        // Partial failure drops the elements that were already written
+        #[derive(Archive, Serialize)]
+        struct Tracked(u32);
+
+        struct Dropped<'a>(&'a Cell<usize>);
+
+        impl Drop for Dropped<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        #[derive(Debug)]
+        struct Failed;
+
+        struct FailAt<'a> {
+            value: u32,
+            drops: &'a Cell<usize>,
+        }
+
+        impl Fallible for FailAt<'_> {
+            type Error = Failed;
+        }
+
+        impl<'a> Deserialize<Dropped<'a>, FailAt<'a>> for ArchivedTracked {
+            fn deserialize(&self, deserializer: &mut FailAt<'a>) -> Result<Dropped<'a>, Failed> {
+                if rkyv::from_archived!(self.0) == deserializer.value {
+                    Err(Failed)
+                } else {
+                    Ok(Dropped(deserializer.drops))
+                }
+            }
+        }
+
+        let value = (0..5).map(Tracked).collect::<Vec<_>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Tracked>>(buf.as_ref()) };
+
+        let drops = Cell::new(0);
+        let mut deserializer = FailAt {
+            value: 3,
+            drops: &drops,
+        };
+        let mut out: [MaybeUninit<Dropped>; 8] = unsafe { MaybeUninit::uninit().assume_init() };
+        let result = archived.deserialize_into_slice(&mut out, &mut deserializer);
+        assert!(matches!(
+            result,
+            Err(DeserializeIntoSliceError::Deserialize(Failed))
+        ));
+        assert_eq!(drops.get(), 3);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_result() {
@@ -95,6 +515,26 @@ mod tests {
         test_archive::<Result<(), _>>(&Err("hello world".to_string()));
         test_archive::<Result<(), _>>(&Err(vec![1, 2, 3, 4]));
         test_archive::<Result<(), _>>(&Err(Box::new(vec![1, 2, 3, 4])));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Result::<String, u32>::Ok("hello world".to_string()))
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Result<String, u32>>(buf.as_ref()) };
+        assert!(archived.is_ok());
+        assert_eq!(archived.ok().unwrap(), "hello world");
+        assert!(archived.err().is_none());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Result::<String, u32>::Err(42))
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Result<String, u32>>(buf.as_ref()) };
+        assert!(!archived.is_ok());
+        assert!(archived.ok().is_none());
+        assert_eq!(archived.err(), Some(&rkyv::to_archived!(42u32)));
     }
 
     #[cfg(all(feature = "std", feature = "validation"))]
@@ -575,6 +1015,113 @@ mod tests {
         assert_eq!(value.b[1], "WORLD");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn vec_sort_unstable() {
+        let value = vec![5u32, 3, 9, 1, 3, 7, 0];
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived = unsafe { archived_root_mut::<Vec<u32>>(Pin::new(buf.as_mut())) };
+
+        archived.as_mut().sort_unstable();
+        assert_eq!(archived.as_slice(), &[0, 1, 3, 3, 5, 7, 9]);
+
+        archived.as_mut().sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(archived.as_slice(), &[9, 7, 5, 3, 3, 1, 0]);
+
+        let deserialized: Vec<u32> = (*archived).deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, vec![9, 7, 5, 3, 3, 1, 0]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn vec_split_at() {
+        let value = (0..10).collect::<Vec<u32>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived = unsafe { archived_root_mut::<Vec<u32>>(Pin::new(buf.as_mut())) };
+
+        for mid in 0..=value.len() {
+            let (left, right) = archived.split_at(mid);
+            assert_eq!(left.len(), mid);
+            assert_eq!(right.len(), value.len() - mid);
+            let whole = left.iter().chain(right.iter()).copied().collect::<Vec<_>>();
+            assert_eq!(whole, value);
+        }
+
+        let (mut left, mut right) = archived.as_mut().split_at_pin_mut(4);
+        left[0] = rkyv::to_archived!(40u32);
+        right[0] = rkyv::to_archived!(60u32);
+        assert_eq!(archived.as_slice(), &[40, 1, 2, 3, 60, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[should_panic]
+    fn vec_split_at_out_of_range() {
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&vec![1u32, 2, 3]).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+        let _ = archived.split_at(4);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn string_mutable_refs() {
+        #[derive(Archive, Serialize)]
+        struct Test {
+            inline: String,
+            out_of_line: String,
+        }
+
+        impl ArchivedTest {
+            fn inline(self: Pin<&mut Self>) -> Pin<&mut Archived<String>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.inline) }
+            }
+
+            fn out_of_line(self: Pin<&mut Self>) -> Pin<&mut Archived<String>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.out_of_line) }
+            }
+        }
+
+        let value = Test {
+            inline: "hello".to_string(),
+            out_of_line: "a string that is too long to be stored inline".to_string(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived = unsafe { archived_root_mut::<Test>(Pin::new(buf.as_mut())) };
+
+        archived
+            .as_mut()
+            .inline()
+            .as_mut_str()
+            .make_ascii_uppercase();
+        archived
+            .as_mut()
+            .out_of_line()
+            .as_mut_str()
+            .make_ascii_uppercase();
+        assert_eq!(archived.inline, "HELLO");
+        assert_eq!(archived.inline.as_bytes(), b"HELLO");
+        assert_eq!(
+            archived.out_of_line.as_bytes(),
+            b"A STRING THAT IS TOO LONG TO BE STORED INLINE"
+        );
+
+        unsafe {
+            archived.as_mut().inline().as_mut_bytes()[0] = b'J';
+        }
+        assert_eq!(archived.inline, "JELLO");
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn enum_mutable_ref() {
@@ -743,6 +1290,54 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_ranges() {
+        use core::ops::{Range, RangeFrom, RangeInclusive, RangeTo};
+
+        test_archive(&(10u64..20));
+        test_archive(&(10u64..=20));
+        test_archive(&(10u64..));
+        test_archive(&(..20u64));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&(10u64..20)).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Range<u64>>(buf.as_ref()) };
+        assert_eq!(archived.start, 10);
+        assert_eq!(archived.end, 20);
+        assert!(archived.contains(&rkyv::to_archived!(10u64)));
+        assert!(!archived.contains(&rkyv::to_archived!(20u64)));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&(10u64..)).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<RangeFrom<u64>>(buf.as_ref()) };
+        assert_eq!(archived.start, 10);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&(..20u64)).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<RangeTo<u64>>(buf.as_ref()) };
+        assert_eq!(archived.end, 20);
+
+        // Exhausted inclusive ranges lose their exhausted state when archived
+        let mut exhausted = 10u64..=10;
+        assert_eq!(exhausted.next(), Some(10));
+        assert!(exhausted.is_empty());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&exhausted).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<RangeInclusive<u64>>(buf.as_ref()) };
+        assert_eq!(archived.start, 10);
+        assert_eq!(archived.end, 10);
+        assert!(!archived.is_empty());
+
+        let deserialized: RangeInclusive<u64> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, 10..=10);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_atomic() {
@@ -774,17 +1369,41 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_shared_ptr() {
-        #[derive(Debug, Eq, PartialEq, Archive, Deserialize, Serialize)]
-        #[archive(compare(PartialEq))]
-        #[archive_attr(derive(Debug))]
-        struct Test {
-            a: Rc<u32>,
-            b: Rc<u32>,
-        }
+    fn archive_atomic_u64() {
+        use core::sync::atomic::{AtomicU64, Ordering};
 
-        impl ArchivedTest {
-            fn a(self: Pin<&mut Self>) -> Pin<&mut Archived<Rc<u32>>> {
+        let value = AtomicU64::new(0);
+        value.store(0x0123_4567_89ab_cdef, Ordering::SeqCst);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<AtomicU64>(buf.as_ref()) };
+        assert_eq!(*archived, 0x0123_4567_89ab_cdef);
+
+        // Stores to the original aren't reflected in the archive
+        value.store(0, Ordering::SeqCst);
+        assert_eq!(*archived, 0x0123_4567_89ab_cdef);
+
+        let deserialized: AtomicU64 = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.load(Ordering::SeqCst), 0x0123_4567_89ab_cdef);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_shared_ptr() {
+        use rkyv::rc::ArchivedRc;
+
+        #[derive(Debug, Eq, PartialEq, Archive, Deserialize, Serialize)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            a: Rc<u32>,
+            b: Rc<u32>,
+        }
+
+        impl ArchivedTest {
+            fn a(self: Pin<&mut Self>) -> Pin<&mut Archived<Rc<u32>>> {
                 unsafe { self.map_unchecked_mut(|s| &mut s.a) }
             }
 
@@ -805,6 +1424,7 @@ mod tests {
 
         let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
         assert_eq!(archived, &value);
+        assert!(ArchivedRc::ptr_eq(&archived.a, &archived.b));
 
         let mut mutable_archived =
             unsafe { archived_root_mut::<Test>(Pin::new_unchecked(buf.as_mut())) };
@@ -852,6 +1472,21 @@ mod tests {
         assert_eq!(Rc::strong_count(&deserialized.b), 2);
         assert_eq!(Rc::weak_count(&deserialized.a), 0);
         assert_eq!(Rc::weak_count(&deserialized.b), 0);
+
+        // Equal values in different allocations are not shared
+        let value = Test {
+            a: Rc::new(10),
+            b: Rc::new(10),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+        assert!(!ArchivedRc::ptr_eq(&archived.a, &archived.b));
+        assert!(ArchivedRc::ptr_eq(&archived.a, &archived.a));
     }
 
     #[test]
@@ -875,6 +1510,420 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_optional_shared_ptr() {
+        use rkyv::rc::ArchivedRc;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            value: Rc<String>,
+            maybe: Option<Rc<String>>,
+            missing: Option<Rc<String>>,
+        }
+
+        const PAYLOAD: &str = "a payload that should only be archived once";
+
+        let shared = Rc::new(PAYLOAD.to_string());
+        let value = Test {
+            value: shared.clone(),
+            maybe: Some(shared),
+            missing: None,
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let copies = buf
+            .windows(PAYLOAD.len())
+            .filter(|window| *window == PAYLOAD.as_bytes())
+            .count();
+        assert_eq!(copies, 1);
+
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        let maybe = archived.maybe.as_ref().unwrap();
+        assert!(ArchivedRc::ptr_eq(&archived.value, maybe));
+        assert!(archived.missing.is_none());
+
+        let mut deserializer = DefaultDeserializer::default();
+        let deserialized: Test = archived.deserialize(&mut deserializer).unwrap();
+        assert_eq!(*deserialized.value, PAYLOAD);
+        assert!(Rc::ptr_eq(
+            &deserialized.value,
+            deserialized.maybe.as_ref().unwrap()
+        ));
+        assert!(deserialized.missing.is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_shared_ptr_topology() {
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Graph {
+            nodes: Vec<Rc<String>>,
+            first: Rc<String>,
+            weak: Weak<String>,
+        }
+
+        let a = Rc::new("a".to_string());
+        let b = Rc::new("b".to_string());
+        let c = Rc::new("c".to_string());
+        let value = Graph {
+            nodes: vec![a.clone(), b.clone(), a.clone(), c.clone(), b.clone()],
+            first: a.clone(),
+            weak: Rc::downgrade(&c),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Graph>(buf.as_ref()) };
+
+        let mut deserializer = DefaultDeserializer::default();
+        let deserialized: Graph = archived.deserialize(&mut deserializer).unwrap();
+        core::mem::drop(deserializer);
+
+        let nodes = &deserialized.nodes;
+        assert!(Rc::ptr_eq(&nodes[0], &nodes[2]));
+        assert!(Rc::ptr_eq(&nodes[0], &deserialized.first));
+        assert!(Rc::ptr_eq(&nodes[1], &nodes[4]));
+        assert!(Rc::ptr_eq(&nodes[3], &deserialized.weak.upgrade().unwrap()));
+        assert!(!Rc::ptr_eq(&nodes[0], &nodes[1]));
+        assert!(!Rc::ptr_eq(&nodes[0], &nodes[3]));
+        assert!(!Rc::ptr_eq(&nodes[1], &nodes[3]));
+
+        // The counts match the original sharing
+        assert_eq!(Rc::strong_count(&nodes[0]), 3);
+        assert_eq!(Rc::strong_count(&nodes[1]), 2);
+        assert_eq!(Rc::strong_count(&nodes[3]), 1);
+        assert_eq!(Rc::weak_count(&nodes[3]), 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serializer_current_alignment() {
+        use core::mem::align_of;
+
+        #[derive(Archive, Serialize)]
+        struct Small {
+            a: u8,
+            b: u16,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Large {
+            a: u8,
+            b: u64,
+        }
+
+        fn check<S: Serializer>(serializer: &mut S)
+        where
+            S::Error: core::fmt::Debug,
+            Small: Serialize<S>,
+            Large: Serialize<S>,
+        {
+            let small = Small { a: 1, b: 2 };
+            let large = Large { a: 3, b: 4 };
+
+            assert_eq!(serializer.current_alignment(), 1);
+            serializer.serialize_value(&small).unwrap();
+            assert_eq!(serializer.current_alignment(), align_of::<ArchivedSmall>());
+            serializer.serialize_value(&large).unwrap();
+            assert_eq!(serializer.current_alignment(), align_of::<ArchivedLarge>());
+            serializer.serialize_value(&small).unwrap();
+            assert_eq!(serializer.current_alignment(), align_of::<ArchivedLarge>());
+        }
+
+        check(&mut AlignedSerializer::new(AlignedVec::new()));
+        check(&mut BufferSerializer::new(AlignedBytes([0u8; 256])));
+        check(&mut DefaultSerializer::default());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn to_bytes_with_header() {
+        use core::mem::align_of;
+        use rkyv::header::{check_alignment, to_bytes_with_header, ArchiveHeader, CheckError};
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: u8,
+            b: u64,
+            c: Vec<u32>,
+        }
+
+        let value = Test {
+            a: 1,
+            b: 2,
+            c: vec![3, 4, 5],
+        };
+        let bytes = to_bytes_with_header::<_, 256>(&value).unwrap();
+
+        let header = ArchiveHeader::read(&bytes).unwrap();
+        assert_eq!(header.len(), bytes.len());
+        assert_eq!(header.align(), align_of::<ArchivedTest>());
+
+        let checked = check_alignment(&bytes).unwrap();
+        assert_eq!(checked.len(), header.len());
+        assert_eq!(checked.align(), header.align());
+
+        let archived = unsafe { archived_root::<Test>(&bytes) };
+        assert_eq!(archived.a, 1);
+        assert_eq!(archived.b, 2);
+        assert_eq!(archived.c.as_slice(), &[3, 4, 5]);
+
+        let mut shifted = AlignedVec::new();
+        shifted.push(0);
+        shifted.extend_from_slice(&bytes);
+        match check_alignment(&shifted[1..]) {
+            Err(CheckError::Unaligned { align, .. }) => assert_eq!(align, header.align()),
+            result => panic!("expected unaligned error, got {:?}", result),
+        }
+
+        match check_alignment(&bytes[..bytes.len() - 1]) {
+            Err(CheckError::LengthMismatch { expected, actual }) => {
+                assert_eq!(expected, bytes.len());
+                assert_eq!(actual, bytes.len() - 1);
+            }
+            result => panic!("expected length mismatch, got {:?}", result),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn limit_serializer() {
+        use rkyv::ser::serializers::{LimitSerializer, LimitSerializerError};
+
+        let value = vec![
+            "hello".to_string(),
+            "world".to_string(),
+            "a string long enough to be stored out of line".to_string(),
+        ];
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let len = serializer.pos();
+
+        let mut serializer = LimitSerializer::new(DefaultSerializer::default(), len);
+        serializer.serialize_value(&value).unwrap();
+        assert_eq!(serializer.pos(), len);
+        let buf = serializer.into_inner().into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<String>>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+
+        let mut serializer = LimitSerializer::new(DefaultSerializer::default(), len - 1);
+        match serializer.serialize_value(&value) {
+            Err(LimitSerializerError::SizeLimitExceeded { limit, .. }) => {
+                assert_eq!(limit, len - 1)
+            }
+            _ => panic!("expected the size limit to be exceeded"),
+        }
+        assert!(serializer.pos() < len);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn limit_serializer_inner_error() {
+        use rkyv::ser::serializers::{
+            BufferSerializerError, LimitSerializer, LimitSerializerError,
+        };
+
+        type Error = LimitSerializerError<BufferSerializerError>;
+
+        // Errors from the inner serializer convert into errors of the limit serializer
+        fn write_header(
+            serializer: &mut LimitSerializer<BufferSerializer<AlignedBytes<16>>>,
+        ) -> Result<usize, Error> {
+            serializer.write(b"HEAD")?;
+            let pos = serializer.serialize_value(&0u32)?;
+            Ok(pos)
+        }
+
+        fn write_header_unlimited(
+            serializer: &mut BufferSerializer<AlignedBytes<16>>,
+        ) -> Result<usize, Error> {
+            serializer.write(b"HEAD")?;
+            let pos = serializer.serialize_value(&0u32)?;
+            Ok(pos)
+        }
+
+        let mut serializer =
+            LimitSerializer::new(BufferSerializer::new(AlignedBytes([0u8; 16])), 1024);
+        assert_eq!(write_header(&mut serializer).unwrap(), 4);
+        serializer.write(&[0; 8]).unwrap();
+        match serializer.write(b"!") {
+            Err(LimitSerializerError::Inner(BufferSerializerError::Overflow { .. })) => (),
+            result => panic!("expected the inner buffer to overflow, got {:?}", result),
+        }
+
+        let mut serializer = BufferSerializer::new(AlignedBytes([0u8; 16]));
+        serializer.write(&[0; 16]).unwrap();
+        match write_header_unlimited(&mut serializer) {
+            Err(LimitSerializerError::Inner(BufferSerializerError::Overflow { .. })) => (),
+            result => panic!("expected the inner buffer to overflow, got {:?}", result),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_shared_slice() {
+        let value = Rc::<[u32]>::from(vec![1, 2, 3, 4]);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Rc<[u32]>>(buf.as_ref()) };
+
+        assert_eq!(archived.len(), 4);
+        assert!(!archived.is_empty());
+        assert_eq!(archived.as_slice(), &[1, 2, 3, 4]);
+
+        let value = Rc::<[u32]>::from(Vec::new());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Rc<[u32]>>(buf.as_ref()) };
+
+        assert_eq!(archived.len(), 0);
+        assert!(archived.is_empty());
+        assert!(archived.as_slice().is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialize_shared_ptr_by_value() {
+        use rkyv::{
+            de::deserializers::SharedDeserializeMap, ser::serializers::SharedValueSerializeMap,
+            with::ShareByValue,
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(ShareByValue)]
+            a: Rc<str>,
+            #[with(ShareByValue)]
+            b: Rc<str>,
+            c: Rc<str>,
+        }
+
+        let value = Test {
+            a: Rc::from("interned string"),
+            b: Rc::from("interned string"),
+            c: Rc::from("interned string"),
+        };
+        assert!(!Rc::ptr_eq(&value.a, &value.b));
+
+        let mut serializer = CompositeSerializer::new(
+            AlignedSerializer::new(AlignedVec::new()),
+            AllocScratch::default(),
+            SharedValueSerializeMap::new(),
+        );
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let needle = b"interned string";
+        let copies = buf
+            .windows(needle.len())
+            .filter(|window| window == needle)
+            .count();
+        assert_eq!(copies, 2);
+
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert!(Archived::<Rc<str>>::ptr_eq(&archived.a, &archived.b));
+        assert!(!Archived::<Rc<str>>::ptr_eq(&archived.a, &archived.c));
+
+        let deserialized: Test = archived
+            .deserialize(&mut SharedDeserializeMap::new())
+            .unwrap();
+        assert!(Rc::ptr_eq(&deserialized.a, &deserialized.b));
+        assert_eq!(&*deserialized.c, "interned string");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialize_cyclic_shared_ptr() {
+        use core::cell::UnsafeCell;
+        use rkyv::{
+            ser::{
+                serializers::{
+                    CompositeSerializerError, SharedSerializeMap, SharedSerializeMapError,
+                },
+                SharedSerializeRegistry,
+            },
+            with::Unsafe,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(bound(serialize = "__S: Serializer + SharedSerializeRegistry"))]
+        struct Node {
+            value: u32,
+            #[with(Unsafe)]
+            #[omit_bounds]
+            this: UnsafeCell<Weak<Node>>,
+        }
+
+        let node = Rc::new(Node {
+            value: 42,
+            this: UnsafeCell::new(Weak::new()),
+        });
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&node).unwrap();
+
+        // Point the node back at itself like `Rc::new_cyclic` would
+        unsafe {
+            *node.this.get() = Rc::downgrade(&node);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        match serializer.serialize_value(&node) {
+            Err(CompositeSerializerError::SharedError(
+                SharedSerializeMapError::CyclicSharedPointer(ptr),
+            )) => assert_eq!(ptr, Rc::as_ptr(&node).cast::<u8>()),
+            _ => panic!("expected a cyclic shared pointer error"),
+        }
+
+        // The failed pointer is no longer pending, so the same serializer can serialize it once the
+        // cycle is broken
+        unsafe {
+            *node.this.get() = Weak::new();
+        }
+        serializer.serialize_value(&node).unwrap();
+
+        // Pending pointers are also forgotten when the registry is reset
+        let ptr = Rc::as_ptr(&node).cast::<u8>();
+        let mut shared = SharedSerializeMap::new();
+        shared.begin_shared_ptr(ptr).unwrap();
+        shared.reset();
+        shared.begin_shared_ptr(ptr).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_rc_try_get() {
+        use core::mem::size_of;
+        use rkyv::RelPtr;
+
+        let value = Rc::new(42u32);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Rc<u32>>(buf.as_ref()) };
+        assert_eq!(archived.try_get(), Some(&rkyv::to_archived!(42u32)));
+
+        // Zero the offset of the root relative pointer to simulate a corrupted archive
+        let len = buf.len();
+        for byte in buf[len - size_of::<RelPtr<u32>>()..].iter_mut() {
+            *byte = 0;
+        }
+
+        let archived = unsafe { archived_root::<Rc<u32>>(buf.as_ref()) };
+        assert!(archived.try_get().is_none());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_weak_ptr() {
@@ -966,6 +2015,54 @@ mod tests {
         assert_eq!(Weak::weak_count(&deserialized.b), 1);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn rc_counts_after_round_trip() {
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            strong: Vec<Rc<u32>>,
+            weak: Vec<Weak<u32>>,
+        }
+
+        let shared = Rc::new(10);
+        let value = Test {
+            strong: vec![shared.clone(), shared.clone()],
+            weak: vec![
+                Rc::downgrade(&shared),
+                Rc::downgrade(&shared),
+                Rc::downgrade(&shared),
+            ],
+        };
+        // Handles that aren't serialized aren't counted after deserializing
+        let _extra_weak = Rc::downgrade(&shared);
+        assert_eq!(Rc::strong_count(&shared), 3);
+        assert_eq!(Rc::weak_count(&shared), 4);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        let mut deserializer = DefaultDeserializer::default();
+        let deserialized: Test = archived.deserialize(&mut deserializer).unwrap();
+        core::mem::drop(deserializer);
+
+        let rc = &deserialized.strong[0];
+        assert!(Rc::ptr_eq(rc, &deserialized.strong[1]));
+        assert!(deserialized
+            .weak
+            .iter()
+            .all(|weak| Weak::ptr_eq(weak, &Rc::downgrade(rc))));
+        assert_eq!(Rc::strong_count(rc), 2);
+        assert_eq!(Rc::weak_count(rc), 3);
+
+        drop(deserialized.strong);
+        assert!(deserialized
+            .weak
+            .iter()
+            .all(|weak| weak.upgrade().is_none()));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn derive_attributes() {
@@ -1517,26 +2614,89 @@ mod tests {
                 panic!("expected variant B");
             };
         }
-    }
 
-    #[test]
-    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_atomic() {
-        use core::sync::atomic::{AtomicU32, Ordering};
-        use rkyv::with::Atomic;
+        #[test]
+        #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+        fn with_archive_attribute() {
+            use rkyv::with::AsBox;
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct Test {
-            #[with(Atomic)]
-            value: AtomicU32,
-        }
+            #[derive(Debug, PartialEq)]
+            struct Uuid(u128);
 
-        let value = Test {
-            value: AtomicU32::new(42),
-        };
-        let mut serializer = AlignedSerializer::new(AlignedVec::new());
-        serializer.serialize_value(&value).unwrap();
-        let mut result = serializer.into_inner();
+            struct UuidAsBytes;
+
+            impl ArchiveWith<Uuid> for UuidAsBytes {
+                type Archived = [u8; 16];
+                type Resolver = ();
+
+                unsafe fn resolve_with(value: &Uuid, _: usize, _: (), out: *mut Self::Archived) {
+                    out.write(value.0.to_be_bytes());
+                }
+            }
+
+            impl<S: Fallible + ?Sized> SerializeWith<Uuid, S> for UuidAsBytes {
+                fn serialize_with(_: &Uuid, _: &mut S) -> Result<(), S::Error> {
+                    Ok(())
+                }
+            }
+
+            impl<D: Fallible + ?Sized> DeserializeWith<[u8; 16], Uuid, D> for UuidAsBytes {
+                fn deserialize_with(value: &[u8; 16], _: &mut D) -> Result<Uuid, D::Error> {
+                    Ok(Uuid(u128::from_be_bytes(*value)))
+                }
+            }
+
+            #[derive(Archive, Serialize, Deserialize)]
+            struct Test {
+                #[archive(with = "UuidAsBytes")]
+                id: Uuid,
+                #[with(AsBox)]
+                #[archive(with = "ConvertToString")]
+                value: i32,
+            }
+
+            let value = Test {
+                id: Uuid(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef),
+                value: 10,
+            };
+            let mut serializer = AlignedSerializer::new(AlignedVec::new());
+            serializer.serialize_value(&value).unwrap();
+            let result = serializer.into_inner();
+            let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+            assert_eq!(
+                archived.id,
+                [
+                    0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89,
+                    0xab, 0xcd, 0xef
+                ]
+            );
+            assert_eq!(archived.value.get(), "10");
+
+            let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized.id, value.id);
+            assert_eq!(deserialized.value, 10);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_atomic() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+        use rkyv::with::Atomic;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Atomic)]
+            value: AtomicU32,
+        }
+
+        let value = Test {
+            value: AtomicU32::new(42),
+        };
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let mut result = serializer.into_inner();
         // NOTE: with(Atomic) is only sound if the backing memory is mutable, use with caution!
         let archived = unsafe { archived_root_mut::<Test>(Pin::new(result.as_mut_slice())) };
 
@@ -1605,6 +2765,44 @@ mod tests {
         assert_eq!(archived.value.as_ref(), "hello world");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_cow() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test<'a> {
+            a: Cow<'a, str>,
+            b: Cow<'a, [u32]>,
+        }
+
+        fn to_bytes(value: &Test<'_>) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let borrowed = Test {
+            a: Cow::Borrowed("hello world"),
+            b: Cow::Borrowed(&[1, 2, 3, 4]),
+        };
+        let owned = Test {
+            a: Cow::Owned("hello world".to_string()),
+            b: Cow::Owned(vec![1, 2, 3, 4]),
+        };
+
+        test_archive(&borrowed.a);
+        test_archive(&borrowed.b);
+        test_archive(&owned.a);
+        test_archive(&owned.b);
+        assert_eq!(to_bytes(&borrowed).as_slice(), to_bytes(&owned).as_slice());
+
+        let buf = to_bytes(&borrowed);
+        let archived = unsafe { archived_root::<Test>(buf.as_slice()) };
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert!(matches!(deserialized.a, Cow::Owned(_)));
+        assert!(matches!(deserialized.b, Cow::Owned(_)));
+        assert_eq!(deserialized, owned);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_as_owned() {
@@ -1751,6 +2949,226 @@ mod tests {
         assert!(size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>());
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_intern() {
+        use rkyv::{
+            de::deserializers::StringInternMap,
+            with::{Intern, Map},
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Document {
+            #[with(Intern)]
+            title: Rc<str>,
+            #[with(Map<Intern>)]
+            tags: Vec<Rc<str>>,
+        }
+
+        let tags = ["red", "green", "red", "blue", "green", "red"];
+        let value = Document {
+            title: "red".into(),
+            tags: tags.iter().map(|&tag| Rc::from(tag)).collect(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Document>(buf.as_ref()) };
+
+        assert_eq!(archived.title, "red");
+        assert_eq!(archived.tags.len(), tags.len());
+
+        let mut deserializer = StringInternMap::new();
+        let deserialized: Document = archived.deserialize(&mut deserializer).unwrap();
+
+        assert_eq!(deserializer.len(), 3);
+        assert_eq!(&*deserialized.title, "red");
+        for (tag, expected) in deserialized.tags.iter().zip(tags.iter()) {
+            assert_eq!(&**tag, *expected);
+        }
+        assert!(Rc::ptr_eq(&deserialized.title, &deserialized.tags[0]));
+        assert!(Rc::ptr_eq(&deserialized.tags[0], &deserialized.tags[2]));
+        assert!(Rc::ptr_eq(&deserialized.tags[0], &deserialized.tags[5]));
+        assert!(Rc::ptr_eq(&deserialized.tags[1], &deserialized.tags[4]));
+        assert!(!Rc::ptr_eq(&deserialized.tags[0], &deserialized.tags[1]));
+        assert!(!Rc::ptr_eq(&deserialized.tags[1], &deserialized.tags[3]));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_budgeted() {
+        use rkyv::{
+            de::deserializers::{AllocationLimitExceeded, AllocationLimiter},
+            with::Budgeted,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Message {
+            #[with(Budgeted)]
+            subject: String,
+            #[with(Budgeted)]
+            payload: Vec<u32>,
+        }
+
+        let value = Message {
+            subject: "hello world".to_string(),
+            payload: (0..1024).collect(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Message>(buf.as_ref()) };
+
+        let mut deserializer = AllocationLimiter::new(1024);
+        let result: Result<Message, _> = archived.deserialize(&mut deserializer);
+        match result {
+            Err(AllocationLimitExceeded { limit, requested }) => {
+                assert_eq!(limit, 1024);
+                assert_eq!(requested, 11 + 4 * 1024);
+            }
+            Ok(_) => panic!("deserialized a vector larger than the allocation limit"),
+        }
+
+        let mut deserializer = AllocationLimiter::new(1 << 20);
+        let deserialized: Message = archived.deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserialized, value);
+        assert_eq!(deserializer.allocated(), 11 + 4 * 1024);
+        assert_eq!(
+            deserializer.remaining(),
+            (1 << 20) - deserializer.allocated()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[allow(clippy::manual_div_ceil)]
+    fn with_packed_bools() {
+        use rkyv::with::PackedBools;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(PackedBools)]
+            features: Vec<bool>,
+        }
+
+        for len in [0, 1, 7, 8, 9, 13, 16, 67] {
+            let value = Test {
+                features: (0..len).map(|i| i % 3 == 0 || i % 5 == 0).collect(),
+            };
+
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let mut buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+            assert_eq!(archived.features.len(), len);
+            assert_eq!(archived.features.as_bytes().len(), (len + 7) / 8);
+            assert_eq!(archived.features, *value.features);
+            for (i, &b) in value.features.iter().enumerate() {
+                assert_eq!(archived.features.get(i), b);
+            }
+            assert!(!archived.features.get(len));
+
+            let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, value);
+
+            // Set the unused bits of the last byte, which should be ignored
+            if len % 8 != 0 {
+                let bytes = archived.features.as_bytes();
+                let last = bytes.as_ptr() as usize + bytes.len() - 1 - buf.as_ptr() as usize;
+                buf[last] |= 0xff << (len % 8);
+
+                let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+                assert_eq!(archived.features.iter().len(), len);
+                assert_eq!(archived.features, *value.features);
+                for i in len..(len + 7) / 8 * 8 {
+                    assert!(!archived.features.get(i));
+                }
+
+                let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+                assert_eq!(deserialized, value);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_bit_set() {
+        use rkyv::{collections::ArchivedBitSet, with::AsBitSet};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(AsBitSet)]
+            flags: u128,
+            #[with(AsBitSet)]
+            words: [u64; 3],
+        }
+
+        impl ArchivedTest {
+            fn words(self: Pin<&mut Self>) -> Pin<&mut ArchivedBitSet<3>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.words) }
+            }
+        }
+
+        let value = Test {
+            flags: 1 << 127 | 1 << 64 | 1 << 3 | 1,
+            words: [1 << 63, 0, 1 << 5 | 1 << 2],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived = unsafe { archived_root_mut::<Test>(Pin::new(buf.as_mut())) };
+
+        assert_eq!(archived.flags.capacity(), 128);
+        assert_eq!(archived.flags.count_ones(), 4);
+        assert!(archived.flags.get(0));
+        assert!(!archived.flags.get(1));
+        assert!(archived.flags.get(127));
+        assert!(!archived.flags.get(128));
+        assert!(archived.flags.iter_ones().eq([0, 3, 64, 127]));
+        assert!(archived.words.iter_ones().eq([63, 130, 133]));
+
+        archived.as_mut().words().set(63, false);
+        archived.as_mut().words().set(64, true);
+        archived.as_mut().words().set(191, true);
+        archived.as_mut().words().set(133, true);
+        assert_eq!(archived.words.count_ones(), 4);
+        assert!(archived.words.iter_ones().eq([64, 130, 133, 191]));
+
+        for i in [64, 130, 133, 191] {
+            archived.as_mut().words().set(i, false);
+        }
+        assert!(archived.words.is_empty());
+        assert_eq!(archived.words.iter_ones().next(), None);
+
+        archived.as_mut().words().set(100, true);
+        let deserialized: Test = (*archived).deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.flags, value.flags);
+        assert_eq!(deserialized.words, [0, 1 << 36, 0]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[should_panic]
+    fn with_as_bit_set_out_of_range() {
+        use rkyv::with::AsBitSet;
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            #[with(AsBitSet)]
+            words: [u64; 1],
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Test { words: [0] }).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root_mut::<Test>(Pin::new(buf.as_mut())) };
+        unsafe { archived.map_unchecked_mut(|s| &mut s.words) }.set(64, true);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_niche_nonzero() {
@@ -1958,6 +3376,78 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_binary_heap() {
+        #[cfg(not(feature = "std"))]
+        use alloc::collections::BinaryHeap;
+        #[cfg(feature = "std")]
+        use std::collections::BinaryHeap;
+
+        let value = [5, 1, 8, 3, 9, 2, 7, 4, 6, 0]
+            .iter()
+            .copied()
+            .collect::<BinaryHeap<i32>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<BinaryHeap<i32>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), value.len());
+        assert_eq!(archived.peek(), Some(&rkyv::to_archived!(9i32)));
+        assert!(archived.iter().eq(value.iter()));
+
+        let deserialized: BinaryHeap<i32> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.peek(), Some(&9));
+        assert_eq!(deserialized.into_sorted_vec(), value.into_sorted_vec());
+
+        let value = BinaryHeap::<i32>::new();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<BinaryHeap<i32>>(result.as_slice()) };
+        assert!(archived.is_empty());
+        assert!(archived.peek().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_deque() {
+        #[cfg(not(feature = "std"))]
+        use alloc::collections::VecDeque;
+        use rkyv::to_archived;
+        #[cfg(feature = "std")]
+        use std::collections::VecDeque;
+
+        // Push to both ends so that the elements wrap around the ring buffer
+        let mut value = VecDeque::<i32>::with_capacity(8);
+        for i in 0..4 {
+            value.push_back(i);
+            value.push_front(-i - 1);
+        }
+        value.pop_back();
+        value.push_back(10);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<VecDeque<i32>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), value.len());
+        assert_eq!(archived.front(), Some(&to_archived!(-4i32)));
+        assert_eq!(archived.back(), Some(&to_archived!(10i32)));
+        for (i, x) in value.iter().enumerate() {
+            assert_eq!(archived.get(i), Some(&to_archived!(*x)));
+            assert_eq!(&archived[i], x);
+        }
+        assert!(archived.get(value.len()).is_none());
+        assert_eq!(archived, &value);
+
+        let deserialized: VecDeque<i32> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_btree_map() {
@@ -2006,6 +3496,55 @@ mod tests {
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_map_next_prev() {
+        use rkyv::{from_archived, to_archived};
+
+        let value: BTreeMap<u32, u32> = BTreeMap::new();
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<u32, u32>>(result.as_slice()) };
+        assert!(archived.get_next(&to_archived!(0u32)).is_none());
+        assert!(archived.get_prev(&to_archived!(0u32)).is_none());
+
+        // Enough sparse keys to span several leaf nodes
+        let value = (1..=2_000)
+            .map(|i| (i * 10, i))
+            .collect::<BTreeMap<u32, u32>>();
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<u32, u32>>(result.as_slice()) };
+
+        let get_next = |k: u32| {
+            archived
+                .get_next(&to_archived!(k))
+                .map(|(k, v)| (from_archived!(*k), from_archived!(*v)))
+        };
+        let get_prev = |k: u32| {
+            archived
+                .get_prev(&to_archived!(k))
+                .map(|(k, v)| (from_archived!(*k), from_archived!(*v)))
+        };
+
+        for k in 0..=20_010 {
+            assert_eq!(get_next(k), value.range(k..).next().map(|(k, v)| (*k, *v)));
+            assert_eq!(
+                get_prev(k),
+                value.range(..=k).next_back().map(|(k, v)| (*k, *v))
+            );
+        }
+
+        assert_eq!(get_next(15).map(|(k, _)| k), Some(20));
+        assert_eq!(get_prev(15).map(|(k, _)| k), Some(10));
+        assert_eq!(get_next(20).map(|(k, _)| k), Some(20));
+        assert_eq!(get_prev(20).map(|(k, _)| k), Some(20));
+        assert!(get_prev(9).is_none());
+        assert!(get_next(20_001).is_none());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_btree_set() {
@@ -2033,6 +3572,31 @@ mod tests {
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_set_contains() {
+        // Enough sparse values to span several nodes
+        let value = (0..1_000u64).map(|i| i * 3).collect::<BTreeSet<_>>();
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeSet<u64>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), value.len());
+        for i in 0..3_000u64 {
+            assert_eq!(
+                archived.contains(&rkyv::to_archived!(i)),
+                value.contains(&i)
+            );
+        }
+        assert!(archived.iter().eq(value.iter()));
+        assert!(archived
+            .iter()
+            .zip(archived.iter().skip(1))
+            .all(|(a, b)| a < b));
+    }
+
     #[test]
     // This test is unfortunately too slow to run through miri
     #[cfg_attr(miri, ignore)]
@@ -2163,4 +3727,533 @@ mod tests {
         assert_eq!(tracker.max_allocations(), 1);
         assert_ne!(tracker.min_buffer_size(), 0);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_sorted_map() {
+        use rkyv::{
+            collections::sorted_map::{ArchivedSortedMap, SortedMapResolver},
+            ser::ScratchSpace,
+            string::ArchivedString,
+        };
+
+        // `u8` keys are archived as themselves regardless of the archive endianness
+        struct SortedMap(Vec<(u8, String)>);
+
+        impl Archive for SortedMap {
+            type Archived = ArchivedSortedMap<u8, ArchivedString>;
+            type Resolver = SortedMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedSortedMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for SortedMap {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedSortedMap::serialize_from_iter(
+                        self.0.iter().map(|(k, v)| (k, v)),
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        fn serialize(entries: &[(u8, &str)]) -> AlignedVec {
+            let value = SortedMap(entries.iter().map(|&(k, v)| (k, v.to_string())).collect());
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        // Empty
+        let buf = serialize(&[]);
+        let archived = unsafe { archived_root::<SortedMap>(buf.as_ref()) };
+        assert!(archived.is_empty());
+        assert_eq!(archived.get(&0), None);
+        assert_eq!(archived.first(), None);
+        assert_eq!(archived.last(), None);
+        assert_eq!(archived.range(..).count(), 0);
+        assert_eq!(archived.range(0..=u8::MAX).count(), 0);
+
+        // Single element
+        let buf = serialize(&[(5, "five")]);
+        let archived = unsafe { archived_root::<SortedMap>(buf.as_ref()) };
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived.get(&5).unwrap(), "five");
+        assert_eq!(archived.get(&4), None);
+        assert_eq!(archived.get(&6), None);
+        assert_eq!(archived.range(5..=5).count(), 1);
+        assert_eq!(archived.range(5..5).count(), 0);
+        assert_eq!(archived.range(..5).count(), 0);
+        assert_eq!(archived.range(6..).count(), 0);
+        assert_eq!(archived.range(..).count(), 1);
+
+        // Entries are serialized out of order
+        let buf = serialize(&[
+            (30, "thirty"),
+            (10, "ten"),
+            (50, "fifty"),
+            (20, "twenty"),
+            (40, "forty"),
+        ]);
+        let archived = unsafe { archived_root::<SortedMap>(buf.as_ref()) };
+        assert_eq!(archived.len(), 5);
+        assert!(archived
+            .keys()
+            .copied()
+            .eq([10, 20, 30, 40, 50].iter().copied()));
+        assert!(archived
+            .values()
+            .eq(["ten", "twenty", "thirty", "forty", "fifty"].iter()));
+        assert_eq!(archived.first().map(|(k, _)| *k), Some(10));
+        assert_eq!(archived.last().map(|(k, _)| *k), Some(50));
+        assert_eq!(archived.get_index(2).map(|(k, _)| *k), Some(30));
+
+        for &(key, value) in &[(10, "ten"), (30, "thirty"), (50, "fifty")] {
+            assert!(archived.contains_key(&key));
+            assert_eq!(archived.get(&key).unwrap(), value);
+            assert_eq!(&archived[&key], value);
+        }
+        for key in &[0, 15, 45, 60] {
+            assert!(!archived.contains_key(key));
+            assert_eq!(archived.get(key), None);
+        }
+
+        fn keys<'a, V: 'a>(iter: impl Iterator<Item = (&'a u8, &'a V)>) -> Vec<u8> {
+            iter.map(|(k, _)| *k).collect()
+        }
+        assert_eq!(keys(archived.range(20..40)), vec![20, 30]);
+        assert_eq!(keys(archived.range(20..=40)), vec![20, 30, 40]);
+        assert_eq!(keys(archived.range(15..45)), vec![20, 30, 40]);
+        assert_eq!(keys(archived.range(..30)), vec![10, 20]);
+        assert_eq!(keys(archived.range(30..)), vec![30, 40, 50]);
+        assert_eq!(keys(archived.range(..)), vec![10, 20, 30, 40, 50]);
+        assert_eq!(keys(archived.range(0..10)), Vec::<u8>::new());
+        assert_eq!(keys(archived.range(51..)), Vec::<u8>::new());
+        // Inverted ranges are empty instead of panicking
+        let (start, end) = (40, 20);
+        assert_eq!(keys(archived.range(start..end)), Vec::<u8>::new());
+        assert_eq!(keys(archived.range(20..40).rev()), vec![30, 20]);
+
+        use core::ops::Bound::{Excluded, Included};
+        assert_eq!(
+            keys(archived.range((Excluded(20), Included(40)))),
+            vec![30, 40]
+        );
+        assert_eq!(
+            keys(archived.range((Excluded(20), Excluded(30)))),
+            Vec::<u8>::new()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn record_offsets() {
+        use rkyv::ser::OffsetRecorder;
+
+        #[derive(Default)]
+        struct Offsets(Vec<(&'static str, usize, usize)>);
+
+        impl OffsetRecorder for Offsets {
+            fn record_offset(&mut self, type_name: &'static str, pos: usize, size: usize) {
+                self.0.push((type_name, pos, size));
+            }
+        }
+
+        #[derive(Default)]
+        struct RecordingSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+            offsets: Offsets,
+        }
+
+        impl Fallible for RecordingSerializer {
+            type Error = <AlignedSerializer<AlignedVec> as Fallible>::Error;
+        }
+
+        impl Serializer for RecordingSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes)
+            }
+
+            fn offset_recorder(&mut self) -> Option<&mut dyn OffsetRecorder> {
+                Some(&mut self.offsets)
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: u32,
+            b: Box<u64>,
+            c: Option<Box<u16>>,
+        }
+
+        let value = Test {
+            a: 1,
+            b: Box::new(2),
+            c: Some(Box::new(3)),
+        };
+
+        let mut serializer = RecordingSerializer::default();
+        let root_pos = serializer.serialize_value(&value).unwrap();
+        let len = serializer.pos();
+        let offsets = serializer.offsets.0;
+
+        assert_eq!(offsets.len(), 3);
+        assert_eq!(offsets[0].0, core::any::type_name::<Archived<u64>>());
+        assert_eq!(offsets[1].0, core::any::type_name::<Archived<u16>>());
+        for pair in offsets.windows(2) {
+            assert!(pair[0].1 + pair[0].2 <= pair[1].1);
+        }
+
+        let &(type_name, pos, size) = offsets.last().unwrap();
+        assert!(type_name.ends_with("ArchivedTest"));
+        assert_eq!(pos, root_pos);
+        assert_eq!(size, core::mem::size_of::<ArchivedTest>());
+        assert_eq!(pos + size, len);
+
+        // The default serializers don't record anything
+        let mut serializer = DefaultSerializer::default();
+        assert!(serializer.offset_recorder().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialized_size_matches() {
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: Rc<String>,
+            b: Rc<String>,
+            c: u8,
+            d: Vec<u64>,
+        }
+
+        let shared = Rc::new("a string that is shared between two fields".to_string());
+        let value = Test {
+            a: shared.clone(),
+            b: shared,
+            c: 42,
+            d: vec![1, 2, 3],
+        };
+
+        let size = rkyv::serialized_size::<_, 256>(&value).unwrap();
+        let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+        assert_eq!(size, bytes.len());
+
+        // The shared string is only counted once
+        let unshared = Test {
+            a: Rc::new(value.a.as_ref().clone()),
+            b: Rc::new(value.b.as_ref().clone()),
+            c: 42,
+            d: vec![1, 2, 3],
+        };
+        let unshared_size = rkyv::serialized_size::<_, 256>(&unshared).unwrap();
+        assert_eq!(
+            unshared_size,
+            rkyv::to_bytes::<_, 256>(&unshared).unwrap().len()
+        );
+        assert!(size < unshared_size);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn append_to_existing_archive() {
+        use rkyv::archived_value;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(Debug))]
+        struct Record {
+            id: u32,
+            message: String,
+        }
+
+        fn write_batch(
+            buf: AlignedVec,
+            records: &[Record],
+            index: &mut Vec<u32>,
+        ) -> (AlignedVec, usize) {
+            let len = buf.len();
+            let mut serializer = AlignedSerializer::from_existing(buf, len);
+            for record in records {
+                index.push(serializer.serialize_value(record).unwrap() as u32);
+            }
+            let mut serializer =
+                CompositeSerializer::new(serializer, AllocScratch::default(), Infallible);
+            let root = serializer.serialize_value(index).unwrap();
+            (serializer.into_serializer().into_inner(), root)
+        }
+
+        let record = |id: u32| Record {
+            id,
+            message: id.to_string(),
+        };
+
+        let mut index = Vec::new();
+        let (buf, first_root) = write_batch(AlignedVec::new(), &[record(0), record(1)], &mut index);
+        let first_len = buf.len();
+        let first_bytes = buf.as_slice().to_vec();
+
+        let (buf, second_root) = write_batch(buf, &[record(2), record(3), record(4)], &mut index);
+
+        // The first batch was not rewritten
+        assert_eq!(&buf[..first_len], first_bytes.as_slice());
+
+        let check = |root: usize, count: usize| {
+            let archived_index = unsafe { archived_value::<Vec<u32>>(buf.as_ref(), root) };
+            assert_eq!(archived_index.len(), count);
+            for (id, &pos) in archived_index.iter().enumerate() {
+                let pos = rkyv::from_archived!(pos) as usize;
+                let archived = unsafe { archived_value::<Record>(buf.as_ref(), pos) };
+                assert_eq!(archived.id, id as u32);
+                assert_eq!(archived.message, id.to_string());
+            }
+        };
+
+        check(first_root, 2);
+        check(second_root, 5);
+        assert_eq!(unsafe { archived_root::<Vec<u32>>(buf.as_ref()) }.len(), 5);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialize_trace_fields() {
+        use core::fmt;
+        use rkyv::ser::TraceSerializer;
+
+        #[derive(Debug)]
+        struct BudgetExceeded {
+            trace: Vec<(&'static str, &'static str)>,
+        }
+
+        impl fmt::Display for BudgetExceeded {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "serialization budget exceeded")?;
+                for (type_name, field_name) in self.trace.iter() {
+                    write!(f, " in {}.{}", type_name, field_name)?;
+                }
+                Ok(())
+            }
+        }
+
+        struct BudgetSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+            budget: usize,
+        }
+
+        impl Fallible for BudgetSerializer {
+            type Error = BudgetExceeded;
+        }
+
+        impl Serializer for BudgetSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                if self.inner.pos() + bytes.len() > self.budget {
+                    return Err(BudgetExceeded { trace: Vec::new() });
+                }
+                self.inner.write(bytes).unwrap();
+                Ok(())
+            }
+        }
+
+        impl TraceSerializer for BudgetSerializer {
+            fn trace_field(
+                mut error: Self::Error,
+                type_name: &'static str,
+                field_name: &'static str,
+            ) -> Self::Error {
+                error.trace.push((type_name, field_name));
+                error
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(trace)]
+        struct Inner {
+            name: String,
+            email: String,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(trace)]
+        struct Outer {
+            id: u32,
+            inner: Inner,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(trace)]
+        enum Message {
+            Ping,
+            Text(u32, String),
+        }
+
+        let value = Outer {
+            id: 1,
+            inner: Inner {
+                name: "a name that is too long to be inlined".to_string(),
+                email: "name@example.com".to_string(),
+            },
+        };
+
+        let mut serializer = BudgetSerializer {
+            inner: AlignedSerializer::new(AlignedVec::new()),
+            budget: 48,
+        };
+        let error = serializer.serialize_value(&value).unwrap_err();
+        assert_eq!(error.trace, [("Inner", "email"), ("Outer", "inner")]);
+        assert_eq!(
+            error.to_string(),
+            "serialization budget exceeded in Inner.email in Outer.inner"
+        );
+
+        let mut serializer = BudgetSerializer {
+            inner: AlignedSerializer::new(AlignedVec::new()),
+            budget: 0,
+        };
+        let error = serializer
+            .serialize_value(&Message::Text(1, "a message that won't fit".to_string()))
+            .unwrap_err();
+        assert_eq!(error.trace, [("Message::Text", "1")]);
+
+        // Errors that don't come from a field aren't traced
+        let mut serializer = BudgetSerializer {
+            inner: AlignedSerializer::new(AlignedVec::new()),
+            budget: 0,
+        };
+        let error = serializer.serialize_value(&Message::Ping).unwrap_err();
+        assert!(error.trace.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_skip_field() {
+        use core::cell::Cell;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            a: u32,
+            #[archive(skip)]
+            hash: Cell<Option<u64>>,
+            b: String,
+        }
+
+        assert_eq!(
+            core::mem::size_of::<ArchivedTest>(),
+            core::mem::size_of::<(Archived<u32>, Archived<String>)>(),
+        );
+
+        let value = Test {
+            a: 42,
+            hash: Cell::new(Some(0xdead_beef)),
+            b: "hello world".to_string(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+        assert_eq!(archived.b, "hello world");
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.a, 42);
+        assert_eq!(deserialized.hash.get(), None);
+        assert_eq!(deserialized.b, "hello world");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_versioned() {
+        use rkyv::version::{
+            archive_version, archived_versioned_root, serialize_versioned, ArchiveVersion,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(version = 1)]
+        struct ConfigV1 {
+            name: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(version = 2)]
+        struct ConfigV2 {
+            name: String,
+            retries: u32,
+        }
+
+        impl From<ConfigV1> for ConfigV2 {
+            fn from(value: ConfigV1) -> Self {
+                Self {
+                    name: value.name,
+                    retries: 3,
+                }
+            }
+        }
+
+        assert_eq!(ConfigV1::VERSION, 1);
+        assert_eq!(ConfigV2::VERSION, 2);
+
+        fn load(bytes: &[u8]) -> Option<ConfigV2> {
+            match archive_version(bytes)? {
+                1 => unsafe { archived_versioned_root::<ConfigV1>(bytes) }.map(|archived| {
+                    let v1: ConfigV1 = archived.deserialize(&mut Infallible).unwrap();
+                    v1.into()
+                }),
+                2 => unsafe { archived_versioned_root::<ConfigV2>(bytes) }
+                    .map(|archived| archived.deserialize(&mut Infallible).unwrap()),
+                _ => None,
+            }
+        }
+
+        let v1 = ConfigV1 {
+            name: "old config".to_string(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serialize_versioned(&mut serializer, &v1).unwrap();
+        let v1_bytes = serializer.into_serializer().into_inner();
+
+        let v2 = ConfigV2 {
+            name: "new config".to_string(),
+            retries: 5,
+        };
+        let mut serializer = DefaultSerializer::default();
+        serialize_versioned(&mut serializer, &v2).unwrap();
+        let v2_bytes = serializer.into_serializer().into_inner();
+
+        assert_eq!(archive_version(v1_bytes.as_ref()), Some(1));
+        assert_eq!(archive_version(v2_bytes.as_ref()), Some(2));
+        assert_eq!(archive_version(&[0; 2]), None);
+
+        assert!(unsafe { archived_versioned_root::<ConfigV2>(v1_bytes.as_ref()) }.is_none());
+        assert_eq!(
+            unsafe { archived_versioned_root::<ConfigV1>(v1_bytes.as_ref()) }
+                .unwrap()
+                .name,
+            "old config"
+        );
+
+        assert_eq!(
+            load(v1_bytes.as_ref()),
+            Some(ConfigV2 {
+                name: "old config".to_string(),
+                retries: 3,
+            })
+        );
+        assert_eq!(load(v2_bytes.as_ref()), Some(v2));
+    }
 }
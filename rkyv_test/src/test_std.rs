@@ -2,9 +2,9 @@
 mod tests {
     use crate::util::alloc::*;
     use rkyv::{
-        archived_root,
+        archived_root, from_archived,
         ser::{serializers::WriteSerializer, Serializer},
-        AlignedBytes, Archive, Deserialize, Serialize,
+        to_archived, AlignedBytes, Archive, Deserialize, Serialize,
     };
     use std::collections::{HashMap, HashSet};
 
@@ -27,6 +27,81 @@ mod tests {
             .expect_err("serialized to an undersized buffer must fail");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn write_serializer_matches_aligned_serializer() {
+        use rkyv::{ser::serializers::AlignedSerializer, AlignedVec};
+
+        #[derive(Archive, Serialize)]
+        struct Example {
+            a: u8,
+            b: String,
+            c: u64,
+            d: String,
+        }
+
+        let value = Example {
+            a: 1,
+            b: "hello".to_string(),
+            c: 42,
+            d: "a string long enough to be stored out of line".to_string(),
+        };
+
+        let mut write_serializer = WriteSerializer::new(Vec::new());
+        write_serializer.serialize_value(&value).unwrap();
+        let written = write_serializer.into_inner();
+
+        let mut aligned_serializer = AlignedSerializer::new(AlignedVec::new());
+        aligned_serializer.serialize_value(&value).unwrap();
+        let aligned = aligned_serializer.into_inner();
+
+        assert_eq!(written.as_slice(), aligned.as_slice());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn pooled_scratch() {
+        use rkyv::{
+            ser::serializers::{
+                AlignedSerializer, CompositeSerializer, PooledScratch, SharedSerializeMap,
+            },
+            AlignedVec,
+        };
+
+        fn serialize(
+            hash_map: &HashMap<String, String>,
+            scratch: PooledScratch,
+        ) -> (AlignedVec, PooledScratch) {
+            let mut serializer = CompositeSerializer::new(
+                AlignedSerializer::new(AlignedVec::new()),
+                scratch,
+                SharedSerializeMap::new(),
+            );
+            serializer.serialize_value(hash_map).unwrap();
+            let (serializer, scratch, _) = serializer.into_components();
+            (serializer.into_inner(), scratch)
+        }
+
+        let mut hash_map = HashMap::new();
+        for i in 0..100 {
+            hash_map.insert(format!("key {}", i), format!("value {}", i));
+        }
+
+        let (buf, scratch) = serialize(&hash_map, PooledScratch::new());
+        let allocation_count = scratch.allocation_count();
+        assert!(allocation_count > 0);
+        drop(buf);
+
+        let (buf, scratch) = serialize(&hash_map, scratch);
+        assert_eq!(scratch.allocation_count(), allocation_count);
+
+        let archived_value = unsafe { archived_root::<HashMap<String, String>>(buf.as_ref()) };
+        assert_eq!(archived_value.len(), hash_map.len());
+        for (key, value) in hash_map.iter() {
+            assert_eq!(&archived_value[key.as_str()], value);
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_hash_map() {
@@ -34,76 +109,1390 @@ mod tests {
         {
             test_archive(&HashMap::<i32, i32>::new());
 
-            let mut hash_map = HashMap::new();
-            hash_map.insert(1, 2);
-            hash_map.insert(3, 4);
-            hash_map.insert(5, 6);
-            hash_map.insert(7, 8);
+            let mut hash_map = HashMap::new();
+            hash_map.insert(1, 2);
+            hash_map.insert(3, 4);
+            hash_map.insert(5, 6);
+            hash_map.insert(7, 8);
+
+            test_archive(&hash_map);
+        }
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert("hello".to_string(), "world".to_string());
+        hash_map.insert("foo".to_string(), "bar".to_string());
+        hash_map.insert("baz".to_string(), "bat".to_string());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, String>>(buf.as_ref()) };
+
+        assert_eq!(archived_value.len(), hash_map.len());
+
+        for (key, value) in hash_map.iter() {
+            assert!(archived_value.contains_key(key.as_str()));
+            assert_eq!(&archived_value[key.as_str()], value);
+        }
+
+        for (key, value) in archived_value.iter() {
+            assert!(hash_map.contains_key(key.as_str()));
+            assert_eq!(&hash_map[key.as_str()], value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_hash_map_into_btree_map() {
+        use std::collections::BTreeMap;
+
+        let mut hash_map = HashMap::new();
+        for i in 0..64u32 {
+            hash_map.insert(format!("key {:02}", i), i * 3);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        let deserialized: BTreeMap<String, u32> = archived_value
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        let expected = hash_map.into_iter().collect::<BTreeMap<_, _>>();
+        assert_eq!(deserialized, expected);
+        assert!(deserialized
+            .keys()
+            .zip(deserialized.keys().skip(1))
+            .all(|(a, b)| a < b));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_get_borrowed_str() {
+        use std::borrow::Cow;
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert("hello".to_string(), 1u32);
+        hash_map.insert("world".to_string(), 2u32);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        let (one, two) = (to_archived!(1u32), to_archived!(2u32));
+
+        assert_eq!(archived_value.get("hello"), Some(&one));
+        assert!(archived_value.contains_key("world"));
+        assert!(archived_value.get("missing").is_none());
+
+        let owned = "world".to_string();
+        assert_eq!(archived_value.get(owned.as_str()), Some(&two));
+        let cow: Cow<'_, str> = Cow::Owned("hello".to_string());
+        assert_eq!(archived_value.get(&*cow), Some(&one));
+        let boxed: Box<str> = "world".into();
+        assert_eq!(archived_value.get(&*boxed), Some(&two));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_sorted_keys() {
+        // A simple xorshift generator so that the keys are inserted in a scrambled order
+        let mut state = 0x2545_f491u32;
+        let mut hash_map = HashMap::new();
+        while hash_map.len() < 200 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            hash_map.insert(state % 10_000, state.to_string());
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<u32, String>>(buf.as_ref()) };
+
+        let mut expected = hash_map.keys().copied().collect::<Vec<_>>();
+        expected.sort_unstable();
+
+        let sorted = archived_value.sorted_keys();
+        assert_eq!(sorted.len(), expected.len());
+        assert!(sorted.copied().eq(expected.iter().copied()));
+
+        let mut order = vec![0; archived_value.len() + 8];
+        let sorted = archived_value.sorted_keys_in(&mut order);
+        assert!(sorted.copied().eq(expected.iter().copied()));
+        for key in archived_value.sorted_keys_in(&mut order) {
+            assert_eq!(archived_value[key], hash_map[&from_archived!(*key)]);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the length of a hash index must match")]
+    fn hash_map_resolve_from_len_mismatched_len() {
+        use core::mem::MaybeUninit;
+        use rkyv::collections::ArchivedHashMap;
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert(1u32, 10u32);
+        hash_map.insert(2u32, 20u32);
+
+        let mut serializer = DefaultSerializer::default();
+        let resolver = hash_map.serialize(&mut serializer).unwrap();
+        let pos = serializer.align_for::<ArchivedHashMap<u32, u32>>().unwrap();
+
+        let mut out = MaybeUninit::<ArchivedHashMap<u32, u32>>::uninit();
+        unsafe {
+            ArchivedHashMap::resolve_from_len(hash_map.len() + 1, pos, resolver, out.as_mut_ptr());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_hash() {
+        use core::hash::{Hash, Hasher};
+        use std::collections::hash_map::DefaultHasher;
+
+        fn archive_and_hash(map: &HashMap<String, u32>) -> u64 {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(map).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+            let mut hasher = DefaultHasher::new();
+            archived_value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut forward = HashMap::new();
+        for i in 0..100u32 {
+            forward.insert(i.to_string(), i);
+        }
+        let mut backward = HashMap::with_capacity(1000);
+        for i in (0..100u32).rev() {
+            backward.insert(i.to_string(), i);
+        }
+        assert_eq!(archive_and_hash(&forward), archive_and_hash(&backward));
+
+        let mut different = forward.clone();
+        different.insert("0".to_string(), 100);
+        assert_ne!(archive_and_hash(&forward), archive_and_hash(&different));
+        different.remove("0");
+        assert_ne!(archive_and_hash(&forward), archive_and_hash(&different));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_get_or() {
+        const DEFAULT: u32 = 10;
+        let default = to_archived!(DEFAULT);
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert("retries".to_string(), 3u32);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        assert_eq!(
+            from_archived!(*archived_value.get_or("retries", &default)),
+            3
+        );
+        assert_eq!(
+            from_archived!(*archived_value.get_or("timeout", &default)),
+            DEFAULT
+        );
+
+        let mut called = false;
+        assert_eq!(
+            from_archived!(*archived_value.get_or_else("retries", || {
+                called = true;
+                &default
+            })),
+            3
+        );
+        assert!(!called);
+        assert_eq!(
+            from_archived!(*archived_value.get_or_else("timeout", || {
+                called = true;
+                &default
+            })),
+            DEFAULT
+        );
+        assert!(called);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[allow(deprecated)]
+    fn archive_hash_map_hasher() {
+        use std::collections::HashMap;
+
+        test_archive(&HashMap::<i8, i32, ahash::RandomState>::default());
+
+        let mut hash_map: HashMap<i8, _, ahash::RandomState> = HashMap::default();
+        hash_map.insert(1, 2);
+        hash_map.insert(3, 4);
+        hash_map.insert(5, 6);
+        hash_map.insert(7, 8);
+
+        test_archive(&hash_map);
+
+        let mut hash_map: HashMap<_, _, ahash::RandomState> = HashMap::default();
+        hash_map.insert("hello".to_string(), "world".to_string());
+        hash_map.insert("foo".to_string(), "bar".to_string());
+        hash_map.insert("baz".to_string(), "bat".to_string());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value =
+            unsafe { archived_root::<HashMap<String, String, ahash::RandomState>>(buf.as_ref()) };
+
+        assert_eq!(archived_value.len(), hash_map.len());
+
+        for (key, value) in hash_map.iter() {
+            assert!(archived_value.contains_key(key.as_str()));
+            assert_eq!(&archived_value[key.as_str()], value);
+        }
+
+        for (key, value) in archived_value.iter() {
+            assert!(hash_map.contains_key(key.as_str()));
+            assert_eq!(&hash_map[key.as_str()], value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_custom_hasher() {
+        use core::hash::Hasher;
+        use rkyv::{
+            collections::{hash_index::ArchiveHasher, hash_map::HashMapResolver, ArchivedHashMap},
+            ser::ScratchSpace,
+            Archived,
+        };
+
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        #[derive(Clone)]
+        struct FnvHasher(u64);
+
+        impl Hasher for FnvHasher {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for &byte in bytes {
+                    self.0 ^= byte as u64;
+                    self.0 = self.0.wrapping_mul(FNV_PRIME);
+                }
+            }
+        }
+
+        impl ArchiveHasher for FnvHasher {
+            fn make_hasher() -> Self {
+                FnvHasher(FNV_OFFSET_BASIS)
+            }
+        }
+
+        struct FnvMap(HashMap<String, u32>);
+
+        impl Archive for FnvMap {
+            type Archived = ArchivedHashMap<Archived<String>, Archived<u32>, FnvHasher>;
+            type Resolver = HashMapResolver<FnvHasher>;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for FnvMap {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe { ArchivedHashMap::serialize_from_iter(self.0.iter(), serializer) }
+            }
+        }
+
+        let value = FnvMap(
+            (0..100u32)
+                .map(|i| (format!("key {}", i), i))
+                .collect::<HashMap<_, _>>(),
+        );
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<FnvMap>(buf.as_ref()) };
+
+        assert_eq!(archived_value.hasher().finish(), FNV_OFFSET_BASIS);
+        assert_eq!(archived_value.len(), value.0.len());
+
+        for (key, value) in value.0.iter() {
+            assert_eq!(
+                archived_value.get(key.as_str()),
+                Some(&to_archived!(*value))
+            );
+        }
+        assert!(archived_value.get("missing").is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_serialize_from_iter_checked() {
+        use core::{alloc::Layout, ptr::NonNull};
+        use rkyv::{
+            collections::{hash_map::DuplicateKeyError, ArchivedHashMap},
+            ser::ScratchSpace,
+            AlignedVec, Archived, Fallible,
+        };
+
+        #[derive(Debug)]
+        enum CheckedSerializerError<E> {
+            Inner(E),
+            DuplicateKey,
+        }
+
+        impl<E> From<DuplicateKeyError> for CheckedSerializerError<E> {
+            fn from(_: DuplicateKeyError) -> Self {
+                Self::DuplicateKey
+            }
+        }
+
+        #[derive(Default)]
+        struct CheckedSerializer<S> {
+            inner: S,
+        }
+
+        impl<S: Fallible> Fallible for CheckedSerializer<S> {
+            type Error = CheckedSerializerError<S::Error>;
+        }
+
+        impl<S: Serializer> Serializer for CheckedSerializer<S> {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner
+                    .write(bytes)
+                    .map_err(CheckedSerializerError::Inner)
+            }
+        }
+
+        impl<S: ScratchSpace> ScratchSpace for CheckedSerializer<S> {
+            unsafe fn push_scratch(
+                &mut self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, Self::Error> {
+                self.inner
+                    .push_scratch(layout)
+                    .map_err(CheckedSerializerError::Inner)
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), Self::Error> {
+                self.inner
+                    .pop_scratch(ptr, layout)
+                    .map_err(CheckedSerializerError::Inner)
+            }
+        }
+
+        type CheckedError = CheckedSerializerError<<DefaultSerializer as Fallible>::Error>;
+
+        fn serialize(entries: &[(String, u32)]) -> Result<AlignedVec, CheckedError> {
+            let mut serializer = CheckedSerializer::<DefaultSerializer>::default();
+            let resolver =
+                ArchivedHashMap::<Archived<String>, Archived<u32>>::serialize_from_iter_checked(
+                    entries.iter().map(|(key, value)| (key, value)),
+                    &mut serializer,
+                )?;
+            let hash_map = entries.iter().cloned().collect::<HashMap<_, _>>();
+            serializer.align_for::<Archived<HashMap<String, u32>>>()?;
+            unsafe {
+                serializer.resolve_aligned(&hash_map, resolver)?;
+            }
+            Ok(serializer.inner.into_serializer().into_inner())
+        }
+
+        let entries = (0..50u32)
+            .map(|i| (format!("key {}", i), i))
+            .collect::<Vec<_>>();
+        let buf = serialize(&entries).unwrap();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+        assert_eq!(archived_value.len(), entries.len());
+        for (key, value) in entries.iter() {
+            assert_eq!(
+                archived_value.get(key.as_str()),
+                Some(&to_archived!(*value))
+            );
+        }
+
+        let mut duplicated = entries.clone();
+        duplicated.push(("key 17".to_string(), 100));
+        assert!(matches!(
+            serialize(&duplicated),
+            Err(CheckedSerializerError::DuplicateKey)
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_serialize_from_iter_by_key() {
+        use rkyv::{
+            collections::hash_map::{ArchivedHashMap, HashMapResolver},
+            ser::ScratchSpace,
+            Archived,
+        };
+
+        #[derive(Archive, Serialize)]
+        struct Record {
+            id: u32,
+            name: String,
+        }
+
+        struct Records(Vec<Record>);
+
+        impl Archive for Records {
+            type Archived = ArchivedHashMap<Archived<u32>, ArchivedRecord>;
+            type Resolver = HashMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Records {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedHashMap::serialize_from_iter_by_key(
+                        self.0.iter(),
+                        |record| &record.id,
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        let value = Records(
+            (0..100u32)
+                .map(|i| Record {
+                    id: i * 7,
+                    name: format!("record {}", i),
+                })
+                .collect(),
+        );
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<Records>(buf.as_ref()) };
+
+        assert_eq!(archived_value.len(), value.0.len());
+        for record in value.0.iter() {
+            let archived_record = archived_value.get(&to_archived!(record.id)).unwrap();
+            assert_eq!(archived_record.id, record.id);
+            assert_eq!(archived_record.name, record.name);
+        }
+        assert!(archived_value.get(&to_archived!(1u32)).is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_serialize_from_iter_lazy() {
+        use core::cell::Cell;
+        use rkyv::{
+            collections::hash_map::{ArchivedHashMap, HashMapResolver},
+            ser::{LazySerialize, ScratchSpace},
+            string::ArchivedString,
+            Archived,
+        };
+
+        // A value that's computed when the map is serialized
+        struct Thunk<'a> {
+            id: u32,
+            calls: &'a Cell<usize>,
+        }
+
+        impl<S: Serializer + ?Sized> LazySerialize<S> for &Thunk<'_> {
+            type Value = String;
+            type Output = String;
+
+            fn materialize(self) -> String {
+                self.calls.set(self.calls.get() + 1);
+                format!("value {}", self.id)
+            }
+        }
+
+        struct LazyMap<V>(HashMap<u32, V>);
+
+        impl<V> Archive for LazyMap<V> {
+            type Archived = ArchivedHashMap<Archived<u32>, ArchivedString>;
+            type Resolver = HashMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<V, S> Serialize<S> for LazyMap<V>
+        where
+            for<'a> &'a V: LazySerialize<S, Value = String>,
+            S: Serializer + ScratchSpace + ?Sized,
+        {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe { ArchivedHashMap::serialize_from_iter_lazy(self.0.iter(), serializer) }
+            }
+        }
+
+        let calls = Cell::new(0);
+        let value = LazyMap(
+            (0..50u32)
+                .map(|id| (id, Thunk { id, calls: &calls }))
+                .collect(),
+        );
+        assert_eq!(calls.get(), 0);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<LazyMap<Thunk>>(buf.as_ref()) };
+
+        assert_eq!(calls.get(), 50);
+        assert_eq!(archived_value.len(), 50);
+        for id in 0..50u32 {
+            assert_eq!(
+                archived_value.get(&to_archived!(id)).unwrap(),
+                &format!("value {}", id)
+            );
+        }
+
+        // Values that already exist are serialized by reference
+        let mut hash_map = HashMap::new();
+        hash_map.insert(1u32, "one".to_string());
+        hash_map.insert(2u32, "two".to_string());
+        let value = LazyMap(hash_map);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<LazyMap<String>>(buf.as_ref()) };
+
+        assert_eq!(archived_value.len(), 2);
+        assert_eq!(archived_value.get(&to_archived!(1u32)).unwrap(), "one");
+        assert_eq!(archived_value.get(&to_archived!(2u32)).unwrap(), "two");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_serialize_from_iter_owned() {
+        use rkyv::{
+            collections::hash_map::{ArchivedHashMap, HashMapResolver},
+            ser::ScratchSpace,
+            string::ArchivedString,
+            Archived,
+        };
+
+        fn generate(len: u32) -> impl ExactSizeIterator<Item = (u32, String)> {
+            (0..len).map(|i| (i * 7, format!("value {}", i)))
+        }
+
+        // Generates its entries when it's serialized
+        struct Generated(u32);
+
+        impl Archive for Generated {
+            type Archived = ArchivedHashMap<Archived<u32>, ArchivedString>;
+            type Resolver = HashMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMap::resolve_from_len(self.0 as usize, pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Generated {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe { ArchivedHashMap::serialize_from_iter_owned(generate(self.0), serializer) }
+            }
+        }
+
+        // Collects its entries before serializing them by reference
+        struct Collected(Vec<(u32, String)>);
+
+        impl Archive for Collected {
+            type Archived = ArchivedHashMap<Archived<u32>, ArchivedString>;
+            type Resolver = HashMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Collected {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedHashMap::serialize_from_iter(
+                        self.0.iter().map(|(key, value)| (key, value)),
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Generated(50)).unwrap();
+        let owned_buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<Generated>(owned_buf.as_ref()) };
+
+        assert_eq!(archived_value.len(), 50);
+        for (key, value) in generate(50) {
+            assert_eq!(archived_value.get(&to_archived!(key)).unwrap(), &value);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Collected(generate(50).collect()))
+            .unwrap();
+        let ref_buf = serializer.into_serializer().into_inner();
+
+        assert_eq!(owned_buf.as_slice(), ref_buf.as_slice());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_soa() {
+        use core::mem::size_of_val;
+        use rkyv::{
+            collections::{hash_map_soa::HashMapSoAResolver, ArchivedHashMapSoA},
+            ser::ScratchSpace,
+            string::ArchivedString,
+            Archived,
+        };
+
+        struct SoAMap(HashMap<u32, String>);
+
+        impl Archive for SoAMap {
+            type Archived = ArchivedHashMapSoA<Archived<u32>, ArchivedString>;
+            type Resolver = HashMapSoAResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMapSoA::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for SoAMap {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe { ArchivedHashMapSoA::serialize_from_iter(self.0.iter(), serializer) }
+            }
+        }
+
+        let value = SoAMap(
+            (0..100u32)
+                .map(|i| (i * 3, format!("value {}", i)))
+                .collect(),
+        );
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<SoAMap>(buf.as_ref()) };
+
+        assert_eq!(archived_value.len(), 100);
+        for (key, value) in value.0.iter() {
+            let key = to_archived!(*key);
+            assert_eq!(archived_value.get(&key).unwrap(), value);
+            assert_eq!(&archived_value[&key], value);
+            assert!(archived_value.contains_key(&key));
+        }
+        assert!(archived_value.get(&to_archived!(1u32)).is_none());
+        assert_eq!(archived_value.iter().count(), 100);
+
+        // Keys are packed together without any values between them
+        let keys = archived_value.keys_slice();
+        let values = archived_value.values_slice();
+        let keys_start = keys.as_ptr() as usize;
+        let keys_end = keys_start + size_of_val(keys);
+        let values_start = values.as_ptr() as usize;
+        let values_end = values_start + size_of_val(values);
+        assert!(keys_end <= values_start || values_end <= keys_start);
+        for (key, value) in keys.iter().zip(values.iter()) {
+            assert_eq!(archived_value.get(key), Some(value));
+        }
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<SoAMap>(buf.as_ref()).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_entry_pin() {
+        use core::{
+            hash::{Hash, Hasher},
+            pin::Pin,
+            sync::atomic::{AtomicUsize, Ordering},
+        };
+        use rkyv::{archived_root_mut, collections::hash_map::EntryPin};
+
+        static HASHES: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Archive, Serialize, Hash, PartialEq, Eq)]
+        #[archive_attr(derive(Debug, PartialEq, Eq))]
+        struct Key(u32);
+
+        impl Hash for ArchivedKey {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                HASHES.fetch_add(1, Ordering::Relaxed);
+                self.0.hash(state);
+            }
+        }
+
+        for &len in [1u32, 10, 100].iter() {
+            let hash_map = (0..len).map(|i| (Key(i), i)).collect::<HashMap<_, _>>();
+
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&hash_map).unwrap();
+            let mut buf = serializer.into_serializer().into_inner();
+            let mut archived_value =
+                unsafe { archived_root_mut::<HashMap<Key, u32>>(Pin::new(buf.as_mut())) };
+
+            for i in 0..len {
+                let key = ArchivedKey(to_archived!(i));
+
+                let before = HASHES.load(Ordering::Relaxed);
+                assert!(archived_value.get(&key).is_some());
+                let get_hashes = HASHES.load(Ordering::Relaxed) - before;
+
+                let before = HASHES.load(Ordering::Relaxed);
+                match archived_value.as_mut().entry_pin(&key) {
+                    EntryPin::Occupied(mut entry) => {
+                        assert_eq!(entry.key(), &key);
+                        assert_eq!(*entry.get(), i);
+                        *entry.get_pin() = to_archived!(i * 2);
+                    }
+                    EntryPin::Vacant => panic!("expected an occupied entry"),
+                }
+                assert_eq!(HASHES.load(Ordering::Relaxed) - before, get_hashes);
+            }
+
+            assert!(archived_value
+                .as_mut()
+                .entry_pin(&ArchivedKey(to_archived!(len)))
+                .is_vacant());
+
+            for (key, value) in archived_value.iter() {
+                assert_eq!(*value, key.0 * 2);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_iter_mut() {
+        use core::pin::Pin;
+        use rkyv::archived_root_mut;
+
+        let hash_map = (0..20u32).map(|i| (i, i)).collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived_value =
+            unsafe { archived_root_mut::<HashMap<u32, u32>>(Pin::new(buf.as_mut())) };
+
+        for (key, value) in archived_value.as_mut().iter_mut() {
+            *value = to_archived!(from_archived!(*key) * 10);
+        }
+        for (key, value) in hash_map.iter() {
+            let archived = archived_value.get(&to_archived!(*key));
+            assert_eq!(archived, Some(&to_archived!(*value * 10)));
+        }
+
+        assert_eq!(archived_value.as_mut().values_mut().len(), hash_map.len());
+        for value in archived_value.as_mut().values_mut() {
+            *value += 1;
+        }
+        for (key, value) in hash_map.iter() {
+            let archived = archived_value.get(&to_archived!(*key));
+            assert_eq!(archived, Some(&to_archived!(*value * 10 + 1)));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_get_many_pin_mut() {
+        use core::pin::Pin;
+        use rkyv::archived_root_mut;
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert("a".to_string(), 1u32);
+        hash_map.insert("b".to_string(), 2);
+        hash_map.insert("c".to_string(), 3);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived_value =
+            unsafe { archived_root_mut::<HashMap<String, u32>>(Pin::new(buf.as_mut())) };
+
+        let [mut a, mut c] = archived_value
+            .as_mut()
+            .get_many_pin_mut(["a", "c"])
+            .unwrap();
+        core::mem::swap(&mut *a, &mut *c);
+        assert_eq!(archived_value.get("a"), Some(&to_archived!(3u32)));
+        assert_eq!(archived_value.get("b"), Some(&to_archived!(2u32)));
+        assert_eq!(archived_value.get("c"), Some(&to_archived!(1u32)));
+
+        assert!(archived_value
+            .as_mut()
+            .get_many_pin_mut(["a", "b", "a"])
+            .is_none());
+        assert!(archived_value
+            .as_mut()
+            .get_many_pin_mut(["a", "d"])
+            .is_none());
+        assert!(archived_value
+            .as_mut()
+            .get_many_pin_mut::<str, 0>([])
+            .is_some());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_get_with_hash() {
+        use core::hash::{Hash, Hasher};
+
+        let mut hash_map = HashMap::new();
+        for i in 0..100u32 {
+            hash_map.insert(i.to_string(), i);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        for i in 0..110u32 {
+            let key = i.to_string();
+            let mut hasher = archived_value.hasher();
+            key.as_str().hash(&mut hasher);
+            let hash = hasher.finish();
+
+            assert_eq!(
+                archived_value.get_with_hash(key.as_str(), hash),
+                archived_value.get(key.as_str())
+            );
+            assert_eq!(
+                archived_value.get_with_hash(key.as_str(), hash).is_some(),
+                i < 100
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_deserialize_into() {
+        let hash_map = (0..1000u32)
+            .map(|i| (i, i.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<u32, String>>(buf.as_ref()) };
+
+        let mut deserialized = HashMap::new();
+        archived_value
+            .deserialize_into(&mut deserialized, &mut DefaultDeserializer::default())
+            .unwrap();
+
+        assert_eq!(deserialized, hash_map);
+        // Reserving up front means the map only allocated once for all of the entries
+        assert_eq!(
+            deserialized.capacity(),
+            HashMap::<u32, String>::with_capacity(hash_map.len()).capacity()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_index_displacement_stats() {
+        use rkyv::collections::hash_index::DisplacementStats;
+
+        let hash_map = (0..1000u32)
+            .map(|i| (i.to_string(), i))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        let stats = archived_value.hash_index().displacement_stats();
+        assert!(stats.nonzero_count > 0);
+        assert!(stats.nonzero_count < hash_map.len());
+        assert!(stats.max > 0);
+        assert!(stats.mean > 0.0);
+        assert!(stats.mean <= stats.max as f64);
 
-            test_archive(&hash_map);
+        let empty = HashMap::<String, u32>::new();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&empty).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        assert_eq!(
+            archived_value.hash_index().displacement_stats(),
+            DisplacementStats {
+                max: 0,
+                mean: 0.0,
+                nonzero_count: 0,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_deserialize_with_capacity() {
+        let hash_map = (0..100u32)
+            .map(|i| (i, i.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<u32, String>>(buf.as_ref()) };
+
+        let mut deserialized: HashMap<u32, String> = archived_value
+            .deserialize_with_capacity(50, &mut DefaultDeserializer::default())
+            .unwrap();
+        assert_eq!(deserialized, hash_map);
+        assert!(deserialized.capacity() >= hash_map.len() + 50);
+
+        let capacity = deserialized.capacity();
+        for i in 100..150u32 {
+            deserialized.insert(i, i.to_string());
         }
+        assert_eq!(deserialized.capacity(), capacity);
+    }
 
-        let mut hash_map = HashMap::new();
-        hash_map.insert("hello".to_string(), "world".to_string());
-        hash_map.insert("foo".to_string(), "bar".to_string());
-        hash_map.insert("baz".to_string(), "bat".to_string());
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_deserialize_checked() {
+        use core::{mem::size_of, slice};
+        use rkyv::{
+            collections::hash_map::{ArchivedHashMap, HashMapLengthError},
+            from_archived, to_archived, Archived, Fallible, FixedUsize,
+        };
+
+        struct CheckedDeserializer;
+
+        impl Fallible for CheckedDeserializer {
+            type Error = HashMapLengthError;
+        }
+
+        const LEN: usize = 32;
+
+        let hash_map = (0..LEN as u32)
+            .map(|i| (i, i * 2))
+            .collect::<HashMap<_, _>>();
 
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&hash_map).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<HashMap<u32, u32>>(buf.as_ref()) };
+        archived.check_len().unwrap();
+        let deserialized: HashMap<u32, u32> = archived
+            .deserialize_checked(&mut CheckedDeserializer)
+            .unwrap();
+        assert_eq!(deserialized, hash_map);
+
+        // The root hash map is at the end of the archive. Every length it stores is equal to the
+        // number of entries, and none of its relative pointers are. Shrinking the lengths keeps
+        // every access in bounds.
+        let root_size = size_of::<ArchivedHashMap<Archived<u32>, Archived<u32>>>();
+        let start = buf.len() - root_size;
+        let words = unsafe {
+            slice::from_raw_parts_mut(
+                buf.as_mut_ptr().add(start).cast::<Archived<usize>>(),
+                root_size / size_of::<Archived<usize>>(),
+            )
+        };
+        for word in words.iter_mut() {
+            if from_archived!(*word) as usize == LEN {
+                *word = to_archived!((LEN - 1) as FixedUsize);
+            }
+        }
+
+        let archived = unsafe { archived_root::<HashMap<u32, u32>>(buf.as_ref()) };
+        assert_eq!(archived.len(), LEN - 1);
+        match archived.check_len() {
+            Err(HashMapLengthError { len }) => assert_eq!(len, LEN - 1),
+            result => panic!("expected a hash map length error, got {:?}", result),
+        }
+        let result: Result<HashMap<u32, u32>, _> =
+            archived.deserialize_checked(&mut CheckedDeserializer);
+        match result {
+            Err(HashMapLengthError { len }) => assert_eq!(len, LEN - 1),
+            result => panic!("expected a hash map length error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hash_map_insertion_order")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_iter_insertion_order() {
+        use rkyv::{collections::ArchivedHashMap, Archived};
+
+        let entries = (0..100u32)
+            .rev()
+            .map(|i| (format!("key {}", i), i))
+            .collect::<Vec<_>>();
+        let hash_map = entries.iter().cloned().collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        unsafe {
+            let resolver = ArchivedHashMap::<Archived<String>, Archived<u32>>::serialize_from_iter(
+                entries.iter().map(|(key, value)| (key, value)),
+                &mut serializer,
+            )
+            .unwrap();
+            serializer
+                .align_for::<Archived<HashMap<String, u32>>>()
+                .unwrap();
+            serializer.resolve_aligned(&hash_map, resolver).unwrap();
+        }
         let buf = serializer.into_serializer().into_inner();
-        let archived_value = unsafe { archived_root::<HashMap<String, String>>(buf.as_ref()) };
 
-        assert_eq!(archived_value.len(), hash_map.len());
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<HashMap<String, u32>>(buf.as_ref()).unwrap();
+
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+        assert_eq!(archived_value.iter_insertion_order().len(), entries.len());
+        for ((key, value), (archived_key, archived_value)) in
+            entries.iter().zip(archived_value.iter_insertion_order())
+        {
+            assert_eq!(archived_key, key);
+            assert_eq!(archived_value, value);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hash_map_buckets")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_serialize_from_iter_with_buckets() {
+        use rkyv::{
+            collections::{hash_map::HashMapBuildOptions, ArchivedHashMap},
+            AlignedVec, Archived,
+        };
+
+        let hash_map = (0..1000u32)
+            .map(|i| (format!("key {}", i), i))
+            .collect::<HashMap<_, _>>();
+
+        fn serialize(hash_map: &HashMap<String, u32>, options: HashMapBuildOptions) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            unsafe {
+                let resolver =
+                    ArchivedHashMap::<Archived<String>, Archived<u32>>::serialize_from_iter_with(
+                        hash_map.iter(),
+                        options,
+                        &mut serializer,
+                    )
+                    .unwrap();
+                serializer
+                    .align_for::<Archived<HashMap<String, u32>>>()
+                    .unwrap();
+                serializer.resolve_aligned(hash_map, resolver).unwrap();
+            }
+            serializer.into_serializer().into_inner()
+        }
+
+        // The default options produce the same archive as serializing the hash map directly
+        let default_buf = serialize(&hash_map, HashMapBuildOptions::default());
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        assert_eq!(
+            default_buf.as_slice(),
+            serializer.into_serializer().into_inner().as_slice()
+        );
+
+        let wide_buf = serialize(&hash_map, HashMapBuildOptions { bucket_ratio: 4.0 });
+
+        #[cfg(feature = "validation")]
+        {
+            rkyv::check_archived_root::<HashMap<String, u32>>(default_buf.as_ref()).unwrap();
+            rkyv::check_archived_root::<HashMap<String, u32>>(wide_buf.as_ref()).unwrap();
+        }
 
+        let default_value = unsafe { archived_root::<HashMap<String, u32>>(default_buf.as_ref()) };
+        let wide_value = unsafe { archived_root::<HashMap<String, u32>>(wide_buf.as_ref()) };
+
+        assert_eq!(default_value.hash_index().buckets(), hash_map.len());
+        assert_eq!(wide_value.hash_index().buckets(), 4 * hash_map.len());
+        assert_eq!(wide_value.len(), hash_map.len());
         for (key, value) in hash_map.iter() {
-            assert!(archived_value.contains_key(key.as_str()));
-            assert_eq!(&archived_value[key.as_str()], value);
+            assert_eq!(wide_value.get(key.as_str()), Some(value));
         }
+        assert!(wide_value.get("missing").is_none());
 
-        for (key, value) in archived_value.iter() {
-            assert!(hash_map.contains_key(key.as_str()));
-            assert_eq!(&hash_map[key.as_str()], value);
+        // Bucket ratios that don't produce an archivable bucket count are rejected
+        assert!(HashMapBuildOptions {
+            bucket_ratio: f32::NAN
         }
+        .buckets(10)
+        .is_none());
+        assert!(HashMapBuildOptions {
+            bucket_ratio: f32::INFINITY
+        }
+        .buckets(10)
+        .is_none());
+        assert!(HashMapBuildOptions { bucket_ratio: 1e30 }
+            .buckets(10)
+            .is_none());
+        assert!(HashMapBuildOptions { bucket_ratio: 0.0 }
+            .buckets(10)
+            .is_none());
+        assert!(HashMapBuildOptions { bucket_ratio: -1.0 }
+            .buckets(10)
+            .is_none());
+        assert!(HashMapBuildOptions { bucket_ratio: 0.5 }
+            .buckets(10)
+            .is_none());
+        assert_eq!(
+            HashMapBuildOptions { bucket_ratio: 1.5 }.buckets(10),
+            Some(15)
+        );
+
+        let default_stats = default_value.hash_index().displacement_stats();
+        let wide_stats = wide_value.hash_index().displacement_stats();
+        assert!(wide_stats.max < default_stats.max);
+        assert!(wide_stats.nonzero_count < default_stats.nonzero_count);
     }
 
     #[test]
+    #[cfg(feature = "hash_map_tombstones")]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    #[allow(deprecated)]
-    fn archive_hash_map_hasher() {
-        use std::collections::HashMap;
+    fn hash_map_tombstone_if() {
+        use core::pin::Pin;
+        use rkyv::archived_root_mut;
 
-        test_archive(&HashMap::<i8, i32, ahash::RandomState>::default());
+        let mut hash_map = HashMap::new();
+        for i in 0..100u32 {
+            hash_map.insert(format!("key {}", i), i);
+        }
 
-        let mut hash_map: HashMap<i8, _, ahash::RandomState> = HashMap::default();
-        hash_map.insert(1, 2);
-        hash_map.insert(3, 4);
-        hash_map.insert(5, 6);
-        hash_map.insert(7, 8);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived_value =
+            unsafe { archived_root_mut::<HashMap<String, u32>>(Pin::new(buf.as_mut())) };
+
+        archived_value
+            .as_mut()
+            .tombstone_if(|_, value| *value % 2 == 0);
+
+        assert_eq!(archived_value.len(), 50);
+        for i in 0..100u32 {
+            let key = format!("key {}", i);
+            if i % 2 == 0 {
+                assert!(archived_value.get(key.as_str()).is_none());
+            } else {
+                assert_eq!(archived_value.get(key.as_str()), Some(&i.into()));
+            }
+        }
+        assert_eq!(archived_value.iter().len(), 50);
+        assert!(archived_value.values().all(|value| *value % 2 == 1));
+        assert_eq!(archived_value.as_mut().values_mut().count(), 50);
 
-        test_archive(&hash_map);
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<HashMap<String, u32>>(buf.as_ref()).unwrap();
+    }
 
-        let mut hash_map: HashMap<_, _, ahash::RandomState> = HashMap::default();
-        hash_map.insert("hello".to_string(), "world".to_string());
-        hash_map.insert("foo".to_string(), "bar".to_string());
-        hash_map.insert("baz".to_string(), "bat".to_string());
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn hash_map_serialize_from_iter_par() {
+        use rkyv::collections::ArchivedHashMap;
+
+        fn serialize(hash_map: &HashMap<u32, String>, par: bool) -> Vec<u8> {
+            let mut serializer = DefaultSerializer::default();
+            unsafe {
+                let resolver = if par {
+                    ArchivedHashMap::serialize_from_iter_par(hash_map.iter(), &mut serializer)
+                } else {
+                    ArchivedHashMap::serialize_from_iter(hash_map.iter(), &mut serializer)
+                }
+                .unwrap();
+                serializer.resolve_aligned(hash_map, resolver).unwrap();
+            }
+            serializer.into_serializer().into_inner().to_vec()
+        }
+
+        for &len in [0u32, 1, 7, 1000, 20000].iter() {
+            let hash_map = (0..len)
+                .map(|i| (i.wrapping_mul(0x9e37_79b9), i.to_string()))
+                .collect::<HashMap<_, _>>();
 
+            let sequential = serialize(&hash_map, false);
+            let parallel = serialize(&hash_map, true);
+            assert_eq!(sequential, parallel);
+
+            let archived_value = unsafe { archived_root::<HashMap<u32, String>>(&parallel) };
+            assert_eq!(archived_value.len(), hash_map.len());
+            for (key, value) in hash_map.iter() {
+                assert_eq!(&archived_value[key], value);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "size_16"))]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_get_batch() {
+        let empty = HashMap::<String, u32>::new();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&empty).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+        assert_eq!(archived_value.get_batch(&["hello"]), vec![None]);
+        assert!(archived_value.get_batch::<str>(&[]).is_empty());
+
+        let hash_map = (0..10_000u32)
+            .map(|i| (format!("key{}", i * 2), i))
+            .collect::<HashMap<_, _>>();
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&hash_map).unwrap();
         let buf = serializer.into_serializer().into_inner();
-        let archived_value =
-            unsafe { archived_root::<HashMap<String, String, ahash::RandomState>>(buf.as_ref()) };
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        // Half of the keys are present, and batch sizes around the prefetch distance are covered
+        let keys = (0..20_000u32)
+            .rev()
+            .map(|i| format!("key{}", i))
+            .collect::<Vec<_>>();
+        let keys = keys.iter().map(|k| k.as_str()).collect::<Vec<_>>();
+        for len in [0, 1, 7, 8, 9, 16, 17, 100, keys.len()].iter().copied() {
+            let batch = archived_value.get_batch(&keys[..len]);
+            assert_eq!(batch.len(), len);
+            for (key, value) in keys.iter().zip(batch) {
+                assert_eq!(value, archived_value.get(*key));
+            }
+        }
 
-        assert_eq!(archived_value.len(), hash_map.len());
+        let batched = archived_value
+            .get_batch(&keys)
+            .into_iter()
+            .filter(Option::is_some)
+            .count();
+        assert_eq!(batched, 10_000);
+    }
 
-        for (key, value) in hash_map.iter() {
-            assert!(archived_value.contains_key(key.as_str()));
-            assert_eq!(&archived_value[key.as_str()], value);
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_deserialize_with() {
+        use rkyv::Fallible;
+
+        #[derive(Debug, PartialEq)]
+        struct NegativePrice(String);
+
+        struct PriceDeserializer;
+
+        impl Fallible for PriceDeserializer {
+            type Error = NegativePrice;
         }
 
-        for (key, value) in archived_value.iter() {
-            assert!(hash_map.contains_key(key.as_str()));
-            assert_eq!(&hash_map[key.as_str()], value);
+        let check_price = |k: &rkyv::string::ArchivedString, v: &rkyv::Archived<i32>| {
+            if from_archived!(*v) < 0 {
+                Err(NegativePrice(k.to_string()))
+            } else {
+                Ok(())
+            }
+        };
+
+        let mut prices = HashMap::new();
+        prices.insert("apple".to_string(), 3);
+        prices.insert("banana".to_string(), 1);
+        prices.insert("cherry".to_string(), 7);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&prices).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, i32>>(buf.as_ref()) };
+
+        let deserialized: HashMap<String, i32> = archived_value
+            .deserialize_with(&mut PriceDeserializer, check_price)
+            .unwrap();
+        assert_eq!(deserialized, prices);
+
+        prices.insert("durian".to_string(), -2);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&prices).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, i32>>(buf.as_ref()) };
+
+        let result: Result<HashMap<String, i32>, _> =
+            archived_value.deserialize_with(&mut PriceDeserializer, check_price);
+        assert_eq!(result, Err(NegativePrice("durian".to_string())));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_entries_slice() {
+        let hash_map = (0..100u32)
+            .map(|i| (i.to_string(), i))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        let entries = archived_value.entries_slice();
+        assert_eq!(entries.len(), archived_value.len());
+        for (key, value) in hash_map.iter() {
+            assert!(entries.iter().any(|e| e.key == *key && e.value == *value));
         }
+        assert!(entries
+            .iter()
+            .map(|e| (&e.key, &e.value))
+            .eq(archived_value.iter()));
     }
 
     #[test]
@@ -143,6 +1532,70 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_set_contains_all() {
+        let hash_set = (0..100).map(|i| i.to_string()).collect::<HashSet<_>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_set).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashSet<String>>(buf.as_ref()) };
+
+        // All present
+        let present = (0..100).map(|i| i.to_string()).collect::<Vec<_>>();
+        let present = present.iter().map(String::as_str).collect::<Vec<_>>();
+        assert!(archived_value.contains_all(&present));
+        assert!(archived_value.missing(&present).is_empty());
+
+        // Some missing
+        let candidates = (50..150).map(|i| i.to_string()).collect::<Vec<_>>();
+        let candidates = candidates.iter().map(String::as_str).collect::<Vec<_>>();
+        assert!(!archived_value.contains_all(&candidates));
+        assert_eq!(archived_value.missing(&candidates), &candidates[50..]);
+        assert!(!archived_value.contains_all(&["hello"]));
+        assert_eq!(archived_value.missing(&["hello"]), vec!["hello"]);
+
+        // Empty input
+        assert!(archived_value.contains_all::<str>(&[]));
+        assert!(archived_value.missing::<str>(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_set_size() {
+        let hash_set = (0..100u32).collect::<HashSet<_>>();
+        let hash_map = hash_set.iter().map(|&k| (k, ())).collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_set).unwrap();
+        let set_buf = serializer.into_serializer().into_inner();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let map_buf = serializer.into_serializer().into_inner();
+
+        let archived_value = unsafe { archived_root::<HashSet<u32>>(set_buf.as_ref()) };
+        assert_eq!(archived_value.len(), hash_set.len());
+        assert!(hash_set
+            .iter()
+            .all(|k| archived_value.contains(&to_archived!(*k))));
+        assert!(!archived_value.contains(&to_archived!(100u32)));
+
+        // By default, sets are stored as a map of units. With `hash_set_keys_only`, they store the
+        // same keys and hash index but none of the per-entry data that hash maps can be configured
+        // to store.
+        #[cfg(not(feature = "hash_set_keys_only"))]
+        assert_eq!(set_buf.len(), map_buf.len());
+        #[cfg(feature = "hash_set_keys_only")]
+        assert!(set_buf.len() <= map_buf.len());
+        #[cfg(all(
+            feature = "hash_set_keys_only",
+            any(feature = "hash_map_insertion_order", feature = "hash_map_tombstones")
+        ))]
+        assert!(set_buf.len() < map_buf.len());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     #[allow(deprecated)]
@@ -211,15 +1664,128 @@ mod tests {
         };
 
         test_archive(&value);
+
+        let value = TestNet {
+            ip: IpAddr::V6(Ipv6Addr::new(31, 41, 59, 26, 53, 58, 97, 93)),
+            sock: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(31, 41, 59, 26), 5358)),
+            ..value
+        };
+
+        test_archive(&value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn c_string() {
-        use std::ffi::CString;
+        use std::{
+            borrow::Cow,
+            ffi::{CStr, CString},
+        };
 
         let value = unsafe { CString::from_vec_unchecked("hello world".to_string().into_bytes()) };
         test_archive(&value);
+
+        test_archive(&Cow::Borrowed(value.as_c_str()));
+        test_archive(&Cow::<CStr>::Owned(value));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn path_buf() {
+        use std::path::PathBuf;
+
+        test_archive(&PathBuf::new());
+        test_archive(&PathBuf::from("hello/world.txt"));
+        test_archive(&PathBuf::from("h\u{e9}llo/w\u{f6}rld/\u{1f980}"));
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+            // Unix paths don't have to be valid UTF-8
+            let value = PathBuf::from(OsStr::from_bytes(b"foo/\xffbar"));
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<PathBuf>(buf.as_ref()) };
+
+            assert_eq!(archived.as_bytes(), b"foo/\xffbar");
+            assert_eq!(archived.as_path(), value.as_path());
+            assert_eq!(archived, &value);
+
+            let deserialized: PathBuf = archived
+                .deserialize(&mut DefaultDeserializer::default())
+                .unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn path_buf_as_string() {
+        use rkyv::{
+            ser::serializers::AlignedSerializer,
+            with::{AsString, AsStringError},
+            AlignedVec, Fallible, Infallible,
+        };
+        use std::path::PathBuf;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AsString)]
+            value: PathBuf,
+        }
+
+        struct StringSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+        }
+
+        impl Fallible for StringSerializer {
+            type Error = AsStringError;
+        }
+
+        impl Serializer for StringSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).unwrap();
+                Ok(())
+            }
+        }
+
+        let value = Test {
+            value: PathBuf::from("hello world"),
+        };
+        let mut serializer = StringSerializer {
+            inner: AlignedSerializer::new(AlignedVec::new()),
+        };
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.inner.into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert_eq!(archived.value, "hello world");
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+
+        assert_eq!(deserialized.value.to_str().unwrap(), "hello world");
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+            let value = Test {
+                value: PathBuf::from(OsStr::from_bytes(b"foo/\xffbar")),
+            };
+            let mut serializer = StringSerializer {
+                inner: AlignedSerializer::new(AlignedVec::new()),
+            };
+            match serializer.serialize_value(&value) {
+                Err(AsStringError::InvalidUTF8) => (),
+                result => panic!("expected an invalid UTF-8 error, got {:?}", result),
+            }
+        }
     }
 
     // TODO: figure out errors
@@ -306,34 +1872,6 @@ mod tests {
     //     assert_eq!(deserialized.value, "hello world");
     // }
 
-    // #[test]
-    // #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    // fn path_buf() {
-    //     use rkyv::with::ToString;
-    //     use core::str::FromStr;
-    //     use std::path::PathBuf;
-
-    //     #[derive(Archive, Serialize, Deserialize)]
-    //     struct Test {
-    //         #[with(ToString)]
-    //         value: PathBuf,
-    //     }
-
-    //     let value = Test {
-    //         value: PathBuf::from_str("hello world").unwrap(),
-    //     };
-    //     let mut serializer = AlignedSerializer::new(AlignedVec::new());
-    //     serializer.serialize_value(&value).unwrap();
-    //     let result = serializer.into_inner();
-    //     let archived = unsafe { archived_root::<Test>(result.as_slice()) };
-
-    //     assert_eq!(archived.value, "hello world");
-
-    //     let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
-
-    //     assert_eq!(deserialized.value.to_str().unwrap(), "hello world");
-    // }
-
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     // Don't run these tests with non-native endianness because ArchivedHashMap won't have
@@ -364,4 +1902,87 @@ mod tests {
         value.insert(());
         test_archive(&value);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_duration() {
+        use std::time::Duration;
+
+        test_archive(&Duration::new(0, 0));
+        test_archive(&Duration::new(0, 250_000_000));
+        test_archive(&Duration::new(12, 345_678_901));
+        test_archive(&Duration::new(u64::MAX, 999_999_999));
+
+        let value = Duration::from_millis(1_500);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Duration>(buf.as_ref()) };
+
+        assert_eq!(archived.as_secs(), 1);
+        assert_eq!(archived.subsec_nanos(), 500_000_000);
+        assert_eq!(archived.subsec_millis(), 500);
+        assert_eq!(archived.as_millis(), 1_500);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_unix_timestamp() {
+        use core::convert::Infallible;
+        use rkyv::{
+            ser::serializers::AlignedSerializer,
+            with::{SerializeWith, UnixTimestamp, UnixTimestampError},
+            AlignedVec, Fallible,
+        };
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        #[derive(Default)]
+        struct TimestampSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+        }
+
+        impl Fallible for TimestampSerializer {
+            type Error = UnixTimestampError;
+        }
+
+        impl Serializer for TimestampSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|e: Infallible| match e {})
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Event {
+            #[with(UnixTimestamp)]
+            time: SystemTime,
+            elapsed: Duration,
+        }
+
+        let value = Event {
+            time: UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789),
+            elapsed: Duration::from_micros(250),
+        };
+
+        let mut serializer = TimestampSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.inner.into_inner();
+        let archived = unsafe { archived_root::<Event>(buf.as_ref()) };
+
+        assert_eq!(archived.time.as_secs(), 1_600_000_000);
+        assert_eq!(archived.time.subsec_nanos(), 123_456_789);
+        assert_eq!(archived.elapsed, value.elapsed);
+
+        let deserialized: Event = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+        match UnixTimestamp::serialize_with(&before_epoch, &mut TimestampSerializer::default()) {
+            Err(UnixTimestampError::TimeBeforeUnixEpoch) => (),
+            result => panic!("expected time before epoch error, got {:?}", result),
+        }
+    }
 }
@@ -12,7 +12,7 @@ mod tests {
     };
     use bytecheck::CheckBytes;
     use rkyv::{
-        check_archived_root, check_archived_value, ser::Serializer, AlignedBytes, Archive,
+        access, check_archived_root, check_archived_value, ser::Serializer, AlignedBytes, Archive,
         Deserialize, Infallible, Serialize,
     };
     #[cfg(feature = "std")]
@@ -380,6 +380,49 @@ mod tests {
         serialize_and_check(&Node::Cons(Box::new(Node::Cons(Box::new(Node::Nil)))));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_max_depth() {
+        use rkyv::validation::{check_archived_root_with_context, validators::DefaultValidator};
+
+        #[derive(Archive, Serialize)]
+        #[archive(bound(serialize = "__S: Serializer"))]
+        #[archive_attr(derive(CheckBytes))]
+        #[archive_attr(check_bytes(
+            bound = "__C: ::rkyv::validation::ArchiveContext, <__C as ::rkyv::Fallible>::Error: ::bytecheck::Error"
+        ))]
+        enum Node {
+            Nil,
+            Cons(
+                #[omit_bounds]
+                #[archive_attr(omit_bounds)]
+                Box<Node>,
+            ),
+        }
+
+        let mut value = Node::Nil;
+        for _ in 0..10 {
+            value = Node::Cons(Box::new(value));
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        // The root and each box are validated one level deeper than the last
+        let mut validator = DefaultValidator::with_max_depth(buf.as_ref(), 11);
+        check_archived_root_with_context::<Node, _>(buf.as_ref(), &mut validator).unwrap();
+
+        // The depth error surfaces from the innermost box that could not be checked
+        let mut validator = DefaultValidator::with_max_depth(buf.as_ref(), 5);
+        let error = check_archived_root_with_context::<Node, _>(buf.as_ref(), &mut validator)
+            .map(|_| ())
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("exceeded the maximum subtree depth of 5"));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn check_shared_ptr() {
@@ -403,6 +446,48 @@ mod tests {
         check_archived_root::<Test>(buf.as_ref()).unwrap();
     }
 
+    #[cfg(feature = "size_32")]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_shared_ptr_cycle() {
+        use rkyv::{
+            rc::validation::SharedPointerError,
+            validation::{
+                validators::{ArchiveError, DefaultValidatorError},
+                CheckArchiveError,
+            },
+        };
+
+        // Self-referential shared pointer
+        let synthetic_buf = AlignedBytes([
+            0u8, 0u8, 0u8, 0u8, // Points to itself
+        ]);
+
+        match check_archived_value::<Rc<u32>>(synthetic_buf.as_ref(), 0) {
+            Err(CheckArchiveError::CheckBytesError(SharedPointerError::ContextError(
+                DefaultValidatorError::ArchiveError(ArchiveError::SubtreePointerOutOfBounds {
+                    ..
+                }),
+            ))) => (),
+            other => panic!("expected subtree pointer out of bounds error, got {:?}", other),
+        }
+
+        // Shared pointers that point to each other: the inner shared pointer is 4 bytes forward
+        // and the outer shared pointer is 4 bytes back
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&[4i32, -4i32]).unwrap();
+        let synthetic_buf = serializer.into_serializer().into_inner();
+
+        match check_archived_value::<Rc<Rc<u32>>>(synthetic_buf.as_ref(), 4) {
+            Err(CheckArchiveError::CheckBytesError(SharedPointerError::ValueCheckBytesError(
+                SharedPointerError::ContextError(DefaultValidatorError::ArchiveError(
+                    ArchiveError::SubtreePointerOutOfBounds { .. },
+                )),
+            ))) => (),
+            other => panic!("expected subtree pointer out of bounds error, got {:?}", other),
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn check_b_tree() {
@@ -531,6 +616,88 @@ mod tests {
         check_archived_root::<Duration>(&[0xFF, 16]).unwrap_err();
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_arrays() {
+        check_archived_root::<[u8; 0]>(&[]).unwrap();
+        check_archived_root::<[bool; 1]>(&[1]).unwrap();
+        check_archived_root::<[bool; 1]>(&[2]).unwrap_err();
+
+        let mut bools = [1u8; 33];
+        check_archived_root::<[bool; 33]>(&bools).unwrap();
+        bools[32] = 2;
+        check_archived_root::<[bool; 33]>(&bools).unwrap_err();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&[0xabcd_u32; 128]).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<[u32; 128]>(buf.as_ref()).unwrap();
+        assert!(archived.iter().all(|&x| x == 0xabcd));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_nonzero() {
+        use core::num::{NonZeroI64, NonZeroU32, NonZeroU8};
+
+        check_archived_root::<NonZeroU8>(&[0]).unwrap_err();
+        check_archived_root::<NonZeroU8>(&[1]).unwrap();
+        check_archived_root::<NonZeroU32>(AlignedBytes([0u8; 4]).as_ref()).unwrap_err();
+        check_archived_root::<NonZeroU32>(AlignedBytes([0u8, 0, 1, 0]).as_ref()).unwrap();
+        check_archived_root::<NonZeroI64>(AlignedBytes([0u8; 8]).as_ref()).unwrap_err();
+        check_archived_root::<NonZeroI64>(AlignedBytes([0xffu8; 8]).as_ref()).unwrap();
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes, Debug))]
+        struct Test {
+            id: NonZeroU32,
+            value: u32,
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Test {
+                id: NonZeroU32::new(1).unwrap(),
+                value: 0,
+            })
+            .unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Test>(buf.as_ref()).unwrap();
+
+        buf.as_mut_slice().fill(0);
+        check_archived_root::<Test>(buf.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_char() {
+        use bytecheck::CharCheckError;
+        use rkyv::validation::CheckArchiveError;
+
+        serialize_and_check(&'a');
+        serialize_and_check(&vec!['h', 'é', '\u{10ffff}', '\0']);
+
+        // Archives a `u32` so that its bytes have the same endianness as an archived `char`
+        let archive_u32 = |value: u32| {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            serializer.into_serializer().into_inner()
+        };
+
+        let valid = archive_u32(0xd7ff);
+        assert_eq!(*check_archived_root::<char>(valid.as_ref()).unwrap(), '\u{d7ff}');
+
+        for invalid_value in [0xd800u32, 0xdfff, 0x110000, u32::MAX] {
+            let bytes = archive_u32(invalid_value);
+            match check_archived_root::<char>(bytes.as_ref()) {
+                Err(CheckArchiveError::CheckBytesError(CharCheckError { invalid_value: v })) => {
+                    assert_eq!(v, invalid_value)
+                }
+                _ => panic!("expected an invalid char error"),
+            }
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn check_invalid_btreemap() {
@@ -555,4 +722,357 @@ mod tests {
             )
         ));
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_sorted_map() {
+        use rkyv::{
+            collections::sorted_map::{
+                validation::SortedMapError, ArchivedSortedMap, SortedMapResolver,
+            },
+            ser::ScratchSpace,
+            validation::CheckArchiveError,
+        };
+
+        struct SortedMap(Vec<(u32, u32)>);
+
+        impl Archive for SortedMap {
+            type Archived = ArchivedSortedMap<u32, u32>;
+            type Resolver = SortedMapResolver;
+
+            unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+                ArchivedSortedMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for SortedMap {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedSortedMap::serialize_from_iter(
+                        self.0.iter().map(|(k, v)| (k, v)),
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        serialize_and_check(&SortedMap(Vec::new()));
+        serialize_and_check(&SortedMap(vec![(1, 10)]));
+
+        let value = SortedMap(vec![(3, 30), (1, 10), (2, 20)]);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<SortedMap>(buf.as_ref()).unwrap();
+
+        // Swap the first two keys so they are out of order
+        let archived = check_archived_root::<SortedMap>(buf.as_ref()).unwrap();
+        let key_offset = |index: usize| {
+            archived.get_index(index).unwrap().0 as *const u32 as usize - buf.as_ptr() as usize
+        };
+        let (first, second) = (key_offset(0), key_offset(1));
+        let size = core::mem::size_of::<u32>();
+        for i in 0..size {
+            buf.as_mut_slice().swap(first + i, second + i);
+        }
+
+        let result = check_archived_root::<SortedMap>(buf.as_ref());
+        assert!(matches!(
+            result,
+            Err(CheckArchiveError::CheckBytesError(SortedMapError::UnsortedKeys { index: 1 }))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn access_checked_root() {
+        use rkyv::validation::validators::CheckError;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes, Debug))]
+        struct Test {
+            name: String,
+            flag: bool,
+            value: u32,
+        }
+
+        let value = Test {
+            name: "hello world, this string is out of line".to_string(),
+            flag: true,
+            value: 42,
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        // Valid buffer
+        let archived = access::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(archived.name, value.name);
+        assert!(archived.flag);
+        assert_eq!(archived.value, 42);
+        let flag_offset = &archived.flag as *const bool as usize - buf.as_ptr() as usize;
+
+        // Too-short buffer
+        let required = core::mem::size_of::<ArchivedTest>();
+        let result = access::<Test>(&buf[..required - 1]);
+        match result {
+            Err(CheckError::BufferTooShort { len, required: r }) => {
+                assert_eq!(len, required - 1);
+                assert_eq!(r, required);
+            }
+            _ => panic!("expected a buffer too short error"),
+        }
+
+        // Corrupt field
+        buf.as_mut_slice()[flag_offset] = 2;
+        let result = access::<Test>(buf.as_ref());
+        assert!(matches!(result, Err(CheckError::Invalid(_))));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_located_error() {
+        use rkyv::validation::validators::check_archived_root_located;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes, Debug))]
+        struct Inner {
+            value: u32,
+            flag: bool,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes, Debug))]
+        struct Outer {
+            name: String,
+            inner: Box<Inner>,
+        }
+
+        let value = Outer {
+            name: "a name that is stored out of line".to_string(),
+            inner: Box::new(Inner {
+                value: 42,
+                flag: true,
+            }),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let archived = check_archived_root_located::<Outer>(buf.as_ref()).unwrap();
+        let base = buf.as_ptr() as usize;
+        let inner_pos = &*archived.inner as *const ArchivedInner as usize - base;
+        let flag_pos = &archived.inner.flag as *const bool as usize - base;
+
+        buf.as_mut_slice()[flag_pos] = 2;
+        let error = check_archived_root_located::<Outer>(buf.as_ref()).unwrap_err();
+        assert_eq!(error.pos, Some(inner_pos));
+        let message = error.to_string();
+        let inner_index = message.find("struct member inner").unwrap();
+        let flag_index = message.find("struct member flag").unwrap();
+        assert!(inner_index < flag_index);
+        let suffix = ["(in object at position ", &inner_pos.to_string(), ")"].concat();
+        assert!(message.ends_with(&suffix));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_result_tag() {
+        let ok = check_archived_root::<Result<u8, u8>>(&[0, 5]).unwrap();
+        assert_eq!(ok.ok(), Some(&5));
+        let err = check_archived_root::<Result<u8, u8>>(&[1, 6]).unwrap();
+        assert_eq!(err.err(), Some(&6));
+        check_archived_root::<Result<u8, u8>>(&[2, 5]).unwrap_err();
+        check_archived_root::<Result<u8, u8>>(&[0xff, 5]).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_bound_and_control_flow_tags() {
+        use core::ops::Bound;
+
+        let bound = check_archived_root::<Bound<u8>>(&[0, 5]).unwrap();
+        assert!(bound.is_included());
+        assert_eq!(bound.value(), Some(&5));
+        let bound = check_archived_root::<Bound<u8>>(&[1, 5]).unwrap();
+        assert!(bound.is_excluded());
+        let bound = check_archived_root::<Bound<u8>>(&[2, 0]).unwrap();
+        assert!(bound.is_unbounded());
+        assert_eq!(bound.value(), None);
+        check_archived_root::<Bound<u8>>(&[3, 5]).unwrap_err();
+
+        #[cfg(feature = "control_flow")]
+        {
+            use core::ops::ControlFlow;
+
+            let flow = check_archived_root::<ControlFlow<u8, u8>>(&[0, 5]).unwrap();
+            assert_eq!(flow.continue_value(), Some(&5));
+            let flow = check_archived_root::<ControlFlow<u8, u8>>(&[1, 6]).unwrap();
+            assert_eq!(flow.break_value(), Some(&6));
+            check_archived_root::<ControlFlow<u8, u8>>(&[2, 5]).unwrap_err();
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_niched_option_box() {
+        use core::mem::size_of;
+        use rkyv::{
+            boxed::ArchivedBox, niche::option_box::ArchivedOptionBox, with::Niche, Archived,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            #[with(Niche)]
+            inner: Option<Box<str>>,
+        }
+
+        // The null pointer niche stores `None` without a separate tag
+        assert_eq!(size_of::<ArchivedOptionBox<str>>(), size_of::<ArchivedBox<str>>());
+        assert_eq!(size_of::<ArchivedTest>(), size_of::<ArchivedBox<str>>());
+        assert!(size_of::<ArchivedTest>() < size_of::<Archived<Option<Box<str>>>>());
+
+        let value = Test {
+            inner: Some("hello world".into()),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(archived.inner.as_deref(), Some("hello world"));
+
+        let value = Test { inner: None };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert!(archived.inner.is_none());
+
+        // A non-null pointer that points out of bounds is still rejected
+        let bytes = AlignedBytes(0x1000i32.to_le_bytes());
+        assert!(check_archived_value::<Test>(&bytes.0, 0).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_packed_bools() {
+        use core::mem::size_of;
+        use rkyv::{from_archived, to_archived, with::PackedBools, Archived, FixedUsize};
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            #[with(PackedBools)]
+            flags: Vec<bool>,
+        }
+
+        let value = Test {
+            flags: (0..13).map(|i| i % 3 == 0).collect(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(archived.flags.len(), 13);
+
+        // The length of the bools is the last word of the archive
+        let len_pos = buf.len() - size_of::<Archived<usize>>();
+        let set_len = |buf: &mut [u8], len: usize| unsafe {
+            let word = buf.as_mut_ptr().add(len_pos).cast::<Archived<usize>>();
+            assert!(from_archived!(*word) as usize <= 16);
+            *word = to_archived!(len as FixedUsize);
+        };
+
+        // Two bytes hold up to 16 bools
+        set_len(buf.as_mut_slice(), 16);
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(archived.flags.len(), 16);
+        set_len(buf.as_mut_slice(), 17);
+        assert!(check_archived_root::<Test>(buf.as_ref()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_binary_heap() {
+        #[cfg(not(feature = "std"))]
+        use alloc::collections::BinaryHeap;
+        use rkyv::{collections::binary_heap::BinaryHeapError, validation::CheckArchiveError};
+        #[cfg(feature = "std")]
+        use std::collections::BinaryHeap;
+
+        let value = [5, 1, 8, 3, 9].iter().copied().collect::<BinaryHeap<i32>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<BinaryHeap<i32>>(buf.as_ref()).unwrap();
+        assert_eq!(archived.peek(), Some(&rkyv::to_archived!(9i32)));
+
+        // Binary heaps are archived like their backing vector, so an unordered vector is an
+        // invalid heap
+        let value = vec![1, 9, 5, 3];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let result = check_archived_root::<BinaryHeap<i32>>(buf.as_ref());
+        assert!(matches!(
+            result,
+            Err(CheckArchiveError::CheckBytesError(BinaryHeapError::HeapOrder { index: 1 }))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_lazy_field() {
+        use rkyv::{archived_root, with::Lazy};
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            id: u32,
+            #[archive(lazy)]
+            flag: bool,
+            #[with(Lazy)]
+            payload: String,
+        }
+
+        let value = Test {
+            id: 42,
+            flag: true,
+            payload: "a string long enough to be stored out of line".to_string(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(archived.id, 42);
+        assert!(*archived.flag.get(buf.as_ref()).unwrap());
+        assert_eq!(
+            archived.payload.get(buf.as_ref()).unwrap(),
+            "a string long enough to be stored out of line"
+        );
+
+        // Lazy fields must be validated with the archive that contains them
+        let other = AlignedBytes([0u8; 16]);
+        assert!(archived.flag.get(&other.0).is_err());
+        // Including an archive that ends before the lazy field
+        assert!(archived.flag.get(&buf[..1]).is_err());
+
+        // Corrupt the lazy bool
+        let flag_pos = {
+            let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+            let flag = unsafe { archived.flag.get_unchecked() };
+            flag as *const bool as usize - buf.as_ptr() as usize
+        };
+        buf.as_mut_slice()[flag_pos] = 2;
+
+        // The root still validates because the lazy field isn't validated until it's accessed
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(archived.id, 42);
+        assert!(archived.flag.get(buf.as_ref()).is_err());
+        assert!(archived.payload.get(buf.as_ref()).is_ok());
+    }
 }
@@ -25,4 +25,403 @@ mod tests {
         set.insert("baz".to_string());
         serialize_and_check(&set);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hashmap_corrupted_displacement() {
+        use crate::util::alloc::DefaultSerializer;
+        use core::slice;
+        use rkyv::{
+            check_archived_root, collections::hash_map::validation::HashMapError, from_archived,
+            ser::Serializer, to_archived, validation::CheckArchiveError, Archived,
+        };
+
+        const LEN: usize = 32;
+
+        let map = (0..LEN as u32)
+            .map(|i| (i, i * 2))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&map)
+            .expect("failed to archive value");
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<HashMap<u32, u32>>(buf.as_ref()).unwrap();
+
+        // The displacements of the hash index are the first thing written to the archive, and
+        // u32 keys and values have no dependencies that would be written before them.
+        let displace =
+            unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr().cast::<Archived<u32>>(), LEN) };
+        let (slot, value) = displace
+            .iter()
+            .enumerate()
+            .map(|(i, d)| (i, from_archived!(*d)))
+            .find(|&(_, d)| d & 0x80_00_00_00 == 0)
+            .expect("no direct displacements in hash index");
+        displace[slot] = to_archived!((value + 1) % LEN as u32);
+
+        match check_archived_root::<HashMap<u32, u32>>(buf.as_ref()) {
+            Err(CheckArchiveError::CheckBytesError(HashMapError::InvalidKeyPosition {
+                ..
+            })) => (),
+            result => panic!(
+                "expected invalid key position, got {:?}",
+                result.map(|_| ())
+            ),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hash_map_buckets")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hashmap_zero_buckets() {
+        use crate::util::alloc::DefaultSerializer;
+        use core::{mem::size_of, slice};
+        use rkyv::{
+            check_archived_root,
+            collections::{
+                hash_index::validation::HashIndexError,
+                hash_map::{validation::HashMapError, HashMapBuildOptions},
+                ArchivedHashMap,
+            },
+            from_archived,
+            ser::Serializer,
+            validation::CheckArchiveError,
+            Archived,
+        };
+
+        const LEN: usize = 32;
+
+        let map = (0..LEN as u32)
+            .map(|i| (i, i * 2))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        unsafe {
+            let resolver =
+                ArchivedHashMap::<Archived<u32>, Archived<u32>>::serialize_from_iter_with(
+                    map.iter(),
+                    HashMapBuildOptions { bucket_ratio: 2.0 },
+                    &mut serializer,
+                )
+                .unwrap();
+            serializer
+                .align_for::<Archived<HashMap<u32, u32>>>()
+                .unwrap();
+            serializer.resolve_aligned(&map, resolver).unwrap();
+        }
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<HashMap<u32, u32>>(buf.as_ref()).unwrap();
+
+        // The bucket count is the only value in the root hash map equal to twice its length
+        let root_size = size_of::<ArchivedHashMap<Archived<u32>, Archived<u32>>>();
+        let start = buf.len() - root_size;
+        let words = unsafe {
+            slice::from_raw_parts_mut(
+                buf.as_mut_ptr().add(start).cast::<Archived<usize>>(),
+                root_size / size_of::<Archived<usize>>(),
+            )
+        };
+        let buckets = words
+            .iter_mut()
+            .find(|word| from_archived!(**word) as usize == 2 * LEN)
+            .expect("failed to find bucket count");
+        *buckets = Default::default();
+
+        match check_archived_root::<HashMap<u32, u32>>(buf.as_ref()) {
+            Err(CheckArchiveError::CheckBytesError(HashMapError::HashIndexError(
+                HashIndexError::InvalidBucketCount { len, buckets },
+            ))) => {
+                assert_eq!(len, LEN);
+                assert_eq!(buckets, 0);
+            }
+            result => panic!(
+                "expected invalid bucket count, got {:?}",
+                result.map(|_| ())
+            ),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hashmap_corrupted_len() {
+        use crate::util::alloc::DefaultSerializer;
+        use core::{mem::size_of, slice};
+        use rkyv::{
+            check_archived_root, collections::ArchivedHashMap, from_archived, ser::Serializer,
+            to_archived, Archived,
+        };
+
+        const LEN: usize = 32;
+
+        let map = (0..LEN as u32)
+            .map(|i| (i, i * 2))
+            .collect::<HashMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&map)
+            .expect("failed to archive value");
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<HashMap<u32, u32>>(buf.as_ref()).unwrap();
+
+        // The root hash map is at the end of the archive. Every length it stores is equal to the
+        // number of entries, and none of its relative pointers are.
+        let root_size = size_of::<ArchivedHashMap<Archived<u32>, Archived<u32>>>();
+        let start = buf.len() - root_size;
+        let words = unsafe {
+            slice::from_raw_parts_mut(
+                buf.as_mut_ptr().add(start).cast::<Archived<usize>>(),
+                root_size / size_of::<Archived<usize>>(),
+            )
+        };
+        let mut corrupted = 0;
+        for word in words.iter_mut() {
+            if from_archived!(*word) as usize == LEN {
+                *word = to_archived!((LEN * 1000) as rkyv::FixedUsize);
+                corrupted += 1;
+            }
+        }
+        assert_ne!(corrupted, 0);
+
+        assert!(check_archived_root::<HashMap<u32, u32>>(buf.as_ref()).is_err());
+        assert!(rkyv::from_bytes::<HashMap<u32, u32>>(buf.as_ref()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_net_tags() {
+        use crate::util::alloc::DefaultSerializer;
+        use core::mem::size_of;
+        use rkyv::{
+            check_archived_root,
+            net::{ArchivedIpAddr, ArchivedSocketAddr},
+            ser::Serializer,
+        };
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4};
+
+        let ip = IpAddr::V6(Ipv6Addr::new(31, 41, 59, 26, 53, 58, 97, 93));
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&ip).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<IpAddr>(buf.as_ref()).unwrap();
+
+        let tag = buf.len() - size_of::<ArchivedIpAddr>();
+        buf[tag] = 2;
+        check_archived_root::<IpAddr>(buf.as_ref()).unwrap_err();
+
+        let sock = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(31, 41, 59, 26), 5358));
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&sock).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<SocketAddr>(buf.as_ref()).unwrap();
+
+        let tag = buf.len() - size_of::<ArchivedSocketAddr>();
+        buf[tag] = 0xff;
+        check_archived_root::<SocketAddr>(buf.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(not(feature = "size_16"))]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_bytes_streaming_cursor() {
+        use crate::util::alloc::DefaultSerializer;
+        use bytecheck::CheckBytes;
+        use rkyv::{
+            ser::Serializer,
+            validation::{
+                validators::{
+                    check_bytes_streaming, check_bytes_streaming_with_context, StreamingValidator,
+                    StreamingValidatorError,
+                },
+                CheckArchiveError,
+            },
+            Archive, Serialize,
+        };
+        use std::io::Cursor;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            name: String,
+            values: Vec<u32>,
+            names: Vec<Box<str>>,
+        }
+
+        let value = Test {
+            name: "hello world".to_string(),
+            values: (0..100_000).collect(),
+            names: (0..100).map(|i| i.to_string().into_boxed_str()).collect(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let archive = serializer.into_serializer().into_inner();
+
+        // Bytes that aren't reachable from the root don't have to be read
+        const UNREACHABLE: usize = 16 * 4096;
+        let mut bytes = vec![0xcc; UNREACHABLE];
+        bytes.extend_from_slice(archive.as_ref());
+        let mut reader = Cursor::new(bytes);
+
+        check_bytes_streaming::<Test, _>(&mut reader).unwrap();
+
+        {
+            let mut validator = StreamingValidator::new(&mut reader, 4096).unwrap();
+            assert_eq!(validator.len(), UNREACHABLE + archive.len());
+            check_bytes_streaming_with_context::<Test, _>(&mut validator).unwrap();
+            assert!(validator.bytes_read() >= archive.len() as u64);
+            assert!(validator.bytes_read() <= archive.len() as u64 + 2 * 4096);
+        }
+
+        // Invalid bytes are still caught
+        let mut bytes = reader.into_inner();
+        let name = bytes
+            .windows(11)
+            .position(|window| window == b"hello world")
+            .unwrap();
+        bytes[name] = 0xff;
+        match check_bytes_streaming::<Test, _>(&mut Cursor::new(bytes)) {
+            Err(CheckArchiveError::CheckBytesError(_)) => (),
+            result => panic!("expected a check bytes error, got {:?}", result),
+        }
+
+        // Readers that are too short to hold the root fail without reading out of bounds
+        match check_bytes_streaming::<Test, _>(&mut Cursor::new(vec![0u8; 2])) {
+            Err(CheckArchiveError::ContextError(StreamingValidatorError::ArchiveError(_))) => (),
+            result => panic!("expected an archive error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "relocate")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn relocate_moved_buffer() {
+        use bytecheck::CheckBytes;
+        use core::{convert::TryFrom, ptr, slice};
+        use rkyv::{
+            check_archived_root, from_archived,
+            relocate::{relocate_in_place, unrelocate_in_place, RelocateError},
+            ser::{
+                serializers::{AllocSerializer, RelocationSerializer},
+                Serializer,
+            },
+            AlignedVec, Archive, Archived, FixedUsize, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            name: Box<str>,
+            values: Vec<u32>,
+            boxed: Box<u64>,
+        }
+
+        let value = Test {
+            name: "hello world".into(),
+            values: vec![1, 2, 3, 4],
+            boxed: Box::new(42),
+        };
+        let mut serializer = RelocationSerializer::new(AllocSerializer::<256>::default());
+        serializer.serialize_value(&value).unwrap();
+        let (serializer, table) = serializer.into_parts();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(table.len(), 3);
+
+        let mut moved = AlignedVec::new();
+        moved.extend_from_slice(buf.as_ref());
+        assert_ne!(moved.as_ptr(), buf.as_ptr());
+
+        // Relocate to the address of the moved buffer
+        let base = moved.as_ptr() as usize;
+        if FixedUsize::try_from(base + moved.len()).is_err() {
+            // The addresses don't fit in the relative pointers without the `size_64` feature
+            match relocate_in_place(moved.as_mut_slice(), &table, base) {
+                Err(RelocateError::ExceedsStorageRange { pos }) => {
+                    assert_eq!(pos, table.as_slice()[0].pos)
+                }
+                result => panic!("expected an exceeds storage range error, got {:?}", result),
+            }
+            assert_eq!(moved.as_slice(), buf.as_slice());
+            return;
+        }
+        relocate_in_place(moved.as_mut_slice(), &table, base).unwrap();
+
+        // Values can be read by following the absolute addresses
+        let address = |pos: usize| {
+            let address =
+                unsafe { ptr::read_unaligned(moved[pos..].as_ptr().cast::<Archived<usize>>()) };
+            from_archived!(address) as usize
+        };
+        let relocations = table.as_slice();
+        let name = unsafe { slice::from_raw_parts(address(relocations[0].pos) as *const u8, 11) };
+        assert_eq!(name, b"hello world");
+        let values = unsafe {
+            slice::from_raw_parts(address(relocations[1].pos) as *const Archived<u32>, 4)
+        };
+        assert_eq!(values, &[1, 2, 3, 4]);
+        let boxed = unsafe { &*(address(relocations[2].pos) as *const Archived<u64>) };
+        assert_eq!(*boxed, 42);
+
+        // Relocating twice is detected and leaves the buffer unchanged
+        let relocated = moved.clone();
+        match relocate_in_place(moved.as_mut_slice(), &table, base) {
+            Err(RelocateError::Mismatch { pos }) => assert_eq!(pos, relocations[0].pos),
+            result => panic!("expected a mismatch error, got {:?}", result),
+        }
+        assert_eq!(moved.as_slice(), relocated.as_slice());
+
+        unrelocate_in_place(moved.as_mut_slice(), &table, base).unwrap();
+        assert_eq!(moved.as_slice(), buf.as_slice());
+        let archived = check_archived_root::<Test>(moved.as_ref()).unwrap();
+        assert_eq!(archived.name.as_ref(), "hello world");
+        assert_eq!(archived.values.as_slice(), &[1, 2, 3, 4]);
+        assert_eq!(*archived.boxed, 42);
+    }
+
+    #[test]
+    #[cfg(feature = "relocate")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn relocate_default_offsets_only() {
+        use core::{marker::PhantomData, mem::size_of};
+        use rkyv::{
+            rel_ptr::{Offset, RawRelPtr},
+            ser::{
+                serializers::{AllocSerializer, RelocationSerializer},
+                Serializer,
+            },
+            Archive, Archived, Fallible, Serialize,
+        };
+
+        // Archives a relative pointer to the position just past itself
+        struct Forward<O>(PhantomData<O>);
+
+        impl<O: Offset> Archive for Forward<O> {
+            type Archived = RawRelPtr<O>;
+            type Resolver = ();
+
+            unsafe fn resolve(&self, pos: usize, _: (), out: *mut Self::Archived) {
+                RawRelPtr::emplace(pos, pos + size_of::<O>(), out);
+            }
+        }
+
+        impl<O: Offset, S: Fallible + ?Sized> Serialize<S> for Forward<O> {
+            fn serialize(&self, _: &mut S) -> Result<(), S::Error> {
+                Ok(())
+            }
+        }
+
+        fn record<O: Offset>() -> usize {
+            let mut serializer = RelocationSerializer::new(AllocSerializer::<256>::default());
+            serializer
+                .serialize_value(&Forward::<O>(PhantomData))
+                .unwrap();
+            serializer.into_parts().1.len()
+        }
+
+        assert_eq!(record::<Archived<isize>>(), 1);
+        // Offsets of other types aren't recorded, even if they're the same size
+        assert_eq!(record::<Archived<usize>>(), 0);
+        assert_eq!(record::<i8>(), 0);
+    }
 }